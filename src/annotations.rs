@@ -1,5 +1,5 @@
 use colored::Colorize;
-use nom::{bytes::complete::tag, combinator::{map, opt}, multi::separated_list0, sequence::{delimited, preceded, terminated, tuple}};
+use nom::{branch::alt, bytes::complete::tag, combinator::{map, opt}, multi::separated_list0, sequence::{delimited, preceded, terminated, tuple}};
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 
@@ -23,7 +23,7 @@ pub fn parse_annotation<'a>(input: Span<'a>) -> PResult<'a, Annotation> {
                         tuple((empty0, tag(","), empty0)), 
                         tuple((
                             opt(terminated(identifier_parser, tuple((empty0, tag(":"), empty0)))),
-                            string_parser
+                            alt((string_parser, identifier_parser))
                         ))
                     ),
                     tuple((empty0, opt(tuple((tag(","), empty0))), tag(")")))
@@ -95,12 +95,14 @@ mod tests {
         let simple_str = "@doc(\"this is some doc\")";
         let named_str = "@arg(arg_name: \"doc1\", arg_name_2: \"doc2\")";
         let mixed_str = "@test(named_arg_1: \"doc1\", \"pos_arg_1\", named_arg_2: \"doc2\", \"pos_arg_2\")";
+        let ident_str = "@derive(Stringable, named: value)";
 
         let empty = parse_annotation(empty_str.into()).unwrap().1;
         let empty_noargs = parse_annotation(empty_noargs_str.into()).unwrap().1;
         let simple = parse_annotation(simple_str.into()).unwrap().1;
         let named = parse_annotation(named_str.into()).unwrap().1;
         let mixed = parse_annotation(mixed_str.into()).unwrap().1;
+        let ident = parse_annotation(ident_str.into()).unwrap().1;
 
         assert_eq!(empty, Annotation { name: "example".into(), args: FxHashMap::default() });
 
@@ -121,5 +123,10 @@ mod tests {
             ("0".into(), "pos_arg_1".into()),
             ("1".into(), "pos_arg_2".into())
         ].iter().cloned().collect() });
+
+        assert_eq!(ident, Annotation { name: "derive".into(), args: [
+            ("0".into(), "Stringable".into()),
+            ("named".into(), "value".into())
+        ].iter().cloned().collect() });
     }
 }
\ No newline at end of file
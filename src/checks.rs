@@ -1,18 +1,43 @@
 use std::collections::{HashSet, HashMap};
 
 use colored::Colorize;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::annotations::Annotation;
 use crate::compilation::NessaError;
 use crate::context::NessaContext;
+use crate::interfaces::{InterfaceConstraint, HASH, PRINTABLE, STRINGABLE};
 use crate::formats::{check_class_name, check_fn_name, check_interface_name, check_template_name};
 use crate::located_nessa_warning;
-use crate::parser::{NessaExpr, Location};
-use crate::operations::Operator;
-use crate::types::{Type, BOOL};
+use crate::parser::{NessaExpr, Location, MatchPattern};
+use crate::operations::{Operator, AND_BINOP_ID, DEREF_UNOP_ID, EQ_BINOP_ID, LT_BINOP_ID, OR_BINOP_ID};
+use crate::types::{Type, ARR_ID, BOOL, BOOL_ID, ERROR, FLOAT_ID, INT_ID, STR_ID};
 use crate::patterns::Pattern;
 
+// Registers of the variables that may have been moved, along with their names and the location of the move
+type MovedVariables = FxHashMap<usize, (String, Location)>;
+
+// State of the move analysis that is not tied to a single path
+#[derive(Default)]
+struct MoveCheckState {
+    loops: Vec<(MovedVariables, MovedVariables)>, // Moved variables on each continue and break of the innermost loop
+    blocks: Vec<Option<MovedVariables>>,          // Moved variables on each return of the innermost do block
+    references: Vec<(usize, Location)>            // References to variables that are still in use in the current line
+}
+
+fn merge_moved(a: &mut MovedVariables, b: &MovedVariables) {
+    for (k, v) in b {
+        a.entry(*k).or_insert_with(|| v.clone());
+    }
+}
+
+fn moved_variable_error(name: &String, moved_at: &Location, l: &Location) -> NessaError {
+    NessaError::compiler_error(
+        format!("Variable {} may have been moved in line {}, column {} before this use", name.green(), moved_at.line, moved_at.column), 
+        l, vec!(format!("Use a copy (*{}) if you need the value after moving it", name))
+    )
+}
+
 /*
                                                   ╒══════════════════╕
     ============================================= │  IMPLEMENTATION  │ =============================================
@@ -28,7 +53,7 @@ impl NessaContext {
             NessaExpr::NaryOperationDefinition(l, _, _, _, _, _, _, body)  => NessaContext::ensured_return_check_body(body, l, "Operation"),
 
             NessaExpr::CompiledLambda(l, _, _, _, _, body) |
-            NessaExpr::FunctionDefinition(l, _, _, _, _, _, body) => NessaContext::ensured_return_check_body(body, l, "Function"),
+            NessaExpr::FunctionDefinition(l, _, _, _, _, _, _, _, body) => NessaContext::ensured_return_check_body(body, l, "Function"),
 
             NessaExpr::DoBlock(l, body, _) => NessaContext::ensured_return_check_body(body, l, "Do block"),
 
@@ -59,6 +84,9 @@ impl NessaContext {
                     }
                 }
 
+                NessaExpr::CompiledTry(_, b, _, _, _, h) if NessaContext::ensured_return_check_body(b, l, instance).is_ok() && 
+                                                             NessaContext::ensured_return_check_body(h, l, instance).is_ok() => return Ok(()),
+
                 _ => {}
             }
         }
@@ -71,14 +99,7 @@ impl NessaContext {
             (NessaExpr::Break(..), _) |
             (NessaExpr::Continue(..), _) |
             (NessaExpr::Literal(..), _) |
-            (NessaExpr::Tuple(..), _) |
             (NessaExpr::Variable(..), _) |
-            (NessaExpr::UnaryOperation(..), _) |
-            (NessaExpr::BinaryOperation(..), _) |
-            (NessaExpr::NaryOperation(..), _) |
-            (NessaExpr::FunctionCall(..), _) |
-            (NessaExpr::AttributeAccess(..), _) |
-            (NessaExpr::AttributeAssignment(..), _) |
             (NessaExpr::PrefixOperatorDefinition(..), _) |
             (NessaExpr::PostfixOperatorDefinition(..), _) |
             (NessaExpr::BinaryOperatorDefinition(..), _) |
@@ -87,8 +108,41 @@ impl NessaContext {
             (NessaExpr::InterfaceImplementation(..), _) |
             (NessaExpr::ClassDefinition(..), _) => Ok(()),
 
+            (NessaExpr::VariadicArgs(..), _) |
+            (NessaExpr::Tuple(..), _) |
+            (NessaExpr::UnaryOperation(..), _) |
+            (NessaExpr::BinaryOperation(..), _) |
+            (NessaExpr::NaryOperation(..), _) |
+            (NessaExpr::FunctionCall(..), _) |
+            (NessaExpr::AttributeAccess(..), _) |
+            (NessaExpr::AttributeAssignment(..), _) => {
+                for p in expr.propagations() {
+                    self.return_check(p, ret_type)?;
+                }
+
+                Ok(())
+            },
+
             (NessaExpr::CompiledVariableDefinition(_, _, _, _, e), _) |
-            (NessaExpr::CompiledVariableAssignment(_, _, _, _, e), _) => self.return_check(e, &None),
+            (NessaExpr::CompiledVariableAssignment(_, _, _, _, e), _) => self.return_check(e, ret_type),
+
+            // Uses outside of functions are rejected by main_propagation_check
+            (NessaExpr::CompiledPropagate(_, _, e), None) => self.return_check(e, ret_type),
+
+            (NessaExpr::CompiledPropagate(l, _, e), Some(expected_t)) => {
+                self.return_check(e, ret_type)?;
+                let (t, _) = self.infer_type(e)?.propagation_split().unwrap();
+
+                if t.bindable_to(expected_t, self) {
+                    Ok(())
+
+                } else {
+                    Err(NessaError::compiler_error(
+                        format!("Propagated value of type {} is not bindable to expected return value of type {}", t.get_name(self), expected_t.get_name(self)), 
+                        l, vec!()
+                    ))
+                }
+            },
 
             (NessaExpr::Return(l, _), None) => {
                 Err(NessaError::compiler_error(
@@ -112,7 +166,7 @@ impl NessaContext {
                 }
             },
 
-            (NessaExpr::FunctionDefinition(_, _, _, t, _, ret, body), None) |
+            (NessaExpr::FunctionDefinition(_, _, _, t, _, _, _, ret, body), None) |
             (NessaExpr::PrefixOperationDefinition(_, _, _, t, _, _, ret, body), None) |
             (NessaExpr::PostfixOperationDefinition(_, _, _, t, _, _, ret, body), None) |
             (NessaExpr::BinaryOperationDefinition(_, _, _, t, _, _, ret, body), None) |
@@ -161,6 +215,28 @@ impl NessaContext {
                 Ok(())
             },
 
+            (NessaExpr::CompiledTry(_, b, _, _, _, h), ret) => {
+                for line in b.iter().chain(h) {
+                    self.return_check(line, ret)?;
+                }
+
+                Ok(())
+            },
+
+            (NessaExpr::CompiledMatch(_, _, e, arms), ret) => {
+                self.return_check(e, ret)?;
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.return_check(g_inner, ret)?;
+                    }
+
+                    self.return_check(b, ret)?;
+                }
+
+                Ok(())
+            },
+
             (NessaExpr::If(_, ih, ib, ei, eb), ret) => {
                 self.return_check(ih, ret)?;
 
@@ -191,6 +267,32 @@ impl NessaContext {
         }
     }
 
+    // The ? operator returns from the enclosing function, so the main program cannot use it
+    pub fn main_propagation_check(expr: &NessaExpr) -> Result<(), NessaError> {
+        if let Some(p) = expr.propagations().first() {
+            return Err(NessaError::compiler_error(
+                "The ? operator is only allowed inside function, operation and lambda bodies".into(), 
+                p.get_location(), vec!()
+            ));
+        }
+
+        match expr {
+            NessaExpr::While(_, _, b) |
+            NessaExpr::CompiledFor(_, _, _, _, _, b) => b.iter().try_for_each(NessaContext::main_propagation_check),
+
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => b.iter().chain(h).try_for_each(NessaContext::main_propagation_check),
+
+            NessaExpr::If(_, _, ib, ei, eb) => {
+                ib.iter()
+                  .chain(ei.iter().flat_map(|(_, b)| b))
+                  .chain(eb.iter().flatten())
+                  .try_for_each(NessaContext::main_propagation_check)
+            },
+
+            _ => Ok(())
+        }
+    }
+
     pub fn ambiguity_check(&self, expr: &NessaExpr) -> Result<(), NessaError> {
         return match expr {
             NessaExpr::Break(..) |
@@ -207,6 +309,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, body, _) |
             NessaExpr::CompiledLambda(_, _, _, _, _, body) |
+            NessaExpr::VariadicArgs(_, _, body) |
             NessaExpr::Tuple(_, body) => {
                 for line in body {
                     self.ambiguity_check(line)?;
@@ -403,6 +506,7 @@ impl NessaContext {
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::Return(_, e) => {
                 self.ambiguity_check(e)?;
                 self.infer_type(e)?;
@@ -414,7 +518,7 @@ impl NessaContext {
             NessaExpr::PostfixOperationDefinition(_, _, _, t, _, _, _, b) |
             NessaExpr::BinaryOperationDefinition(_, _, _, t, _, _, _, b) |
             NessaExpr::NaryOperationDefinition(_, _, _, t, _, _, _, b) |
-            NessaExpr::FunctionDefinition(_, _, _, t, _, _, b) => {
+            NessaExpr::FunctionDefinition(_, _, _, t, _, _, _, _, b) => {
                 if t.is_empty() {
                     for line in b {
                         self.ambiguity_check(line)?;
@@ -432,6 +536,28 @@ impl NessaContext {
                 Ok(())
             }
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for line in b.iter().chain(h) {
+                    self.ambiguity_check(line)?;
+                }
+
+                Ok(())
+            }
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.ambiguity_check(e)?;
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.ambiguity_check(g_inner)?;
+                    }
+
+                    self.ambiguity_check(b)?;
+                }
+
+                Ok(())
+            }
+
             NessaExpr::Macro(..) => { Ok(()) },
 
             _ => unimplemented!("{:?}", expr)
@@ -466,6 +592,7 @@ impl NessaContext {
                 NessaContext::break_continue_check(e, allowed)
             }
 
+            NessaExpr::VariadicArgs(_, _, args) |
             NessaExpr::Tuple(_, args) => args.iter().try_for_each(|i| NessaContext::break_continue_check(i, allowed)),
             
             NessaExpr::If(_, i, ib, ei, eb) => {
@@ -500,6 +627,28 @@ impl NessaContext {
                 Ok(())
             }
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for i in b.iter().chain(h) {
+                    NessaContext::break_continue_check(i, allowed)?;
+                }
+
+                Ok(())
+            }
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                NessaContext::break_continue_check(e, allowed)?;
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        NessaContext::break_continue_check(g_inner, allowed)?;
+                    }
+
+                    NessaContext::break_continue_check(b, allowed)?;
+                }
+
+                Ok(())
+            }
+
             NessaExpr::CompiledFor(_, _, _, _, c, b) |
             NessaExpr::While(_, c, b) => {
                 NessaContext::break_continue_check(c, true)?;
@@ -544,6 +693,7 @@ impl NessaContext {
             },
 
             NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::Return(_, e) => NessaContext::break_continue_check(e, allowed),
 
             NessaExpr::CompiledLambda(_, _, _, _, _, b) => {
@@ -595,7 +745,7 @@ impl NessaContext {
             },
 
 
-            NessaExpr::FunctionDefinition(_, _, _, tm, _, _, b) => {
+            NessaExpr::FunctionDefinition(_, _, _, tm, _, _, _, _, b) => {
                 if tm.is_empty() {
                     for i in b {
                         NessaContext::break_continue_check(i, false)?;    
@@ -639,6 +789,7 @@ impl NessaContext {
                 self.invalid_type_check(b)
             }
 
+            NessaExpr::VariadicArgs(_, _, args) |
             NessaExpr::Tuple(_, args) => args.iter().try_for_each(|i| self.invalid_type_check(i)),
             
             NessaExpr::If(_, i, ib, ei, eb) => {
@@ -680,6 +831,42 @@ impl NessaContext {
                 Ok(())
             }
 
+            NessaExpr::CompiledTry(l, b, _, _, t, h) => {
+                if t.has_self() {
+                    return Err(NessaError::compiler_error(
+                        format!("{} type found outside an interface", Type::SelfType.get_name(self)),
+                        l, vec!()
+                    ));
+                }
+
+                for i in b.iter().chain(h) {
+                    self.invalid_type_check(i)?;
+                }
+
+                Ok(())
+            }
+
+            NessaExpr::CompiledMatch(l, _, e, arms) => {
+                self.invalid_type_check(e)?;
+
+                for (p, g, b) in arms {
+                    if p.types().iter().any(|t| t.has_self()) {
+                        return Err(NessaError::compiler_error(
+                            format!("{} type found outside an interface", Type::SelfType.get_name(self)),
+                            l, vec!()
+                        ));
+                    }
+
+                    if let Some(g_inner) = g {
+                        self.invalid_type_check(g_inner)?;
+                    }
+
+                    self.invalid_type_check(b)?;
+                }
+
+                Ok(())
+            }
+
             NessaExpr::CompiledFor(_, _, _, _, c, b) |
             NessaExpr::While(_, c, b) => {
                 self.invalid_type_check(c)?;
@@ -759,6 +946,7 @@ impl NessaContext {
             },
 
             NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::Return(_, e) => self.invalid_type_check(e),
 
             NessaExpr::CompiledLambda(l, _, c, args, ret, b) => {
@@ -859,7 +1047,7 @@ impl NessaContext {
             },
 
 
-            NessaExpr::FunctionDefinition(l, _, _, tm, args, ret, b) => {
+            NessaExpr::FunctionDefinition(l, _, _, tm, args, _, _, ret, b) => {
                 if tm.is_empty() {
                     for i in b {
                         self.invalid_type_check(i)?;    
@@ -878,7 +1066,7 @@ impl NessaContext {
                 Ok(())
             },
 
-            NessaExpr::ClassDefinition(l, _, _, _, args, alias, _) => {
+            NessaExpr::ClassDefinition(l, _, _, _, args, alias, _, _) => {
                 if let Some(t) = alias {
                     if t.has_self() {
                         return Err(NessaError::compiler_error(
@@ -918,6 +1106,90 @@ impl NessaContext {
         };
     }
 
+    // Checks if every value of type t matches the pattern
+    pub fn match_pattern_covers(&self, pattern: &MatchPattern, t: &Type) -> bool {
+        let covers_all = |p: &MatchPattern, t: &Type| self.match_alternatives(t.deref_type()).iter().all(|i| self.match_pattern_covers(p, i));
+
+        match pattern {
+            MatchPattern::Wildcard => true,
+            MatchPattern::Literal(_) => false,
+            MatchPattern::Type(pt) => t.bindable_to(pt, self),
+
+            MatchPattern::Class(pt, p) => {
+                t.bindable_to(pt, self) && match self.match_class_attributes(t).or_else(|| self.match_class_attributes(pt)) {
+                    Some(atts) => p.iter().zip(&atts).all(|(i, att)| covers_all(i, att)),
+                    None => false
+                }
+            },
+
+            MatchPattern::Tuple(p) => match t {
+                Type::And(v) if v.len() == p.len() => p.iter().zip(v).all(|(i, elem)| covers_all(i, elem)),
+                _ => false
+            },
+
+            MatchPattern::Binding(_, p) |
+            MatchPattern::CompiledBinding(_, _, _, p) => self.match_pattern_covers(p, t)
+        }
+    }
+
+    // Interpolated expressions must implement Stringable
+    pub fn stringable_check(&self, t: &Type, l: &Location) -> Result<(), NessaError> {
+        if !t.has_templates() && !self.implements_interface(t, &STRINGABLE, &mut HashMap::new(), &mut HashMap::new()) {
+            return Err(NessaError::compiler_error(format!(
+                "Unable to convert value of type {} to {} because it does not implement {}",
+                t.get_name(self),
+                "String".cyan(),
+                "Stringable".green()
+            ), l, vec!()));
+        }
+
+        Ok(())
+    }
+
+    // Only types with a parser (primitives and classes with literal syntaxes) can be parsed at runtime
+    pub fn parseable_check(&self, t: &Type, l: &Location) -> Result<(), NessaError> {
+        match t {
+            Type::Basic(id) if self.type_templates[*id].parser.is_some() => Ok(()),
+            _ if t.has_templates() => Ok(()),
+
+            _ => Err(NessaError::compiler_error(format!(
+                "Unable to parse values of type {} because it does not have a parser or a literal syntax",
+                t.get_name(self)
+            ), l, vec!()))
+        }
+    }
+
+    fn check_constraint_well_formed(&self, c: &InterfaceConstraint, l: &Location) -> Result<(), NessaError> {
+        let interface = &self.interfaces[c.id];
+        let interface_args = interface.params.len();
+
+        if c.args.len() != interface_args {
+            return Err(
+                NessaError::compiler_error(
+                    format!(
+                        "Interface {}{} expected {} arguments (got {})", 
+                        interface.name.cyan(), 
+                        if interface_args == 0 { 
+                            "".into() 
+                        } else { 
+                            format!("<{}>", interface.params.iter().map(|i| i.green().to_string()).collect::<Vec<_>>().join(", ")) 
+                        },
+                        interface_args, 
+                        c.args.len()
+                    ), 
+                    l, 
+                    vec!()
+                )
+            );
+        }
+
+        for arg in &c.args {
+            self.check_type_well_formed(arg, l)?;
+        }
+
+        Ok(())
+    }
+
     pub fn check_type_well_formed(&self, t: &Type, l: &Location) -> Result<(), NessaError> {
         return match t {
             Type::Empty |
@@ -943,38 +1215,9 @@ impl NessaContext {
                 ))
             }
 
-            Type::TemplateParam(_, cs) => {
-                for c in cs {
-                    let interface = &self.interfaces[c.id];
-                    let interface_args = interface.params.len();
-
-                    if c.args.len() != interface_args {
-                        return Err(
-                            NessaError::compiler_error(
-                                format!(
-                                    "Interface {}{} expected {} arguments (got {})", 
-                                    interface.name.cyan(), 
-                                    if interface_args == 0 { 
-                                        "".into() 
-                                    } else { 
-                                        format!("<{}>", interface.params.iter().map(|i| i.green().to_string()).collect::<Vec<_>>().join(", ")) 
-                                    },
-                                    interface_args, 
-                                    c.args.len()
-                                ), 
-                                l, 
-                                vec!()
-                            )
-                        );
-                    }
-
-                    for arg in &c.args {
-                        self.check_type_well_formed(arg, l)?;
-                    }
-                }
+            Type::TemplateParam(_, cs) => cs.iter().try_for_each(|c| self.check_constraint_well_formed(c, l)),
 
-                Ok(())
-            },
+            Type::Dyn(c) => self.check_constraint_well_formed(c, l),
 
             Type::Template(id, args) => {
                 let t = &self.type_templates[*id];
@@ -1018,6 +1261,7 @@ impl NessaContext {
             NessaExpr::NaryOperatorDefinition(..) => Ok(()),
 
             NessaExpr::DoBlock(_, args, _) |
+            NessaExpr::VariadicArgs(_, _, args) |
             NessaExpr::Tuple(_, args) => {
                 for arg in args {
                     self.type_check(arg)?;
@@ -1037,12 +1281,21 @@ impl NessaContext {
                     Ok(())
 
                 } else{
+                    // Generic implementations are not available to dyn values
+                    let suggestions = match t {
+                        Type::Dyn(c) if !it.is_ref() && self.implements_interface(&it, c, &mut HashMap::new(), &mut HashMap::new()) => {
+                            vec!(format!("Values of type {} can only use non generic implementations of {}", t.get_name(self), c.get_name(self)))
+                        }
+
+                        _ => vec!()
+                    };
+
                     Err(NessaError::compiler_error(format!(
                         "Unable to bind value of type {} to variable {}, which is of type {}",
                         it.get_name(self),
                         n.cyan(),
                         t.get_name(self)
-                    ), l, vec!()))
+                    ), l, suggestions))
                 }
             },
 
@@ -1099,7 +1352,25 @@ impl NessaContext {
                     arg_types.push(self.infer_type(arg)?);
                 }
 
-                let (ov_id, _, _, _) = self.get_first_function_overload(*id, arg_types.clone(), Some(templates.clone()), false, l)?;
+                if self.functions[*id].name == "parse" && templates.len() == 1 {
+                    self.parseable_check(&templates[0], l)?;
+                }
+
+                let (ov_id, _, _, _) = match self.get_first_function_overload(*id, arg_types.clone(), Some(templates.clone()), false, l) {
+                    Ok(res) => res,
+
+                    // Interface functions called on dyn values are dispatched at runtime
+                    Err(_) if templates.is_empty() && self.get_dynamic_function_overload(*id, &arg_types).is_some() => {
+                        for (_, args, ov_id, t) in self.get_dispatch_table(*id, &arg_types, l)? {
+                            self.cache.usages.functions.add_new(*id, args.clone(), t.clone());
+                            self.cache.overloads.functions.insert((*id, args, t), ov_id);
+                        }
+
+                        return Ok(());
+                    },
+
+                    Err(err) => return Err(err)
+                };
 
                 //Invalid number of template arguments
                 if self.functions[*id].overloads[ov_id].templates != templates.len() {
@@ -1287,6 +1558,72 @@ impl NessaContext {
                 Ok(())
             }
 
+            NessaExpr::CompiledTry(l, b, _, n, t, h) => {
+                for line in b {
+                    self.type_check(line)?;
+                }
+
+                if *t != ERROR {
+                    let t_name = t.get_name(self);
+                    let error_name = ERROR.get_name(self);
+
+                    // The standard type can't be named if a class shadows it
+                    let suggestions = if t_name == error_name {
+                        vec!(format!("The standard {} type is shadowed by a class with the same name", error_name))
+
+                    } else {
+                        vec!()
+                    };
+
+                    return Err(NessaError::compiler_error(
+                        format!("Catch variable {} has type {} (expected {})", n.green(), t_name, error_name), 
+                        l, suggestions
+                    ));
+                }
+
+                for line in h {
+                    self.type_check(line)?;
+                }
+
+                Ok(())
+            }
+
+            NessaExpr::CompiledMatch(l, _, e, arms) => {
+                self.type_check(e)?;
+                let value_type = self.infer_type(e)?;
+
+                for (p, g, b) in arms {
+                    for t in p.types() {
+                        self.check_type_well_formed(t, l)?;
+                    }
+
+                    if let Some(g_inner) = g {
+                        self.type_check(g_inner)?;
+                        let t = self.infer_type(g_inner)?;
+
+                        if *t.deref_type() != BOOL {
+                            return Err(NessaError::compiler_error(format!("Match guard inferred to be of type {} (expected Bool, &Bool or @Bool)", t.get_name(self)), l, vec!()));
+                        }
+                    }
+
+                    self.type_check(b)?;
+                }
+
+                // Every possible value has to be covered by an arm without guard
+                let missing = self.match_alternatives(value_type.deref_type()).into_iter()
+                                  .filter(|alt| !arms.iter().any(|(p, g, _)| g.is_none() && self.match_pattern_covers(p, alt)))
+                                  .collect::<Vec<_>>();
+
+                if !missing.is_empty() {
+                    return Err(NessaError::compiler_error(
+                        format!("Match expression is not exhaustive (missing cases for {})", missing.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", ")), 
+                        l, vec!()
+                    ));
+                }
+
+                Ok(())
+            }
+
             NessaExpr::While(l, cond, body) => {
                 self.type_check(cond)?;
                 let t = self.infer_type(cond)?;
@@ -1303,6 +1640,7 @@ impl NessaContext {
             },
 
             NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::Return(_, e) => {
                 self.type_check(e)?;
                 self.infer_type(e)?;
@@ -1409,7 +1747,7 @@ impl NessaContext {
                 Ok(())
             },
 
-            NessaExpr::FunctionDefinition(l, _, _, t, args, r, b) => {
+            NessaExpr::FunctionDefinition(l, _, _, t, args, _, _, r, b) => {
                 self.check_type_well_formed(r, l)?;
 
                 for (_, t) in args {
@@ -1439,10 +1777,17 @@ impl NessaContext {
                 Ok(())
             }
 
-            NessaExpr::InterfaceDefinition(l, _, _, t, fns, uns, bin, nary) => {
+            NessaExpr::InterfaceDefinition(l, _, _, t, p, fns, uns, bin, nary) => {
                 let mut templates = HashSet::new();
 
-                for (_, _, f_t, args, r) in fns {
+                for c in p {
+                    for i in &c.args {
+                        self.check_type_well_formed(i, l)?;
+                        i.template_dependencies(&mut templates);
+                    }
+                }
+
+                for (_, _, f_t, args, r, _) in fns {
                     let mut templates_f = HashSet::new();
 
                     self.check_type_well_formed(r, l)?;
@@ -1550,7 +1895,7 @@ impl NessaContext {
                 Ok(())
             }
 
-            NessaExpr::ClassDefinition(l, _, _, t, attrs, alias, _) => {
+            NessaExpr::ClassDefinition(l, _, _, t, attrs, alias, _, _) => {
                 let mut templates = HashSet::new();
 
                 if let Some(a) = alias {
@@ -1604,7 +1949,7 @@ impl NessaContext {
 
     pub fn class_check(&self, expr: &NessaExpr) -> Result<(), NessaError> {
         match expr {
-            NessaExpr::ClassDefinition(l, _, n, _, attributes, _, _) => {
+            NessaExpr::ClassDefinition(l, _, n, _, attributes, _, _, _) => {
                 for (att, _) in attributes {
                     if attributes.iter().filter(|(i, _)| i == att).count() > 1 {
                         return Err(NessaError::compiler_error(format!("Repeated attribute \"{}\" in class {}", att, n), l, vec!()));
@@ -1671,8 +2016,20 @@ impl NessaContext {
                         offset_ts.iter_mut().for_each(|i| i.offset_templates(max_tms));
 
                         let t_subs = (0..offset_ts.len()).zip(offset_ts.clone()).collect::<HashMap<_, _>>();
+
+                        for p in self.interface_parents(&InterfaceConstraint::new(int_id, ts.clone())) {
+                            if !self.implements_interface(t, &p, &mut HashMap::new(), &mut HashMap::new()) {
+                                return Err(NessaError::compiler_error(
+                                    format!(
+                                        "Unable to implement interface {} for {} because it does not implement {}", 
+                                        n.green(), t.get_name(self), p.get_name(self)
+                                    ), 
+                                    l, vec!()
+                                ));
+                            }
+                        }
                         
-                        for (_, f_n, _, args, ret) in fns {
+                        for (_, f_n, _, args, ret, _) in fns {
                             match self.get_function_id(f_n.clone()) {
                                 Ok(fn_id) => {
                                     let ret_sub = ret.sub_self(&offset_t).sub_templates(&t_subs);
@@ -2003,6 +2360,7 @@ impl NessaContext {
             }
 
             NessaExpr::DoBlock(_, e, _) |
+            NessaExpr::VariadicArgs(_, _, e) |
             NessaExpr::Tuple(_, e) => {
                 for i in e {
                     self.no_template_check(i)?;
@@ -2078,7 +2436,36 @@ impl NessaContext {
                 Ok(())
             }
 
-            NessaExpr::Return(_, e) => self.no_template_check(e),
+            NessaExpr::CompiledTry(l, b, _, _, t, h) => {
+                self.no_template_check_type(t, l)?;
+
+                for i in b.iter().chain(h) {
+                    self.no_template_check(i)?;
+                }
+
+                Ok(())
+            }
+
+            NessaExpr::CompiledMatch(l, _, e, arms) => {
+                self.no_template_check(e)?;
+
+                for (p, g, b) in arms {
+                    for t in p.types() {
+                        self.no_template_check_type(t, l)?;
+                    }
+
+                    if let Some(g_inner) = g {
+                        self.no_template_check(g_inner)?;
+                    }
+
+                    self.no_template_check(b)?;
+                }
+
+                Ok(())
+            }
+
+            NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) => self.no_template_check(e),
             
             _ => unimplemented!("{:?}", expr)
         }
@@ -2177,7 +2564,7 @@ impl NessaContext {
                 Ok(())
             }
 
-            NessaExpr::FunctionDefinition(l, _, _, t, a, _, _) => {
+            NessaExpr::FunctionDefinition(l, _, _, t, a, _, _, _, _) => {
                 let err = self.repeated_args(&a.iter().map(|(n, _)| n).collect(), "Parameter");
 
                 if let Err(msg) = err {
@@ -2265,26 +2652,83 @@ impl NessaContext {
         Ok(())
     }
 
-    pub fn annotation_checks(&self, expr: &NessaExpr) -> Result<(), NessaError> {
-        match expr {
-            NessaExpr::Macro(l, an, _, _, _, _) => {
-                for a in an {
-                    let res = match a.name.as_str() {
-                        "test" => Err(format!("Macros cannot have the {} annotation", "test".cyan())),
-                        "doc" => self.check_noret_doc_annotation(a, &vec!()),
-
-                        n => Err(format!("Annotation with name {} does not exist", n.cyan()))  
-                    };
-                    
-                    res.map_err(|m| NessaError::compiler_error(m, l, vec!()))?;
-                }
-            }
+    // Types whose values can be hashed structurally by the runtime
+    pub fn is_hashable(&self, t: &Type) -> bool {
+        match t {
+            Type::Empty |
+            Type::Basic(INT_ID | FLOAT_ID | STR_ID | BOOL_ID) => true,
+            Type::Template(ARR_ID, v) => self.is_hashable(&v[0]),
+            Type::And(v) => v.iter().all(|i| self.is_hashable(i)),
 
-            NessaExpr::ClassDefinition(l, an, _, _, atts, _, _) => {
+            _ => self.implements_interface(t, &HASH, &mut HashMap::new(), &mut HashMap::new())
+        }
+    }
+
+    // Derived comparisons work structurally, so containers are comparable when their elements are
+    pub fn is_comparable(&self, t: &Type, op_id: usize, l: &Location) -> bool {
+        match t {
+            Type::Template(ARR_ID, v) => self.is_comparable(&v[0], op_id, l),
+            Type::And(v) => v.iter().all(|i| self.is_comparable(i, op_id, l)),
+
+            // Derived operations compare the attributes through references
+            _ => self.get_first_binary_op(op_id, t.clone().to_ref(), t.clone().to_ref(), None, true, l).is_ok()
+        }
+    }
+
+    pub fn check_derive_annotation(&self, annot: &Annotation, atts: &[(String, Type)], l: &Location) -> Result<(), String> {
+        let positional = (0..annot.args.len()).map(|i| i.to_string()).collect::<Vec<_>>();
+        annot.check_args(&[], &positional.iter().map(String::as_str).collect::<Vec<_>>())?;
+
+        let implements = |t: &Type, c: &InterfaceConstraint| self.implements_interface(t, c, &mut HashMap::new(), &mut HashMap::new());
+
+        for capability in annot.args.values() {
+            if !["Eq", "Hash", "Ord", "Printable", "Stringable"].contains(&capability.as_str()) {
+                return Err(format!("Interface {} cannot be derived", capability.green()));
+            }
+
+            // Generic attributes are checked when the derived operations are instantiated
+            for (n, t) in atts.iter().filter(|(_, t)| !t.has_templates()) {
+                let (supported, reason) = match capability.as_str() {
+                    "Stringable" => (implements(t, &STRINGABLE), "does not implement it"),
+                    "Printable" => (implements(t, &PRINTABLE) || implements(t, &STRINGABLE), "does not implement it"),
+                    "Hash" => (self.is_hashable(t), "cannot be hashed"),
+                    "Eq" => (self.is_comparable(t, EQ_BINOP_ID, l), "does not define operator =="),
+                    _ => (self.is_comparable(t, LT_BINOP_ID, l), "does not define operator <")
+                };
+
+                if !supported {
+                    return Err(format!(
+                        "Unable to derive {} because attribute {} of type {} {}", 
+                        capability.green(), n.cyan(), t.get_name(self), reason
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn annotation_checks(&self, expr: &NessaExpr) -> Result<(), NessaError> {
+        match expr {
+            NessaExpr::Macro(l, an, _, _, _, _) => {
+                for a in an {
+                    let res = match a.name.as_str() {
+                        "test" => Err(format!("Macros cannot have the {} annotation", "test".cyan())),
+                        "doc" => self.check_noret_doc_annotation(a, &vec!()),
+
+                        n => Err(format!("Annotation with name {} does not exist", n.cyan()))  
+                    };
+                    
+                    res.map_err(|m| NessaError::compiler_error(m, l, vec!()))?;
+                }
+            }
+
+            NessaExpr::ClassDefinition(l, an, _, _, atts, _, _, _) => {
                 for a in an {
                     let res = match a.name.as_str() {
                         "test" => Err(format!("Classes cannot have the {} annotation", "test".cyan())),
                         "doc" => self.check_noret_doc_annotation(a, atts),
+                        "derive" => self.check_derive_annotation(a, atts, l),
 
                         n => Err(format!("Annotation with name {} does not exist", n.cyan()))  
                     };
@@ -2293,7 +2737,7 @@ impl NessaContext {
                 }
             }
 
-            NessaExpr::InterfaceDefinition(l, an, _, _, fns, unops, binops, naryops) => {
+            NessaExpr::InterfaceDefinition(l, an, _, _, _, fns, unops, binops, naryops) => {
                 for a in an {
                     let res = match a.name.as_str() {
                         "test" => Err(format!("Interfaces cannot have the {} annotation", "test".cyan())),
@@ -2305,7 +2749,7 @@ impl NessaContext {
                     res.map_err(|m| NessaError::compiler_error(m, l, vec!()))?;
                 }
 
-                for (inner_an, _, _, args, _) in fns {
+                for (inner_an, _, _, args, _, _) in fns {
                     for a in inner_an {
                         let res = match a.name.as_str() {
                             "test" => Err(format!("Interface function headers cannot have the {} annotation", "test".cyan())),
@@ -2361,7 +2805,7 @@ impl NessaContext {
                 }
             }
 
-            NessaExpr::FunctionDefinition(l, an, _, t, args, r, _) => {
+            NessaExpr::FunctionDefinition(l, an, _, t, args, _, _, r, _) => {
                 for a in an {
                     let res = match a.name.as_str() {
                         "test" => self.check_test_annotation(a, t, args, r),
@@ -2423,51 +2867,497 @@ impl NessaContext {
         Ok(())
     }
 
-    pub fn check_formats(&self, expr: &NessaExpr) {
+    // Checks the bodies of the definitions in a line (the main program is checked with move_check_body)
+    pub fn move_check(&self, expr: &NessaExpr) -> Result<(), NessaError> {
+        match expr {
+            NessaExpr::FunctionDefinition(_, _, _, tm, _, _, _, _, b) |
+            NessaExpr::PrefixOperationDefinition(_, _, _, tm, _, _, _, b) |
+            NessaExpr::PostfixOperationDefinition(_, _, _, tm, _, _, _, b) |
+            NessaExpr::BinaryOperationDefinition(_, _, _, tm, _, _, _, b) |
+            NessaExpr::NaryOperationDefinition(_, _, _, tm, _, _, _, b) if tm.is_empty() => self.move_check_body(b),
+
+            _ => Ok(())
+        }
+    }
+
+    pub fn move_check_body(&self, lines: &[NessaExpr]) -> Result<(), NessaError> {
+        self.move_check_lines(lines, &mut MovedVariables::default(), &mut MoveCheckState::default()).map(|_| ())
+    }
+
+    // Returns true if the end of the lines is never reached
+    fn move_check_lines(&self, lines: &[NessaExpr], moved: &mut MovedVariables, state: &mut MoveCheckState) -> Result<bool, NessaError> {
+        let references_len = state.references.len();
+
+        for line in lines {
+            let diverges = self.move_check_expr(line, moved, state)?;
+
+            state.references.truncate(references_len);
+
+            if diverges {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // Operands are evaluated in the given order and the references they produce are kept until all of them are evaluated.
+    // After that, they are only kept if the result can contain them
+    fn move_check_operands(&self, operands: &[&NessaExpr], keeps_references: bool, moved: &mut MovedVariables, state: &mut MoveCheckState) -> Result<bool, NessaError> {
+        let references_start = state.references.len();
+
+        for op in operands {
+            let previous = state.references.len();
+            let moved_before = moved.clone();
+
+            if self.move_check_expr(op, moved, state)? {
+                return Ok(true);
+            }
+
+            for (idx, (n, l)) in moved.iter().filter(|(i, _)| !moved_before.contains_key(i)) {
+                if let Some((_, ref_l)) = state.references[references_start..previous].iter().find(|(i, _)| i == idx) {
+                    return Err(moved_variable_error(n, l, ref_l));
+                }
+            }
+        }
+
+        if !keeps_references {
+            state.references.truncate(references_start);
+        }
+
+        Ok(false)
+    }
+
+    fn move_check_loop(&self, cond: Option<&NessaExpr>, body: &[NessaExpr], element: Option<usize>, moved: &mut MovedVariables, state: &mut MoveCheckState) -> Result<bool, NessaError> {
+        let mut head = moved.clone();
+
+        // Moves inside the body reach the next iterations, so the state is iterated until it does not change
+        loop {
+            let mut current = head.clone();
+
+            if let Some(c) = cond {
+                if self.move_check_expr(c, &mut current, state)? {
+                    *moved = current;
+                    return Ok(true);
+                }
+            }
+
+            let after_cond = current.clone();
+
+            if let Some(idx) = element {
+                current.remove(&idx);
+            }
+
+            state.loops.push((MovedVariables::default(), MovedVariables::default()));
+
+            let diverges = self.move_check_lines(body, &mut current, state)?;
+            let (continues, breaks) = state.loops.pop().unwrap();
+
+            let mut next_head = head.clone();
+
+            if !diverges {
+                merge_moved(&mut next_head, &current);
+            }
+
+            merge_moved(&mut next_head, &continues);
+
+            if next_head.len() == head.len() {
+                *moved = after_cond;
+                merge_moved(moved, &breaks);
+
+                return Ok(false);
+            }
+
+            head = next_head;
+        }
+    }
+
+    fn move_check_pattern(pattern: &MatchPattern, moved: &mut MovedVariables) {
+        match pattern {
+            MatchPattern::CompiledBinding(idx, _, _, p) => {
+                moved.remove(idx);
+                NessaContext::move_check_pattern(p, moved);
+            },
+
+            MatchPattern::Tuple(v) |
+            MatchPattern::Class(_, v) => v.iter().for_each(|i| NessaContext::move_check_pattern(i, moved)),
+
+            _ => {}
+        }
+    }
+
+    // Returns true if the code after the expression is never reached
+    fn move_check_expr(&self, expr: &NessaExpr, moved: &mut MovedVariables, state: &mut MoveCheckState) -> Result<bool, NessaError> {
+        let move_id = self.get_function_id("move".into()).unwrap();
+        let returns_ref = matches!(
+            expr, 
+            NessaExpr::BinaryOperation(..) | NessaExpr::FunctionCall(..) | NessaExpr::NaryOperation(..)
+        ) && self.infer_type(expr).is_ok_and(|t| t.is_ref());
+
+        match expr {
+            NessaExpr::Variable(l, idx, n, _) => {
+                if let Some((_, moved_at)) = moved.get(idx) {
+                    return Err(moved_variable_error(n, moved_at, l));
+                }
+
+                state.references.push((*idx, l.clone()));
+
+                Ok(false)
+            }
+
+            // Values that are dereferenced right away do not keep a reference
+            NessaExpr::UnaryOperation(_, DEREF_UNOP_ID, _, e) if matches!(e.as_ref(), NessaExpr::Variable(..)) => {
+                if let NessaExpr::Variable(l, idx, n, _) = e.as_ref() {
+                    if let Some((_, moved_at)) = moved.get(idx) {
+                        return Err(moved_variable_error(n, moved_at, l));
+                    }
+                }
+
+                Ok(false)
+            }
+
+            // Both explicit moves and the ones inserted by the optimizer
+            NessaExpr::FunctionCall(l, id, _, args) if *id == move_id && args.len() == 1 && matches!(args[0], NessaExpr::Variable(..)) => {
+                if let NessaExpr::Variable(var_l, idx, n, _) = &args[0] {
+                    if let Some((_, moved_at)) = moved.get(idx) {
+                        return Err(moved_variable_error(n, moved_at, var_l));
+                    }
+
+                    moved.insert(*idx, (n.clone(), l.clone()));
+                }
+
+                Ok(false)
+            }
+
+            NessaExpr::CompiledVariableDefinition(_, idx, _, _, e) |
+            NessaExpr::CompiledVariableAssignment(_, idx, _, _, e) => {
+                if self.move_check_expr(e, moved, state)? {
+                    return Ok(true);
+                }
+
+                moved.remove(idx);
+
+                Ok(false)
+            }
+
+            NessaExpr::UnaryOperation(_, _, _, e) |
+            NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::CompiledPropagate(_, _, e) => self.move_check_expr(e, moved, state),
+
+            NessaExpr::Return(_, e) => {
+                if !self.move_check_expr(e, moved, state)? {
+                    if let Some(b) = state.blocks.last_mut() {
+                        merge_moved(b.get_or_insert_with(MovedVariables::default), moved);
+                    }
+                }
+
+                Ok(true)
+            }
+
+            NessaExpr::Continue(_) => {
+                if let Some((c, _)) = state.loops.last_mut() {
+                    merge_moved(c, moved);
+                }
+
+                Ok(true)
+            }
+
+            NessaExpr::Break(_) => {
+                if let Some((_, b)) = state.loops.last_mut() {
+                    merge_moved(b, moved);
+                }
+
+                Ok(true)
+            }
+
+            // The second operand is not always evaluated
+            NessaExpr::BinaryOperation(_, AND_BINOP_ID | OR_BINOP_ID, _, a, b) => {
+                if self.move_check_expr(a, moved, state)? {
+                    return Ok(true);
+                }
+
+                let mut b_moved = moved.clone();
+                let references_len = state.references.len();
+
+                if !self.move_check_expr(b, &mut b_moved, state)? {
+                    merge_moved(moved, &b_moved);
+                }
+
+                state.references.truncate(references_len);
+
+                Ok(false)
+            }
+
+            // Arguments are evaluated from last to first
+            NessaExpr::BinaryOperation(_, _, _, a, b) => self.move_check_operands(&[b, a], returns_ref, moved, state),
+            NessaExpr::AttributeAssignment(_, a, b, _) => self.move_check_operands(&[a, b], false, moved, state),
+            NessaExpr::FunctionCall(_, _, _, args) => self.move_check_operands(&args.iter().rev().collect::<Vec<_>>(), returns_ref, moved, state),
+            NessaExpr::VariadicArgs(_, _, args) |
+            NessaExpr::Tuple(_, args) => self.move_check_operands(&args.iter().rev().collect::<Vec<_>>(), true, moved, state),
+
+            NessaExpr::NaryOperation(_, _, _, a, args) => {
+                let mut operands = args.iter().rev().collect::<Vec<_>>();
+                operands.push(a);
+
+                self.move_check_operands(&operands, returns_ref, moved, state)
+            }
+
+            NessaExpr::CompiledLambda(_, _, c, _, _, b) => {
+                // The body is executed later with its own variables
+                self.move_check_body(b)?;
+
+                self.move_check_operands(&c.iter().rev().map(|(_, i)| i).collect::<Vec<_>>(), true, moved, state)
+            }
+
+            NessaExpr::DoBlock(_, b, _) => {
+                state.blocks.push(None);
+
+                let diverges = self.move_check_lines(b, moved, state)?;
+                let returns = state.blocks.pop().unwrap();
+
+                match (diverges, returns) {
+                    (false, Some(r)) => merge_moved(moved, &r),
+                    (true, Some(r)) => *moved = r,
+                    (true, None) => return Ok(true),
+                    (false, None) => {}
+                }
+
+                Ok(false)
+            }
+
+            NessaExpr::If(_, ih, ib, ei, eb) => {
+                if self.move_check_expr(ih, moved, state)? {
+                    return Ok(true);
+                }
+
+                // States at the end of the branches that reach the next line
+                let mut ends = vec!();
+                let mut current = moved.clone();
+
+                if !self.move_check_lines(ib, &mut current, state)? {
+                    ends.push(current);
+                }
+
+                for (ei_h, ei_b) in ei {
+                    self.move_check_expr(ei_h, moved, state)?;
+
+                    let mut current = moved.clone();
+
+                    if !self.move_check_lines(ei_b, &mut current, state)? {
+                        ends.push(current);
+                    }
+                }
+
+                match eb {
+                    Some(eb_inner) => {
+                        let mut current = moved.clone();
+
+                        if !self.move_check_lines(eb_inner, &mut current, state)? {
+                            ends.push(current);
+                        }
+                    },
+
+                    None => ends.push(moved.clone())
+                }
+
+                if ends.is_empty() {
+                    return Ok(true);
+                }
+
+                moved.clear();
+                ends.iter().for_each(|i| merge_moved(moved, i));
+
+                Ok(false)
+            }
+
+            NessaExpr::While(_, c, b) => self.move_check_loop(Some(c), b, None, moved, state),
+
+            NessaExpr::CompiledFor(_, _, el_idx, _, c, b) => {
+                if self.move_check_expr(c, moved, state)? {
+                    return Ok(true);
+                }
+
+                self.move_check_loop(None, b, Some(*el_idx), moved, state)
+            }
+
+            NessaExpr::CompiledTry(_, b, err_idx, _, _, h) => {
+                let mut body_moved = moved.clone();
+                let body_diverges = self.move_check_lines(b, &mut body_moved, state)?;
+
+                // The handler can start after any part of the body
+                let mut handler_moved = moved.clone();
+                merge_moved(&mut handler_moved, &body_moved);
+                handler_moved.remove(err_idx);
+
+                let handler_diverges = self.move_check_lines(h, &mut handler_moved, state)?;
+
+                match (body_diverges, handler_diverges) {
+                    (true, true) => return Ok(true),
+                    (false, true) => *moved = body_moved,
+                    (true, false) => *moved = handler_moved,
+                    (false, false) => {
+                        *moved = body_moved;
+                        merge_moved(moved, &handler_moved);
+                    }
+                }
+
+                Ok(false)
+            }
+
+            NessaExpr::CompiledMatch(_, match_idx, e, arms) => {
+                if self.move_check_expr(e, moved, state)? {
+                    return Ok(true);
+                }
+
+                moved.remove(match_idx);
+
+                let mut ends = vec!();
+
+                for (p, g, b) in arms {
+                    let mut current = moved.clone();
+                    NessaContext::move_check_pattern(p, &mut current);
+
+                    if let Some(g_inner) = g {
+                        if self.move_check_expr(g_inner, &mut current, state)? {
+                            continue;
+                        }
+                    }
+
+                    if !self.move_check_expr(b, &mut current, state)? {
+                        ends.push(current);
+                    }
+                }
+
+                if ends.is_empty() {
+                    return Ok(true);
+                }
+
+                moved.clear();
+                ends.iter().for_each(|i| merge_moved(moved, i));
+
+                Ok(false)
+            }
+
+            _ => Ok(false)
+        }
+    }
+
+    // Name of the local variable that a reference points into, if any
+    fn local_reference_root<'a>(&self, expr: &'a NessaExpr) -> Option<&'a String> {
+        if !self.infer_type(expr).is_ok_and(|t| t.is_ref()) {
+            return None;
+        }
+
         match expr {
-            NessaExpr::ClassDefinition(l, _, n, ts, _, _, _) => {
+            NessaExpr::Variable(_, _, n, t) if !t.is_ref() => Some(n),
+
+            NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::NaryOperation(_, _, _, e, _) => self.local_reference_root(e),
+
+            // Functions that return references usually return parts of their first argument
+            NessaExpr::FunctionCall(_, _, _, args) => args.first().and_then(|i| self.local_reference_root(i)),
+
+            _ => None
+        }
+    }
+
+    fn escaping_references(&self, lines: &[NessaExpr], res: &mut Vec<(Location, String)>) {
+        for line in lines {
+            match line {
+                NessaExpr::Return(l, e) => {
+                    if let Some(n) = self.local_reference_root(e) {
+                        res.push((l.clone(), format!("Reference to local variable {} escapes through return", n.green())));
+                    }
+                }
+
+                NessaExpr::If(_, _, ib, ei, eb) => {
+                    self.escaping_references(ib, res);
+
+                    for (_, ei_b) in ei {
+                        self.escaping_references(ei_b, res);
+                    }
+
+                    if let Some(eb_inner) = eb {
+                        self.escaping_references(eb_inner, res);
+                    }
+                }
+
+                NessaExpr::While(_, _, b) |
+                NessaExpr::CompiledFor(_, _, _, _, _, b) => self.escaping_references(b, res),
+
+                NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                    self.escaping_references(b, res);
+                    self.escaping_references(h, res);
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    pub fn reference_warnings(&self, expr: &NessaExpr) -> Vec<(Location, String)> {
+        let mut res = vec!();
+
+        match expr {
+            NessaExpr::FunctionDefinition(_, _, _, tm, _, _, _, _, b) |
+            NessaExpr::PrefixOperationDefinition(_, _, _, tm, _, _, _, b) |
+            NessaExpr::PostfixOperationDefinition(_, _, _, tm, _, _, _, b) |
+            NessaExpr::BinaryOperationDefinition(_, _, _, tm, _, _, _, b) |
+            NessaExpr::NaryOperationDefinition(_, _, _, tm, _, _, _, b) if tm.is_empty() => self.escaping_references(b, &mut res),
+
+            _ => {}
+        }
+
+        res
+    }
+
+    pub fn format_warnings(&self, expr: &NessaExpr) -> Vec<(Location, String)> {
+        let mut res = vec!();
+
+        match expr {
+            NessaExpr::ClassDefinition(l, _, n, ts, _, _, _, _) => {
                 if let Err(warn) = check_class_name(n) {
-                    located_nessa_warning!(l, "{}", warn);
+                    res.push((l.clone(), warn));
                 }
 
                 for t in ts {
                     if let Err(warn) = check_template_name(t) {
-                        located_nessa_warning!(l, "{}", warn);
+                        res.push((l.clone(), warn));
                     }
                 }
             }
 
-            NessaExpr::FunctionDefinition(l, _, id, ts, _, _, _) => {
+            NessaExpr::FunctionDefinition(l, _, id, ts, _, _, _, _, _) => {
                 if let Err(warn) = check_fn_name(&self.functions[*id].name) {
-                    located_nessa_warning!(l, "{}", warn);
+                    res.push((l.clone(), warn));
                 }
 
                 for t in ts {
                     if let Err(warn) = check_template_name(t) {
-                        located_nessa_warning!(l, "{}", warn);
+                        res.push((l.clone(), warn));
                     }
                 }
             }
 
-            NessaExpr::InterfaceDefinition(l, _, n, ts, fns, _, _, _) => {
+            NessaExpr::InterfaceDefinition(l, _, n, ts, _, fns, _, _, _) => {
                 if let Err(warn) = check_interface_name(n) {
-                    located_nessa_warning!(l, "{}", warn);
+                    res.push((l.clone(), warn));
                 }
 
                 for t in ts {
                     if let Err(warn) = check_template_name(t) {
-                        located_nessa_warning!(l, "{}", warn);
+                        res.push((l.clone(), warn));
                     }
                 }
 
                 for f in fns {
                     if let Err(warn) = check_fn_name(&f.1) {
-                        located_nessa_warning!(l, "{}", warn);
+                        res.push((l.clone(), warn));
                     }
 
                     for t in f.2.as_ref().unwrap_or(&vec!()) {
                         if let Err(warn) = check_template_name(t) {
-                            located_nessa_warning!(l, "{}", warn);
+                            res.push((l.clone(), warn));
                         }
                     }
                 }
@@ -2475,6 +3365,20 @@ impl NessaContext {
 
             _ => {}
         }
+
+        res
+    }
+
+    pub fn check_formats(&self, expr: &NessaExpr) {
+        for (l, warn) in self.format_warnings(expr) {
+            located_nessa_warning!(self.warning_sink, l, "{}", warn);
+        }
+    }
+
+    pub fn check_references(&self, expr: &NessaExpr) {
+        for (l, warn) in self.reference_warnings(expr) {
+            located_nessa_warning!(self.warning_sink, l, "{}", warn);
+        }
     }
 
     pub fn static_check_expected(&self, expr: &NessaExpr, expected: &Option<Type>) -> Result<(), NessaError> {
@@ -2488,7 +3392,9 @@ impl NessaContext {
         self.macro_check(expr)?;
         self.interface_impl_check(expr)?;
         self.annotation_checks(expr)?;
+        self.move_check(expr)?;
         self.check_formats(expr);
+        self.check_references(expr);
 
         Ok(())
     }
@@ -2811,4 +3717,161 @@ mod tests {
 
         assert!(ctx.parse_and_compile(&code_str).is_err());
     }
-}
\ No newline at end of file
+    #[test]
+    fn match_exhaustiveness_check() {
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int | String | (Int, Bool)) -> Int {
+                return match a {
+                    0 => 1,
+                    Int => 2,
+                    String => 3,
+                    (n, _) => *n
+                };
+            }
+        ".to_string();
+
+        ctx.parse_and_compile(&code_str).unwrap();
+        
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int | String | (Int, Bool)) -> Int {
+                return match a {
+                    Int => 2,
+                    (_, true) => 3
+                };
+            }
+        ".to_string();
+
+        let err = ctx.parse_and_compile(&code_str).unwrap_err();
+        assert!(err.message.contains("missing cases for String, (Int, Bool)"));
+        
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int | String) -> Int {
+                return match a {
+                    n: Int if n > 0 => 2,
+                    String => 3
+                };
+            }
+        ".to_string();
+
+        let err = ctx.parse_and_compile(&code_str).unwrap_err();
+        assert!(err.message.contains("missing cases for Int"));
+    }
+
+    #[test]
+    fn move_check() {
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int, b: Bool) -> Int {
+                if b {
+                    let c = move(a);
+                    a = 5;
+                }
+
+                while b {
+                    let c = *a;
+
+                    if c > 2 {
+                        let d = move(a);
+                        break;
+                    }
+                }
+
+                return 0;
+            }
+        ".to_string();
+
+        ctx.parse_and_compile(&code_str).unwrap();
+        
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int, b: Bool) -> Int {
+                if b {
+                    let c = move(a);
+                }
+
+                return *a;
+            }
+        ".to_string();
+
+        let err = ctx.parse_and_compile(&code_str).unwrap_err();
+        assert!(err.message.contains("Variable a may have been moved"));
+        
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int, b: Bool) -> Int {
+                while b {
+                    let c = move(a);
+                }
+
+                return 0;
+            }
+        ".to_string();
+
+        let err = ctx.parse_and_compile(&code_str).unwrap_err();
+        assert!(err.message.contains("Variable a may have been moved"));
+        
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: Int) -> (Int, @Int) {
+                return (move(a), a);
+            }
+        ".to_string();
+
+        assert!(ctx.parse_and_compile(&code_str).is_err());
+    }
+
+    #[test]
+    fn escaping_reference_warnings() {
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(b: Bool) -> @Int {
+                let a = 5;
+
+                if b {
+                    return a;
+                }
+
+                return a;
+            }
+        ".to_string();
+
+        let lines = ctx.parse_and_precompile(&code_str).unwrap();
+        let warnings = lines.iter().flat_map(|i| ctx.reference_warnings(i)).collect::<Vec<_>>();
+
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().all(|(_, w)| w.contains("escapes through return")));
+        
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+            fn test(a: @Int, b: Bool) -> @Int {
+                if b {
+                    return a;
+                }
+
+                return a;
+            }
+
+            fn test_2() -> Int {
+                let c = 5;
+
+                return *c + 1;
+            }
+        ".to_string();
+
+        let lines = ctx.parse_and_precompile(&code_str).unwrap();
+
+        assert!(lines.iter().all(|i| ctx.reference_warnings(i).is_empty()));
+    }
+}
@@ -21,10 +21,13 @@ use crate::debug::DebugInfoBuilder;
 use crate::graph::DirectedGraph;
 use crate::id_mapper::IdMapper;
 use crate::interfaces::ITERABLE_ID;
+use crate::interfaces::InterfaceConstraint;
+use crate::interfaces::STRINGABLE;
 use crate::macros::NessaMacro;
 use crate::object::TypeInstance;
 use crate::parser::*;
 use crate::object::NessaArray;
+use crate::object::NessaHashTable;
 use crate::types::*;
 use crate::object::Object;
 use crate::functions::*;
@@ -54,7 +57,8 @@ pub struct NessaError {
     pub module: Arc<String>,
     pub fragment: String,
 
-    pub suggestions: Vec<String>
+    pub suggestions: Vec<String>,
+    pub backtrace: Vec<String>
 }
 
 impl NessaError {
@@ -64,7 +68,7 @@ impl NessaError {
     } 
 
     pub fn syntax_error(message: String, line: usize, column: usize, module: Arc<String>, fragment: String, suggestions: Vec<String>) -> Self {
-        NessaError { err_type: "Syntax error".into(), has_location: true, message, line, column, module, fragment, suggestions }
+        NessaError { err_type: "Syntax error".into(), has_location: true, message, line, column, module, fragment, suggestions, backtrace: vec!() }
     }
 
     #[cold]
@@ -77,7 +81,8 @@ impl NessaError {
             column: location.column, 
             module: location.module.clone(),
             fragment: location.span.clone(), 
-            suggestions 
+            suggestions,
+            backtrace: vec!()
         }
     }
 
@@ -91,7 +96,23 @@ impl NessaError {
             column: 0, 
             module: Arc::default(),
             fragment: "".into(), 
-            suggestions: vec!()
+            suggestions: vec!(),
+            backtrace: vec!()
+        }
+    }
+
+    #[cold]
+    pub fn located_execution_error(message: String, module: Arc<String>, line: usize, backtrace: Vec<String>) -> Self {
+        NessaError { 
+            err_type: "Execution error".into(), 
+            has_location: true,
+            message, 
+            line, 
+            column: 0, 
+            module,
+            fragment: "".into(), 
+            suggestions: vec!(),
+            backtrace
         }
     }
 
@@ -105,13 +126,23 @@ impl NessaError {
             column: 0, 
             module: Arc::default(),
             fragment: "".into(), 
-            suggestions: vec!()
+            suggestions: vec!(),
+            backtrace: vec!()
         }
     }
 
     #[cold]
-    pub fn emit(&self) -> ! {
-        if self.has_location {
+    pub fn print(&self) {
+        if self.has_location && self.fragment.is_empty() {
+            eprintln!(
+                "\n[{} in module {}, line {}]\n\n • {}\n", 
+                self.err_type.red().bold(), 
+                self.module.green(),
+                self.line.to_string().yellow(), 
+                self.message
+            );
+
+        } else if self.has_location {
             let mut frag = self.fragment.as_str();
             
             if let Some(pos) = frag.find('\n') {
@@ -150,7 +181,21 @@ impl NessaError {
                 self.message
             );
         }
-        
+
+        if !self.backtrace.is_empty() {
+            eprintln!("[{}]\n", "Backtrace".blue().bold());
+
+            for f in &self.backtrace {
+                eprintln!(" • {}", f);
+            }
+
+            eprintln!();
+        }
+    }
+
+    #[cold]
+    pub fn emit(&self) -> ! {
+        self.print();
         exit_process();
     }
 }
@@ -222,18 +267,24 @@ macro_rules! nessa_warning {
 
 #[macro_export]
 macro_rules! located_nessa_warning {
-    ($l: expr, $pat: expr $( , $more: expr)*) => {
+    ($sink: expr, $l: expr, $pat: expr $( , $more: expr)*) => {
         use colored::Colorize;
         
         let loc = $l;
-
-        println!(
+        let msg = format!(
             "[{} in module {}, line {}, column {}] {}",
             "Warning".yellow(),
             loc.module.green(),
             loc.line.to_string().yellow(), loc.column.to_string().yellow(),
             format!($pat, $($more,)*)
         );
+
+        if $sink == $crate::context::WarningSink::Stderr {
+            eprintln!("{}", msg);
+
+        } else {
+            println!("{}", msg);
+        }
     };
 }
 
@@ -288,10 +339,20 @@ impl NessaContext {
         let mut res = None;
 
         for expr in lines {
+            // Values returned by the ? operator
+            for t in self.propagated_types(expr)? {
+                res = merge_types(res, Some(t), self);
+            }
+
             match expr {
                 NessaExpr::While(_, _, b) |
                 NessaExpr::CompiledFor(_, _, _, _, _, b) => res = merge_types(res, self.infer_lambda_return_type(b)?, self),
 
+                NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                    res = merge_types(res, self.infer_lambda_return_type(b)?, self);
+                    res = merge_types(res, self.infer_lambda_return_type(h)?, self);
+                }
+
                 NessaExpr::If(_, _, ib, ei, eb) => {
                     res = merge_types(res, self.infer_lambda_return_type(ib)?, self);
 
@@ -313,6 +374,20 @@ impl NessaContext {
         Ok(res)
     }
 
+    // Types of the values that the uses of the ? operator in a line can return
+    fn propagated_types(&self, expr: &NessaExpr) -> Result<Vec<Type>, NessaError> {
+        let mut res = vec!();
+
+        for p in expr.propagations() {
+            if let NessaExpr::CompiledPropagate(_, _, e) = p {
+                res.extend(self.infer_type(e)?.propagation_split().map(|(f, _)| f));
+                res.extend(self.propagated_types(e)?);
+            }
+        }
+
+        Ok(res)
+    }
+
     /*
         ╒══════════════════╕
         │ Full compilation │
@@ -323,8 +398,8 @@ impl NessaContext {
         match expr {
             // Compile variable references
             NessaExpr::NameReference(l, n) if var_map.is_var_defined(n) => {
-                let (idx, t) = var_map.get_var(n).unwrap();
-                *expr = NessaExpr::Variable(l.clone(), *idx, n.clone(), t.clone());
+                let (idx, t) = var_map.get_var_narrowed(n).unwrap();
+                *expr = NessaExpr::Variable(l.clone(), idx, n.clone(), t);
             },
 
             NessaExpr::NameReference(l, n) => {
@@ -397,7 +472,30 @@ impl NessaContext {
 
             NessaExpr::BinaryOperation(l, id, t, a, b) => {
                 self.compile_expr_variables(a, registers, var_map)?;
-                self.compile_expr_variables(b, registers, var_map)?;
+
+                // The second operand of a short-circuiting operation knows the result of the first one
+                let narrowings = match *id {
+                    AND_BINOP_ID => self.condition_narrowings(a, true),
+                    OR_BINOP_ID => self.condition_narrowings(a, false),
+                    _ => vec!()
+                };
+
+                if narrowings.is_empty() {
+                    self.compile_expr_variables(b, registers, var_map)?;
+
+                } else {
+                    let b_cpy = b.clone();
+
+                    NessaContext::add_narrowed_context(var_map, &narrowings, std::slice::from_ref(b));
+                    self.compile_expr_variables(b, registers, var_map)?;
+                    var_map.remove_context();
+
+                    // Only operations on booleans are short-circuited
+                    if *self.infer_type(b)?.deref_type() != BOOL {
+                        *b = b_cpy;
+                        self.compile_expr_variables(b, registers, var_map)?;
+                    }
+                }
 
                 let is_func = matches!(b.as_ref(), NessaExpr::FunctionCall(..));
                 let is_name = matches!(b.as_ref(), NessaExpr::QualifiedName(..));
@@ -472,6 +570,28 @@ impl NessaContext {
                 }
             }
             
+            NessaExpr::CompoundAssignment(l, id, a, b) => {
+                self.compile_expr_variables(a, registers, var_map)?;
+                self.compile_expr_variables(b, registers, var_map)?;
+
+                let arg_type_1 = self.infer_type(a)?;
+                let arg_type_2 = self.infer_type(b)?;
+
+                // Defined operations modify the first operand in place and the rest are rewritten as a := a op b
+                if self.get_first_binary_op(*id, arg_type_1, arg_type_2, None, true, l).is_ok() {
+                    *expr = NessaExpr::BinaryOperation(l.clone(), *id, vec!(), a.clone(), b.clone());
+
+                } else {
+                    let base_id = compound_assignment_base(*id).unwrap();
+                    let op = NessaExpr::BinaryOperation(l.clone(), base_id, vec!(), a.clone(), b.clone());
+
+                    *expr = NessaExpr::BinaryOperation(l.clone(), ASSIGN_BINOP_ID, vec!(), a.clone(), Box::new(op));
+                }
+
+                // Recompile after transformation
+                self.compile_expr_variables(expr, registers, var_map)?;
+            }
+
             NessaExpr::NaryOperation(l, id, t, a, b) => {
                 self.compile_expr_variables(a, registers, var_map)?;
 
@@ -481,6 +601,12 @@ impl NessaContext {
 
                 let is_func = matches!(a.as_ref(), NessaExpr::QualifiedName(_, _, Some(_)));
 
+                if !(*id == CALL_OP && is_func) {
+                    if let Some(named) = b.iter().find(|i| matches!(i, NessaExpr::NamedArgument(..))) {
+                        return Err(NessaError::compiler_error("Named arguments can only be used when calling functions".into(), named.get_location(), vec!()));
+                    }
+                }
+
                 if *id == CALL_OP && is_func {
                     if let NessaExpr::QualifiedName(_, _, Some(id)) = a.as_ref() {                    
                         *expr = NessaExpr::FunctionCall(l.clone(), *id, t.clone(), b.clone());
@@ -503,6 +629,12 @@ impl NessaContext {
                 }
             }
 
+            NessaExpr::VariadicArgs(_, _, args) => {
+                for i in args {
+                    self.compile_expr_variables(i, registers, var_map)?;                    
+                }
+            }
+
             NessaExpr::Tuple(_, args) => {
                 if args.len() == 1 {
                     *expr = args.pop().unwrap();
@@ -520,6 +652,8 @@ impl NessaContext {
                     self.compile_expr_variables(i, registers, var_map)?;                    
                 }
 
+                self.complete_call_args(l, *id, t, args, registers)?;
+
                 if t.is_empty() {
                     let arg_types: Vec<_> = args.iter().map(|a| self.infer_type(a)).collect::<Result<_, _>>()?;
 
@@ -538,15 +672,35 @@ impl NessaContext {
             // Compile flow control
             NessaExpr::If(_, h, ib, ei, eb) => {
                 self.compile_expr_variables(h, registers, var_map)?;
+
+                NessaContext::add_narrowed_context(var_map, &self.condition_narrowings(h, true), ib);
                 self.compile_vars_and_infer_ctx(ib, registers, var_map, &vec!())?;
+                var_map.remove_context();
+
+                // Narrowings from the conditions that have been false before reaching each branch
+                let mut failed = self.condition_narrowings(h, false);
 
                 for (ei_h, ei_b) in ei {
+                    NessaContext::add_narrowed_context(var_map, &failed, std::slice::from_ref(ei_h));
                     self.compile_expr_variables(ei_h, registers, var_map)?;
+                    var_map.remove_context();
+
+                    failed.retain(|(_, n, _)| !ei_h.assigns_variable(n));
+
+                    let mut narrowings = failed.clone();
+                    narrowings.extend(self.condition_narrowings(ei_h, true));
+
+                    NessaContext::add_narrowed_context(var_map, &narrowings, ei_b);
                     self.compile_vars_and_infer_ctx(ei_b, registers, var_map, &vec!())?;
+                    var_map.remove_context();
+
+                    failed.extend(self.condition_narrowings(ei_h, false));
                 }
 
                 if let Some(eb_inner) = eb {
+                    NessaContext::add_narrowed_context(var_map, &failed, eb_inner);
                     self.compile_vars_and_infer_ctx(eb_inner, registers, var_map, &vec!())?;
+                    var_map.remove_context();
                 }
             }
 
@@ -585,15 +739,70 @@ impl NessaContext {
                 self.cache.overloads.functions.insert((NEXT_FUNC_ID, vec!(it_mut.clone()), next_args.clone()), next_ov_id);            
                 self.cache.overloads.functions.insert((IS_CONSUMED_FUNC_ID, vec!(it_mut.clone()), consumed_args.clone()), consumed_ov_id);
 
-                self.compile_vars_and_infer_ctx(b, registers, var_map, &vec!(("__iterator__".into(), iterator_type.clone()), (i.clone(), element_type.clone())))?;
+                // Counting loops keep the end and the counter in the loop registers and bind the element to a copy of the counter
+                if self.is_counting_loop(c)? {
+                    let counter = NessaExpr::NameReference(l.clone(), "__counter__".into());
+                    let copy = NessaExpr::UnaryOperation(l.clone(), DEREF_UNOP_ID, vec!(), Box::new(counter));
+
+                    b.insert(0, NessaExpr::VariableDefinition(l.clone(), i.clone(), INT, Box::new(copy)));
+
+                    self.compile_vars_and_infer_ctx(b, registers, var_map, &vec!(("__end__".into(), INT), ("__counter__".into(), INT)))?;
+
+                } else {
+                    self.compile_vars_and_infer_ctx(b, registers, var_map, &vec!(("__iterator__".into(), iterator_type.clone()), (i.clone(), element_type.clone())))?;
+                }
 
                 *expr = NessaExpr::CompiledFor(l.clone(), iterator_idx, element_idx, i.clone(), c.clone(), b.clone());
             }
 
+            NessaExpr::Try(l, b, n, t, h) => {
+                self.compile_vars_and_infer_ctx(b, registers, var_map, &vec!())?;
+
+                // The caught error is stored in the first register of the handler
+                let error_idx = *registers.last().unwrap();
+
+                self.compile_vars_and_infer_ctx(h, registers, var_map, &vec!((n.clone(), t.clone())))?;
+
+                *expr = NessaExpr::CompiledTry(l.clone(), b.clone(), error_idx, n.clone(), t.clone(), h.clone());
+            }
+
             NessaExpr::Return(_, e) => {
                 self.compile_expr_variables(e, registers, var_map)?;
             }
 
+            NessaExpr::NamedArgument(_, _, e) => {
+                self.compile_expr_variables(e, registers, var_map)?;
+            }
+
+            NessaExpr::Propagate(l, e) => {
+                self.compile_expr_variables(e, registers, var_map)?;
+
+                let value_type = self.infer_type(e)?;
+
+                if value_type.propagation_split().is_none() {
+                    return Err(NessaError::compiler_error(
+                        format!("Unable to use ? on a value of type {} (expected a union with () or Error and other types)", value_type.get_name(self)), 
+                        l, vec!()
+                    ));
+                }
+
+                // The value is checked in a temporary register that is free again right after
+                let value_idx = *registers.last().unwrap();
+
+                *expr = NessaExpr::CompiledPropagate(l.clone(), value_idx, e.clone());
+            }
+
+            NessaExpr::Interpolation(l, e) => {
+                let to_string_id = self.get_function_id("to_string".into()).unwrap();
+
+                *expr = NessaExpr::FunctionCall(l.clone(), to_string_id, vec!(), vec!(*e.clone()));
+                self.compile_expr_variables(expr, registers, var_map)?;
+
+                if let NessaExpr::FunctionCall(_, _, _, args) = expr {
+                    self.stringable_check(&self.infer_type(&args[0])?, args[0].get_location())?;
+                }
+            }
+
             NessaExpr::DoBlock(_, b, r) => {
                 self.compile_vars_and_infer_ctx(b, registers, var_map, &vec!())?;
 
@@ -603,6 +812,39 @@ impl NessaContext {
                 }
             }
 
+            NessaExpr::Match(l, e, arms) => {
+                self.compile_expr_variables(e, registers, var_map)?;
+
+                let scrutinee_type = self.infer_type(e)?;
+                let match_idx = registers.pop().unwrap();
+
+                // Patterns are checked against a reference to the stored value
+                let value_type = self.infer_type(&NessaExpr::Variable(l.clone(), match_idx, String::new(), scrutinee_type.clone()))?;
+
+                var_map.add_context();
+                var_map.define_var("__match__".into(), match_idx, scrutinee_type);
+
+                for (p, g, b) in arms.iter_mut() {
+                    var_map.add_context();
+
+                    self.compile_match_pattern(p, &value_type, registers, var_map, l)?;
+
+                    if let Some(g_inner) = g {
+                        self.compile_expr_variables(g_inner, registers, var_map)?;
+                    }
+
+                    self.compile_expr_variables(b, registers, var_map)?;
+
+                    var_map.for_each_last_ctx(|i| registers.push(i));
+                    var_map.remove_context();
+                }
+
+                var_map.for_each_last_ctx(|i| registers.push(i));
+                var_map.remove_context();
+
+                *expr = NessaExpr::CompiledMatch(l.clone(), match_idx, e.clone(), arms.clone());
+            }
+
             NessaExpr::Lambda(l, c, a, r, b) => {
                 let mut captures = vec!();
                 let mut capture_args = vec!();
@@ -610,9 +852,9 @@ impl NessaContext {
                 // Compile lambda captures
                 for n in c {
                     if var_map.is_var_defined(n) {
-                        let (idx, t) = var_map.get_var(n).unwrap();
-                        captures.push((n.clone(), NessaExpr::Variable(l.clone(), *idx, n.clone(), t.clone())));
-                        capture_args.push((n.clone(), t.clone()));
+                        let (idx, t) = var_map.get_var_narrowed(n).unwrap();
+                        captures.push((n.clone(), NessaExpr::Variable(l.clone(), idx, n.clone(), t.clone())));
+                        capture_args.push((n.clone(), t));
                     
                     } else {
                         return Err(NessaError::compiler_error(format!("Variable with name {} is not defined", n.green()), l, vec!()));
@@ -630,7 +872,13 @@ impl NessaContext {
                 self.lambdas += 1;
             },
 
-            NessaExpr::FunctionDefinition(l, _, _, tm, a, r, b) => {
+            NessaExpr::FunctionDefinition(l, _, _, tm, a, d, _, r, b) => {
+                for ((n, t), default) in a.iter().zip(d.iter_mut()) {
+                    if let Some(default) = default {
+                        self.compile_default_value(default, n, t, registers)?;
+                    }
+                }
+
                 if tm.is_empty() {
                     self.compile(b, a)?;
                 }
@@ -699,7 +947,370 @@ impl NessaContext {
         Ok(())
     }
     
-    fn compile_vars_and_infer_ctx(&mut self, body: &mut Vec<NessaExpr>, registers: &mut Vec<usize>, var_map: &mut VariableMap, args: &Vec<(String, Type)>) -> Result<usize, NessaError> {
+    // Variables (register, name and type) whose type is narrowed when a compiled condition evaluates to value
+    fn condition_narrowings(&self, cond: &NessaExpr, value: bool) -> Vec<(usize, String, Type)> {
+        match cond {
+            NessaExpr::FunctionCall(_, id, t, args) if t.len() == 1 && args.len() == 1 && *id == self.get_function_id("is".into()).unwrap() => {
+                let var = match &args[0] {
+                    NessaExpr::UnaryOperation(_, DEREF_UNOP_ID, _, e) => e.as_ref(),
+                    e => e
+                };
+
+                if let (NessaExpr::Variable(_, idx, n, var_type), Ok(arg_type)) = (var, self.infer_type(&args[0])) {
+                    // Types that are not references are checked against the referenced value
+                    let same_kind = matches!((&arg_type, &t[0]), (Type::Ref(_), Type::Ref(_)) | (Type::MutRef(_), Type::MutRef(_))) ||
+                                    !t[0].is_ref();
+
+                    if let Some(narrowed) = arg_type.deref_type().narrow(t[0].deref_type(), value, self).filter(|_| same_kind) {
+                        let new_type = match var_type {
+                            Type::Ref(_) => narrowed.to_ref(),
+                            Type::MutRef(_) => narrowed.to_mut(),
+                            _ => narrowed
+                        };
+
+                        return vec!((*idx, n.clone(), new_type));
+                    }
+                }
+
+                vec!()
+            }
+
+            NessaExpr::UnaryOperation(_, NOT_UNOP_ID, _, e) => self.condition_narrowings(e, !value),
+
+            NessaExpr::BinaryOperation(_, AND_BINOP_ID, _, a, b) if value => {
+                let mut res = self.condition_narrowings(a, true);
+                res.extend(self.condition_narrowings(b, true));
+                res
+            }
+
+            NessaExpr::BinaryOperation(_, OR_BINOP_ID, _, a, b) if !value => {
+                let mut res = self.condition_narrowings(a, false);
+                res.extend(self.condition_narrowings(b, false));
+                res
+            }
+
+            _ => vec!()
+        }
+    }
+
+    // Narrowed types only hold in places where the variable is not assigned again
+    // Default values are compiled without any variables in scope, since they are evaluated at each call site
+    fn compile_default_value(&mut self, default: &mut NessaExpr, name: &str, t: &Type, registers: &mut Vec<usize>) -> Result<(), NessaError> {
+        self.compile_expr_variables(default, registers, &mut VariableMap::new())?;
+
+        let default_t = self.infer_type(default)?;
+
+        if t.has_templates() || default_t.has_templates() {
+            return Err(NessaError::compiler_error(
+                format!("Default value of parameter {} cannot depend on template parameters", name.green()), 
+                default.get_location(), vec!()
+            ));
+        }
+
+        if !default_t.bindable_to(t, self) {
+            return Err(NessaError::compiler_error(
+                format!("Default value of parameter {} has type {}, which is not bindable to {}", name.green(), default_t.get_name(self), t.get_name(self)), 
+                default.get_location(), vec!()
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Adds the arguments of a function call that are omitted or given by name and collects the variadic ones, using the overload that matches
+    fn complete_call_args(&mut self, l: &Location, id: usize, templates: &[Type], args: &mut Vec<NessaExpr>, registers: &mut Vec<usize>) -> Result<(), NessaError> {
+        let positional = args.iter().take_while(|i| !matches!(i, NessaExpr::NamedArgument(..))).count();
+
+        // Only trailing arguments can be omitted
+        if positional == args.len() {
+            let arg_types: Vec<_> = args.iter().map(|a| self.infer_type(a)).collect::<Result<_, _>>()?;
+            let call_templates = if templates.is_empty() { None } else { Some(templates.to_vec()) };
+
+            if self.is_function_overload_ambiguous(id, arg_types.clone()).is_none() {
+                if let Ok((ov_id, _, _, t_args)) = self.get_first_function_overload(id, arg_types.clone(), call_templates, true, l) {
+                    let ov = &self.functions[id].overloads[ov_id];
+
+                    if let Some(elem_t) = ov.variadic_type().cloned() {
+                        // Arrays passed directly are not collected
+                        if args.len() != ov.arity() || !Type::And(arg_types).bindable_to(&ov.args, self) {
+                            let rest = args.split_off(ov.arity() - 1);
+                            let subs = if templates.is_empty() { t_args } else { templates.to_vec() };
+
+                            args.push(self.variadic_array(l, id, elem_t.sub_templates(&subs.into_iter().enumerate().collect()), rest)?);
+                        }
+
+                    } else {
+                        for (_, default) in ov.params.clone().into_iter().skip(args.len()) {
+                            let mut default = default.unwrap();
+                            self.compile_expr_variables(&mut default, registers, &mut VariableMap::new())?;
+                            args.push(default);
+                        }
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+
+        let mut named = vec!();
+
+        for arg in args.split_off(positional) {
+            match arg {
+                NessaExpr::NamedArgument(_, n, e) => named.push((n, *e)),
+                other => return Err(NessaError::compiler_error("Positional arguments cannot come after named arguments".into(), other.get_location(), vec!()))
+            }
+        }
+
+        let overloads = self.functions[id].overloads.iter().map(|ov| (ov.params.clone(), ov.args.clone(), ov.variadic_type().cloned(), ov.ret.clone())).collect::<Vec<_>>();
+        let mut candidates = vec!();
+
+        'overloads: for (params, ov_args, elem_t, ov_ret) in overloads {
+            // Named arguments cannot be given to variadic parameters
+            let fixed = params.len() - elem_t.is_some() as usize;
+
+            if params.is_empty() || (fixed < positional && elem_t.is_none()) {
+                continue;
+            }
+
+            let mut slots = args.iter().take(fixed).cloned().map(Some).collect::<Vec<_>>();
+            let rest = args.iter().skip(fixed).cloned().collect::<Vec<_>>();
+            slots.resize(fixed, None);
+
+            for (n, e) in &named {
+                match params.iter().take(fixed).position(|(p, _)| p == n) {
+                    Some(idx) if slots[idx].is_none() => slots[idx] = Some(e.clone()),
+                    _ => continue 'overloads
+                }
+            }
+
+            let mut full_args = Vec::with_capacity(params.len());
+
+            for (slot, (_, default)) in slots.into_iter().zip(params) {
+                match (slot, default) {
+                    (Some(e), _) => full_args.push(e),
+
+                    (None, Some(mut d)) => {
+                        self.compile_expr_variables(&mut d, registers, &mut VariableMap::new())?;
+                        full_args.push(d);
+                    }
+
+                    (None, None) => continue 'overloads
+                }
+            }
+
+            let arg_types: Vec<_> = full_args.iter().chain(&rest).map(|a| self.infer_type(a)).collect::<Result<_, _>>()?;
+
+            let expected = match (&ov_args, &elem_t) {
+                (Type::And(v), Some(t)) => Type::And(v[..fixed].iter().cloned().chain(std::iter::repeat_n(t.clone(), rest.len())).collect()),
+                _ => ov_args.clone()
+            };
+
+            if let (true, subs) = Type::And(arg_types).bindable_to_subtitutions(&expected, self) {
+                candidates.push((full_args, rest, elem_t.map(|t| t.sub_templates(&subs)), ov_args, ov_ret));
+            }
+        }
+
+        let f_name = &self.functions[id].name;
+
+        let arg_names = args.iter().map(|a| self.infer_type(a).map(|t| t.get_name(self)))
+                            .chain(named.iter().map(|(n, e)| self.infer_type(e).map(|t| format!("{}: {}", n, t.get_name(self)))))
+                            .collect::<Result<Vec<_>, _>>()?
+                            .join(", ");
+
+        match candidates.len() {
+            0 => Err(NessaError::compiler_error(
+                format!("Unable to get function overload for {}({})", f_name.green(), arg_names), 
+                l, vec!()
+            )),
+
+            1 => {
+                let (full_args, rest, elem_t, _, _) = candidates.pop().unwrap();
+                *args = full_args;
+
+                if let Some(t) = elem_t {
+                    args.push(self.variadic_array(l, id, t, rest)?);
+                }
+
+                Ok(())
+            }
+
+            _ => Err(NessaError::compiler_error(
+                format!("Function call {}({}) is ambiguous", f_name.green(), arg_names), l,
+                candidates.iter().map(|(_, _, _, a, r)| format!("Possible overload: {}{} -> {}", f_name, a.get_name(self), r.get_name(self))).collect()
+            ))
+        }
+    }
+
+    // Array with the arguments of a call that are collected by a variadic parameter
+    fn variadic_array(&self, l: &Location, id: usize, elem_t: Type, elems: Vec<NessaExpr>) -> Result<NessaExpr, NessaError> {
+        if elem_t.has_templates() {
+            return Err(NessaError::compiler_error(
+                format!("Unable to infer the type of the variadic arguments of {}", self.functions[id].name.green()), 
+                l, vec!()
+            ));
+        }
+
+        Ok(NessaExpr::VariadicArgs(l.clone(), elem_t, elems))
+    }
+
+    fn narrow_variables(var_map: &mut VariableMap, narrowings: &[(usize, String, Type)], region: &[NessaExpr]) {
+        for (idx, n, t) in narrowings {
+            if !region.iter().any(|i| i.assigns_variable(n)) {
+                var_map.narrow_var(*idx, t.clone());
+            }
+        }
+    }
+
+    fn add_narrowed_context(var_map: &mut VariableMap, narrowings: &[(usize, String, Type)], region: &[NessaExpr]) {
+        var_map.add_context();
+        NessaContext::narrow_variables(var_map, narrowings, region);
+    }
+
+    fn compile_match_pattern(&mut self, pattern: &mut MatchPattern, t: &Type, registers: &mut Vec<usize>, var_map: &mut VariableMap, l: &Location) -> Result<(), NessaError> {
+        match pattern {
+            MatchPattern::Wildcard |
+            MatchPattern::Literal(_) |
+            MatchPattern::CompiledBinding(..) => {},
+
+            MatchPattern::Type(pt) => *pt = self.match_pattern_type(pt, t, l)?,
+
+            MatchPattern::Class(pt, p) => {
+                *pt = self.match_pattern_type(pt, t, l)?;
+
+                let attributes = match self.match_class_attributes(pt) {
+                    Some(a) => a,
+                    None => return Err(NessaError::compiler_error(format!("Type {} cannot be destructured", pt.get_name(self)), l, vec!()))
+                };
+
+                if attributes.len() != p.len() {
+                    return Err(NessaError::compiler_error(
+                        format!("Class {} has {} attributes (got {} patterns)", pt.get_name(self), attributes.len(), p.len()), 
+                        l, vec!()
+                    ));
+                }
+
+                let class_type = NessaContext::match_ref_type(t, pt.clone());
+
+                for (i, att) in p.iter_mut().zip(&attributes) {
+                    self.compile_match_pattern(i, &NessaContext::match_access_type(&class_type, att), registers, var_map, l)?;
+                }
+            },
+
+            MatchPattern::Tuple(p) => {
+                let elements = match self.match_tuple_elements(t, p.len()) {
+                    Some(e) => e,
+                    None => return Err(NessaError::compiler_error(
+                        format!("Type {} cannot be destructured into {} elements", t.deref_type().get_name(self), p.len()), 
+                        l, vec!()
+                    ))
+                };
+
+                let tuple_type = NessaContext::match_ref_type(t, Type::And(elements.clone()));
+
+                for (i, elem) in p.iter_mut().zip(&elements) {
+                    self.compile_match_pattern(i, &NessaContext::match_access_type(&tuple_type, elem), registers, var_map, l)?;
+                }
+            },
+
+            MatchPattern::Binding(n, p) => {
+                self.compile_match_pattern(p, t, registers, var_map, l)?;
+
+                if var_map.is_var_defined_in_last_ctx(n) {
+                    return Err(NessaError::compiler_error(format!("Variable with name {} is already defined", n.green()), l, vec!()));
+                }
+
+                let idx = registers.pop().unwrap();
+                let bound_type = self.match_narrowed_type(p, t);
+
+                var_map.define_var(n.clone(), idx, bound_type.clone());
+
+                *pattern = MatchPattern::CompiledBinding(idx, n.clone(), bound_type, p.clone());
+            }
+        }
+
+        Ok(())
+    }
+
+    // Definitions of the names bound by an irrefutable pattern, accessing the destructured value through e
+    fn destructuring_definitions(&self, pattern: &MatchPattern, e: NessaExpr, l: &Location, res: &mut Vec<NessaExpr>) -> Result<(), NessaError> {
+        let t = self.infer_type(&e)?;
+
+        match pattern {
+            MatchPattern::Wildcard => {},
+
+            MatchPattern::Binding(n, _) => res.push(NessaExpr::VariableDefinition(l.clone(), n.clone(), Type::InferenceMarker, Box::new(e))),
+
+            MatchPattern::Tuple(p) => {
+                if !matches!(t.deref_type(), Type::And(v) if v.len() == p.len()) {
+                    return Err(NessaError::compiler_error(
+                        format!("Type {} cannot be destructured into {} elements", t.deref_type().get_name(self), p.len()), 
+                        l, vec!()
+                    ));
+                }
+
+                for (i, sub) in p.iter().enumerate() {
+                    let get_id = self.get_function_id(format!("get_{}", i)).map_err(|err| NessaError::compiler_error(err, l, vec!()))?;
+                    let elem = NessaExpr::FunctionCall(l.clone(), get_id, vec!(), vec!(e.clone()));
+
+                    self.destructuring_definitions(sub, elem, l, res)?;
+                }
+            },
+
+            MatchPattern::Class(pt, p) => {
+                let pt = self.match_pattern_type(pt, &t, l)?;
+
+                if !t.deref_type().bindable_to(&pt, self) {
+                    return Err(NessaError::compiler_error(
+                        format!("Type {} cannot be destructured as {}", t.deref_type().get_name(self), pt.get_name(self)), 
+                        l, vec!()
+                    ));
+                }
+
+                let attributes = match self.match_class_attributes(&pt) {
+                    Some(a) => a,
+                    None => return Err(NessaError::compiler_error(format!("Type {} cannot be destructured", pt.get_name(self)), l, vec!()))
+                };
+
+                if attributes.len() != p.len() {
+                    return Err(NessaError::compiler_error(
+                        format!("Class {} has {} attributes (got {} patterns)", pt.get_name(self), attributes.len(), p.len()), 
+                        l, vec!()
+                    ));
+                }
+
+                for (i, sub) in p.iter().enumerate() {
+                    self.destructuring_definitions(sub, NessaExpr::AttributeAccess(l.clone(), Box::new(e.clone()), i), l, res)?;
+                }
+            },
+
+            _ => unreachable!()
+        }
+
+        Ok(())
+    }
+
+    // Value type checked by a type pattern. Generic classes take their arguments from the scrutinee
+    fn match_pattern_type(&self, pt: &Type, t: &Type, l: &Location) -> Result<Type, NessaError> {
+        let pt = pt.deref_type().clone();
+
+        if let Type::Basic(id) = pt {
+            if !self.type_templates[id].params.is_empty() && self.type_templates[id].is_nominal() {
+                let alts = self.match_alternatives(t.deref_type());
+
+                return match alts.iter().find(|i| matches!(i, Type::Template(i_id, _) if *i_id == id)) {
+                    Some(i) => Ok(i.clone()),
+                    None => Err(NessaError::compiler_error(
+                        format!("Unable to infer the type arguments of {} from {}", pt.get_name(self), t.deref_type().get_name(self)), 
+                        l, vec!()
+                    ))
+                };
+            }
+        }
+
+        Ok(pt)
+    }
+
+    pub fn compile_vars_and_infer_ctx(&mut self, body: &mut Vec<NessaExpr>, registers: &mut Vec<usize>, var_map: &mut VariableMap, args: &Vec<(String, Type)>) -> Result<usize, NessaError> {
         var_map.add_context();
 
         for (n, t) in args {
@@ -708,8 +1319,37 @@ impl NessaContext {
         }
 
         // Compile each expression sequentially
-        for e in body {
-            self.compile_expr_variables(e, registers, var_map)?;
+        let mut i = 0;
+
+        while i < body.len() {
+            // Destructurings are lowered into a hidden variable and a definition for each bound name
+            if let NessaExpr::DestructuringDefinition(l, p, e) = &body[i] {
+                let (l, p) = (l.clone(), p.clone());
+
+                body[i] = NessaExpr::VariableDefinition(l.clone(), format!("__destructured_{}__", i), Type::InferenceMarker, e.clone());
+                self.compile_expr_variables(&mut body[i], registers, var_map)?;
+
+                if let NessaExpr::CompiledVariableDefinition(_, idx, n, t, _) = &body[i] {
+                    let mut definitions = vec!();
+                    self.destructuring_definitions(&p, NessaExpr::Variable(l.clone(), *idx, n.clone(), t.clone()), &l, &mut definitions)?;
+
+                    body.splice(i + 1..i + 1, definitions);
+                }
+
+                i += 1;
+                continue;
+            }
+
+            self.compile_expr_variables(&mut body[i], registers, var_map)?;
+
+            // Branches that always return narrow the checked variables in the rest of the block
+            if let NessaExpr::If(l, h, ib, ei, None) = &body[i] {
+                if ei.is_empty() && NessaContext::ensured_return_check_body(ib, l, "If").is_ok() {
+                    NessaContext::narrow_variables(var_map, &self.condition_narrowings(h, false), &body[i + 1..]);
+                }
+            }
+
+            i += 1;
         }
 
         let mut max_var = 0;
@@ -820,6 +1460,7 @@ impl NessaContext {
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::UnaryOperation(_, _, _, e) |
             NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) => self.transform_term(e),
 
@@ -833,6 +1474,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::FunctionCall(_, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     self.transform_term(e)?;
@@ -852,6 +1494,28 @@ impl NessaContext {
                 Ok(())
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for e in b.iter_mut().chain(h) {
+                    self.transform_term(e)?;
+                }
+
+                Ok(())
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.transform_term(e)?;
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.transform_term(g_inner)?;
+                    }
+
+                    self.transform_term(b)?;
+                }
+
+                Ok(())
+            },
+
             NessaExpr::NaryOperation(_, _, _, c, exprs) => {
                 self.transform_term(c)?;
 
@@ -899,13 +1563,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -943,6 +1607,9 @@ pub enum PlaceholderType {
     Break, Continue
 }
 
+// Pattern checks, indices of their failure jumps and binding stores
+type MatchPatternCode = (Vec<NessaInstruction>, Vec<usize>, Vec<NessaInstruction>);
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CompiledNessaExpr {
     Empty,
@@ -999,6 +1666,9 @@ pub enum CompiledNessaExpr {
     BinaryOperatorCall(usize, usize, Vec<Type>),
     NaryOperatorCall(usize, usize, Vec<Type>),
 
+    // Calls chosen by the runtime type of the first argument
+    DynamicCall(usize, Vec<(Type, CompiledNessaExpr)>),
+
     NativeFunctionCallNoRet(usize, usize, Vec<Type>), // No return variants
     UnaryOperatorCallNoRet(usize, usize, Vec<Type>),
     BinaryOperatorCallNoRet(usize, usize, Vec<Type>),
@@ -1017,6 +1687,14 @@ pub enum CompiledNessaExpr {
 
     AddStr,
 
+    // In-place arithmetic opcodes (compound assignments)
+    AddAssigni, AddAssignf,
+    SubAssigni, SubAssignf,
+    MulAssigni, MulAssignf,
+    DivAssigni, DivAssignf,
+    ModAssigni, ModAssignf,
+    AddAssignStr,
+
     // Bitwise opcodes
     NotB, AndB, OrB, XorB, Shr, Shl,
 
@@ -1035,6 +1713,15 @@ pub enum CompiledNessaExpr {
     
     Nand, Nor, // Only via peephole optimization
 
+    // Pattern matching opcodes
+    MatchType(Type), MatchValue,
+
+    // Error handling opcodes
+    Try(usize), EndTry,
+
+    // Value propagation opcodes
+    StackBase(usize), EndStackBase, ClearStack,
+
     Placeholder(PlaceholderType),
 
     Halt
@@ -1074,6 +1761,18 @@ impl CompiledNessaExpr {
             self, 
             Assign |
             Inc | Dec
+        ) || self.is_in_place()
+    }
+
+    // Opcodes that modify their first operand, so only the second one is dereferenced
+    pub fn is_in_place(&self) -> bool {
+        use CompiledNessaExpr::*;
+
+        matches!(
+            self, 
+            AddAssigni | SubAssigni | MulAssigni | DivAssigni | ModAssigni |
+            AddAssignf | SubAssignf | MulAssignf | DivAssignf | ModAssignf |
+            AddAssignStr
         )
     }
 
@@ -1134,6 +1833,11 @@ impl CompiledNessaExpr {
             TupleElemCopy(to) => format!("{}({})", "TupleElemCopy".green(), to.to_string().blue()),
             TupleElemDeref(to) => format!("{}({})", "TupleElemDeref".green(), to.to_string().blue()),
 
+            MatchType(t) => format!("{}({})", "MatchType".green(), t.get_name(ctx)),
+
+            Try(to) => format!("{}({})", "Try".green(), to.to_string().blue()),
+            StackBase(to) => format!("{}({})", "StackBase".green(), to.to_string().blue()),
+
             NativeFunctionCall(id, ov, args) => format!(
                 "{}({}, {}, {{{}}})", "FunctionCall".green(), 
                 ctx.functions[*id].name.magenta(), 
@@ -1141,6 +1845,12 @@ impl CompiledNessaExpr {
                 args.iter().map(|i| i.get_name(ctx)).collect::<Vec<_>>().join(", ")
             ),
 
+            DynamicCall(id, table) => format!(
+                "{}({}, {{{}}})", "DynamicCall".green(), 
+                ctx.functions[*id].name.magenta(), 
+                table.iter().map(|(t, i)| format!("{} -> {}", t.get_name(ctx), i.to_string(ctx))).collect::<Vec<_>>().join(", ")
+            ),
+
             NativeFunctionCallNoRet(id, ov, args) => format!(
                 "{}({}, {}, {{{}}})", "FunctionCallNoRet".green(), 
                 ctx.functions[*id].name.magenta(), 
@@ -1228,6 +1938,11 @@ impl NessaInstruction {
         self.debug_info.set_line(loc.module.clone(), loc.line);
         self
     }
+
+    pub fn set_var_name(mut self, name: &str) -> Self {
+        self.debug_info.var_name = Some(name.to_string());
+        self
+    }
 }
 
 impl NessaContext{
@@ -1256,7 +1971,8 @@ impl NessaContext{
                 deps.connect(parent.clone(), (ImportType::Class, obj.get_type_id()), ());
             }
 
-            NessaExpr::Return(_, e) => self.get_inner_dep_graph_expr(e, parent, deps),
+            NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) => self.get_inner_dep_graph_expr(e, parent, deps),
 
             NessaExpr::CompiledVariableAssignment(_, _, _, t, e) |
             NessaExpr::CompiledVariableDefinition(_, _, _, t, e) => {
@@ -1271,6 +1987,7 @@ impl NessaContext{
                 }
             }
 
+            NessaExpr::VariadicArgs(_, _, b) |
             NessaExpr::Tuple(_, b) => {
                 self.get_inner_dep_graph_body(b, parent, deps);
             }
@@ -1314,6 +2031,17 @@ impl NessaContext{
                 }
             }
 
+            NessaExpr::CompoundAssignment(_, id, a, b) => {
+                deps.connect(parent.clone(), (ImportType::Binary, *id), ());
+
+                if let Some(base_id) = compound_assignment_base(*id) {
+                    deps.connect(parent.clone(), (ImportType::Binary, base_id), ());
+                }
+
+                self.get_inner_dep_graph_expr(a, parent, deps);
+                self.get_inner_dep_graph_expr(b, parent, deps);
+            }
+
             NessaExpr::BinaryOperation(_, id, ts, a, b) => {
                 if let Operator::Binary { id, .. } = &self.binary_ops[*id] {
                     deps.connect(parent.clone(), (ImportType::Binary, *id), ());
@@ -1357,6 +2085,15 @@ impl NessaContext{
                 self.get_inner_dep_graph_body(b, parent, deps);
             }
 
+            NessaExpr::CompiledTry(_, b, _, _, t, h) => {
+                self.get_inner_dep_graph_body(b, parent, deps);
+                self.get_inner_dep_graph_body(h, parent, deps);
+
+                for td in t.type_dependencies() {
+                    deps.connect(parent.clone(), (ImportType::Class, td), ());
+                }
+            }
+
             NessaExpr::CompiledFor(_, _, _, _, c, b) => {
                 self.get_inner_dep_graph_expr(c, parent, deps);
                 self.get_inner_dep_graph_body(b, parent, deps);
@@ -1368,6 +2105,28 @@ impl NessaContext{
                 deps.connect(parent.clone(), (ImportType::Fn, IS_CONSUMED_FUNC_ID), ());
             }
 
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.get_inner_dep_graph_expr(e, parent, deps);
+
+                for (p, g, b) in arms {
+                    for t in p.types() {
+                        for td in t.type_dependencies() {
+                            deps.connect(parent.clone(), (ImportType::Class, td), ());
+                        }
+
+                        for id in t.interface_dependencies() {
+                            deps.connect(parent.clone(), (ImportType::Interface, id), ());
+                        }
+                    }
+
+                    if let Some(g_inner) = g {
+                        self.get_inner_dep_graph_expr(g_inner, parent, deps);
+                    }
+
+                    self.get_inner_dep_graph_expr(b, parent, deps);
+                }
+            }
+
             NessaExpr::If(_, ic, ib, ie, eb) => {
                 self.get_inner_dep_graph_expr(ic, parent, deps);
                 self.get_inner_dep_graph_body(ib, parent, deps);
@@ -1408,9 +2167,13 @@ impl NessaContext{
                 }
             }
 
-            NessaExpr::FunctionDefinition(_, _, id, _, args, ret, b) => {
+            NessaExpr::FunctionDefinition(_, _, id, _, args, d, _, ret, b) => {
                 self.get_inner_dep_graph_body(b, &(ImportType::Fn, *id), deps);
 
+                for default in d.iter().flatten() {
+                    self.get_inner_dep_graph_expr(default, &(ImportType::Fn, *id), deps);
+                }
+
                 for (_, t) in args {
                     for td in t.type_dependencies() {
                         deps.connect(parent.clone(), (ImportType::Class, td), ());
@@ -1558,9 +2321,19 @@ impl NessaContext{
                 }
             }
 
-            NessaExpr::InterfaceDefinition(_, _, n, _, fns, uns, bin, nary) => {
+            NessaExpr::InterfaceDefinition(_, _, n, _, p, fns, uns, bin, nary) => {
                 if let Some(t) = self.get_interface(n) {
-                    for (_, f_n, _, a, r) in fns {
+                    for c in p {
+                        deps.connect((ImportType::Interface, t.id), (ImportType::Interface, c.id), ());
+
+                        for tp in &c.args {
+                            for t_dep in tp.type_dependencies() {
+                                deps.connect((ImportType::Interface, t.id), (ImportType::Class, t_dep), ());
+                            }
+                        }
+                    }
+
+                    for (_, f_n, _, a, r, _) in fns {
                         if let Some(f) = self.get_function(f_n) {
                             deps.connect((ImportType::Interface, t.id), (ImportType::Fn, f.id), ());                        
                         }
@@ -1671,10 +2444,23 @@ impl NessaContext{
                 }
             }
 
-            NessaExpr::ClassDefinition(_, _, n, _, _, _, _) => {
+            NessaExpr::ClassDefinition(_, an, n, _, _, _, _, _) => {
                 if let Some(t) = self.get_type_template(n) {
                     deps.connect(parent.clone(), (ImportType::Class, t.id), ());
 
+                    // Derived comparisons are generated as operation definitions that come with the class
+                    for c in an.iter().filter(|i| i.name == "derive").flat_map(|i| i.args.values()) {
+                        let ops = match c.as_str() {
+                            "Eq" => vec!(EQ_BINOP_ID, NEQ_BINOP_ID),
+                            "Ord" => vec!(LT_BINOP_ID, GT_BINOP_ID, LTEQ_BINOP_ID, GTEQ_BINOP_ID),
+                            _ => vec!()
+                        };
+
+                        for id in ops {
+                            deps.connect((ImportType::Class, t.id), (ImportType::Binary, id), ());
+                        }
+                    }
+
                     // Dependencies from the attributes
                     for (_, tp) in &t.attributes {
                         for t_dep in tp.type_dependencies() {
@@ -1712,7 +2498,7 @@ impl NessaContext{
         changed: &mut bool
     ) -> Result<(), NessaError> {
         return match expr {
-            NessaExpr::FunctionDefinition(_, _, id, _, a, r, b) => {
+            NessaExpr::FunctionDefinition(_, _, id, _, a, _, _, r, b) => {
                 let arg_types = a.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
                 let and = Type::And(arg_types.clone());
 
@@ -1734,6 +2520,8 @@ impl NessaContext{
                                     for line in &body {
                                         self.static_check_expected(line, &Some(r.sub_templates(&templates)))?;
                                     }    
+
+                                    self.move_check_body(&body)?;
                                 }
 
                                 self.cache.templates.functions.insert(key, body.clone());
@@ -1770,6 +2558,8 @@ impl NessaContext{
                                     for line in &body {
                                         self.static_check_expected(line, &Some(r.sub_templates(&templates)))?;
                                     }
+
+                                    self.move_check_body(&body)?;
                                 }
 
                                 self.cache.templates.unary.insert(key, body.clone());
@@ -1805,6 +2595,8 @@ impl NessaContext{
                                     for line in &body {
                                         self.static_check_expected(line, &Some(r.sub_templates(&templates)))?;
                                     }
+
+                                    self.move_check_body(&body)?;
                                 }
 
                                 self.cache.templates.binary.insert(key, body.clone());
@@ -1846,6 +2638,8 @@ impl NessaContext{
                                     for line in &body {
                                         self.static_check_expected(line, &Some(r.sub_templates(&templates)))?;
                                     }
+
+                                    self.move_check_body(&body)?;
                                 }
 
                                 self.cache.templates.nary.insert(key, body.clone());
@@ -1885,6 +2679,11 @@ impl NessaContext{
             NessaExpr::Literal(..) |
             NessaExpr::NameReference(..) => {},
 
+            NessaExpr::VariadicArgs(_, t, e) => {
+                *t = t.sub_templates(templates);
+                e.iter_mut().for_each(|i| NessaContext::subtitute_type_params_expr(i, templates));
+            }
+
             NessaExpr::Tuple(_, e) => e.iter_mut().for_each(|i| NessaContext::subtitute_type_params_expr(i, templates)),
 
             NessaExpr::DoBlock(_, e, t) => {
@@ -1909,7 +2708,8 @@ impl NessaContext{
 
             NessaExpr::VariableAssignment(_, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
-            NessaExpr::Return(_, e) => NessaContext::subtitute_type_params_expr(e, templates),
+            NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) => NessaContext::subtitute_type_params_expr(e, templates),
 
             NessaExpr::VariableDefinition(_, _, t, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, t, e) |
@@ -1918,6 +2718,12 @@ impl NessaContext{
 
                 NessaContext::subtitute_type_params_expr(e, templates);
             },
+
+            NessaExpr::DestructuringDefinition(_, p, e) => {
+                p.sub_templates(templates);
+
+                NessaContext::subtitute_type_params_expr(e, templates);
+            },
             
             NessaExpr::UnaryOperation(_, _, t, a) => {
                 t.iter_mut().for_each(|i| *i = i.sub_templates(templates));
@@ -1957,6 +2763,28 @@ impl NessaContext{
                 body.iter_mut().for_each(|i| NessaContext::subtitute_type_params_expr(i, templates));
             },
 
+            NessaExpr::Try(_, body, _, t, handler) |
+            NessaExpr::CompiledTry(_, body, _, _, t, handler) => {
+                *t = t.sub_templates(templates);
+
+                body.iter_mut().chain(handler).for_each(|i| NessaContext::subtitute_type_params_expr(i, templates));
+            },
+
+            NessaExpr::Match(_, e, arms) |
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                NessaContext::subtitute_type_params_expr(e, templates);
+
+                for (p, g, b) in arms {
+                    p.sub_templates(templates);
+
+                    if let Some(g_inner) = g {
+                        NessaContext::subtitute_type_params_expr(g_inner, templates);
+                    }
+
+                    NessaContext::subtitute_type_params_expr(b, templates);
+                }
+            }
+
             NessaExpr::If(_, ih, ib, ei, eb) => {
                 NessaContext::subtitute_type_params_expr(ih, templates);
                 ib.iter_mut().for_each(|i| NessaContext::subtitute_type_params_expr(i, templates));
@@ -1993,7 +2821,7 @@ impl NessaContext{
             NessaExpr::BinaryOperatorDefinition(..) |
             NessaExpr::NaryOperatorDefinition(..) => Ok(()),
 
-            NessaExpr::CompiledLambda(_, i, c, a, _, b) => {
+            NessaExpr::CompiledLambda(_, i, c, a, r, b) => {
                 self.compile_lambdas(b, only_length)?;
 
                 if only_length {
@@ -2002,20 +2830,22 @@ impl NessaContext{
                     self.lambda_code_length += self.compiled_form_body_size(b, true)? + a.len() + c.len();
 
                 } else {
+                    let start = self.lambda_code.len();
+
                     for (i, e) in c.iter().enumerate() {
                         if i == 0 {
                             self.lambda_code.push(NessaInstruction::new_with_type(
                                 CompiledNessaExpr::StoreVariable(i), 
                                 "Lambda expression start".into(),
                                 self.infer_type(&e.1).unwrap()
-                            ));
+                            ).set_var_name(&e.0));
     
                         } else {
                             self.lambda_code.push(NessaInstruction::new_with_type(
                                 CompiledNessaExpr::StoreVariable(i), 
                                 String::new(),
                                 self.infer_type(&e.1).unwrap()
-                            ));
+                            ).set_var_name(&e.0));
                         }
                     }
     
@@ -2024,10 +2854,20 @@ impl NessaContext{
                             CompiledNessaExpr::StoreVariable(i + c.len()), 
                             String::new(),
                             arg.1.clone()
-                        ));
+                        ).set_var_name(&arg.0));
                     }
     
                     self.lambda_code.extend(self.compiled_form_body(b)?);
+
+                    let signature = format!(
+                        "lambda ({}) -> {}",
+                        a.iter().map(|(_, t)| t.get_name_plain(self)).collect::<Vec<_>>().join(", "),
+                        r.get_name_plain(self)
+                    );
+
+                    for i in &mut self.lambda_code[start..] {
+                        i.debug_info.functions.insert(signature.clone());
+                    }
                 }
                 
                 Ok(())
@@ -2036,6 +2876,7 @@ impl NessaContext{
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) |
             NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::UnaryOperation(_, _, _, e) => self.compile_lambda_expr(e, only_length),
 
@@ -2056,6 +2897,27 @@ impl NessaContext{
                 Ok(())
             }
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                self.compile_lambdas(b, only_length)?;
+                self.compile_lambdas(h, only_length)?;
+
+                Ok(())
+            }
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.compile_lambda_expr(e, only_length)?;
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.compile_lambda_expr(g_inner, only_length)?;
+                    }
+
+                    self.compile_lambda_expr(b, only_length)?;
+                }
+
+                Ok(())
+            }
+
             NessaExpr::If(_, ih, ib, ei, eb) => {
                 self.compile_lambda_expr(ih, only_length)?;
                 self.compile_lambdas(ib, only_length)?;
@@ -2073,6 +2935,7 @@ impl NessaContext{
             }
 
             NessaExpr::DoBlock(_, args, _) |
+            NessaExpr::VariadicArgs(_, _, args) |
             NessaExpr::Tuple(_, args) |
             NessaExpr::FunctionCall(_, _, _, args) => self.compile_lambdas(args, only_length),
 
@@ -2103,7 +2966,7 @@ impl NessaContext{
     pub fn compile_function_lambdas(&mut self, lines: &Vec<NessaExpr>, only_length: bool) -> Result<(), NessaError> {
         for expr in lines {
             match expr {
-                NessaExpr::FunctionDefinition(_, _, id, _, a, _, _) => {
+                NessaExpr::FunctionDefinition(_, _, id, _, a, _, _, _, _) => {
                     let arg_types = a.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
                     let and = Type::And(arg_types.clone());
 
@@ -2170,11 +3033,12 @@ impl NessaContext{
         self.compile_lambdas(lines, true)?;
 
         let mut program_size = 1 + self.lambda_code_length;
+        let mut function_ranges = vec!();
 
         // Define function indexes
         for expr in lines {
             match expr {
-                NessaExpr::FunctionDefinition(_, _, id, t, a, ret, _) => {
+                NessaExpr::FunctionDefinition(_, _, id, t, a, _, _, ret, _) => {
                     let arg_types = a.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
                     let and = Type::And(arg_types.clone());
 
@@ -2201,7 +3065,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
         
                                     } else {                                
                                         let signature = format!(
@@ -2216,7 +3081,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
                                     }    
                                 }
                             }
@@ -2252,7 +3118,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
 
                                     } else {                                
                                         let signature = format!(
@@ -2266,7 +3133,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
                                     }    
                                 }
                             }
@@ -2297,7 +3165,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
 
                                     } else {                                
                                         let signature = format!(
@@ -2311,7 +3180,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
                                     }    
                                 }
                             }
@@ -2356,7 +3226,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
         
                                     } else {
                                         let signature = format!(
@@ -2373,7 +3244,8 @@ impl NessaContext{
                                             ret.get_name_plain(self)
                                         );
 
-                                        self.cache.ranges.insert(signature, (init_loc, program_size));
+                                        self.cache.ranges.insert(signature.clone(), (init_loc, program_size));
+                                        function_ranges.push((signature, init_loc, program_size));
                                     }    
                                 }
                             }
@@ -2395,7 +3267,7 @@ impl NessaContext{
         // Define functions
         for expr in lines {
             match expr {
-                NessaExpr::FunctionDefinition(_, _, id, _, a, r, _) => {
+                NessaExpr::FunctionDefinition(_, _, id, _, a, _, _, r, _) => {
                     let arg_types = a.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
                     let and = Type::And(arg_types.clone());
 
@@ -2417,14 +3289,14 @@ impl NessaContext{
                                             CompiledNessaExpr::StoreVariable(i), 
                                             comment,
                                             arg.clone()
-                                        ));
+                                        ).set_var_name(&a[i].0));
 
                                     } else {
                                         res.push(NessaInstruction::new_with_type(
                                             CompiledNessaExpr::StoreVariable(i),
                                             String::new(),
                                             arg.clone()
-                                        ));
+                                        ).set_var_name(&a[i].0));
                                     }
                                 }
 
@@ -2435,8 +3307,8 @@ impl NessaContext{
                     }
                 },
 
-                NessaExpr::PrefixOperationDefinition(_, _, id, _, _, tp, r, _) |
-                NessaExpr::PostfixOperationDefinition(_, _, id, _, _, tp, r, _) => {
+                NessaExpr::PrefixOperationDefinition(_, _, id, _, n, tp, r, _) |
+                NessaExpr::PostfixOperationDefinition(_, _, id, _, n, tp, r, _) => {
                     if let Some(usages) = self.cache.usages.unary.get_checked(id) {
                         for (args, ov) in usages {
                             if Type::And(args.clone()).bindable_to(tp, self) {
@@ -2460,7 +3332,7 @@ impl NessaContext{
                                     CompiledNessaExpr::StoreVariable(0), 
                                     comment,
                                     args[0].clone()
-                                ));
+                                ).set_var_name(n));
     
                                 let sub_b = self.cache.templates.unary.get_checked(&(*id, ov.clone(), vec!(tp.clone()))).unwrap();
                                 res.extend(self.compiled_form_body(&sub_b)?);
@@ -2469,7 +3341,7 @@ impl NessaContext{
                     }
                 },
 
-                NessaExpr::BinaryOperationDefinition(_, _, id, _, (_, t1), (_, t2), r, _) => {
+                NessaExpr::BinaryOperationDefinition(_, _, id, _, (n1, t1), (n2, t2), r, _) => {
                     let and = Type::And(vec!(t1.clone(), t2.clone()));
 
                     if let Some(usages) = self.cache.usages.binary.get_checked(id) {
@@ -2488,13 +3360,13 @@ impl NessaContext{
                                     CompiledNessaExpr::StoreVariable(0), 
                                     comment,
                                     args[0].clone()
-                                ));
+                                ).set_var_name(n1));
 
                                 res.push(NessaInstruction::new_with_type(
                                     CompiledNessaExpr::StoreVariable(1),
                                     String::new(),
                                     args[1].clone()
-                                ));
+                                ).set_var_name(n2));
     
                                 let sub_b = self.cache.templates.binary.get_checked(&(*id, ov.clone(), vec!(t1.clone(), t2.clone()))).unwrap();
                                 res.extend(self.compiled_form_body(&sub_b)?);
@@ -2503,7 +3375,7 @@ impl NessaContext{
                     }
                 },
 
-                NessaExpr::NaryOperationDefinition(_, _, id, _, (_, a_t), a, r, _) => {
+                NessaExpr::NaryOperationDefinition(_, _, id, _, (a_n, a_t), a, r, _) => {
                     let mut arg_types = vec!(a_t.clone());
                     arg_types.extend(a.iter().map(|(_, t)| t).cloned());
 
@@ -2536,14 +3408,14 @@ impl NessaContext{
                                             CompiledNessaExpr::StoreVariable(i), 
                                             comment,
                                             arg.clone()
-                                        ));
+                                        ).set_var_name(a_n));
     
                                     } else {
                                         res.push(NessaInstruction::new_with_type(
                                             CompiledNessaExpr::StoreVariable(i),
                                             String::new(),
                                             arg.clone()
-                                        ));
+                                        ).set_var_name(&a[i - 1].0));
                                     }
                                 }
     
@@ -2576,6 +3448,13 @@ impl NessaContext{
 
         res.push(NessaInstruction::new(CompiledNessaExpr::Halt, "End of the program".into()));
 
+        // Tag the code of every function so execution errors can be traced back to it
+        for (signature, start, end) in function_ranges {
+            for i in &mut res[start..end] {
+                i.debug_info.functions.insert(signature.clone());
+            }
+        }
+
         Ok(res)
     }
 
@@ -2669,7 +3548,17 @@ impl NessaContext{
                 Ok(res)
             },
 
-            CompiledFor(_, _, _, _, c, b) => Ok(self.compiled_form_size(c, false, root_counter)? + self.compiled_form_body_size(b, true)? + 9),
+            CompiledFor(_, _, _, _, c, b) => {
+                if let (true, BinaryOperation(_, _, _, from, to)) = (self.is_counting_loop(c)?, c.as_ref()) {
+                    let needs_copy = self.infer_type(from)?.is_ref() as usize + self.infer_type(to)?.is_ref() as usize;
+
+                    return Ok(self.compiled_form_size(from, false, root_counter)? + self.compiled_form_size(to, false, root_counter)? + self.compiled_form_body_size(b, true)? + 11 + needs_copy);
+                }
+
+                Ok(self.compiled_form_size(c, false, root_counter)? + self.compiled_form_body_size(b, true)? + 9)
+            },
+
+            CompiledTry(_, b, _, _, _, h) => Ok(self.compiled_form_body_size(b, true)? + self.compiled_form_body_size(h, true)? + 4),
 
             While(_, c, b) => {
                 let needs_deref = self.infer_type(c).unwrap().is_ref();
@@ -2683,6 +3572,32 @@ impl NessaContext{
                 Ok(self.compiled_form_body_size(b, true)?)
             }
 
+            CompiledPropagate(_, _, e) => {
+                *root_counter += root as usize; // Add drop instruction
+
+                Ok(self.compiled_form_size(e, false, root_counter)? + 8)
+            }
+
+            CompiledMatch(l, id, e, arms) => {
+                *root_counter += root as usize; // Add drop instruction
+
+                let (value_type, access) = self.match_access(l, *id, e)?;
+                let mut res = self.compiled_form_size(e, false, root_counter)? + 1;
+
+                for (p, g, b) in arms {
+                    let (tests, _, binds) = self.compiled_form_pattern(p, &value_type, &access, l)?;
+
+                    res += tests.len() + binds.len() + self.compiled_form_size(b, false, root_counter)? + 1 + self.pushes_no_value(b)? as usize;
+
+                    if let Some(g_inner) = g {
+                        let needs_deref = self.infer_type(g_inner)?.is_ref();
+                        res += self.compiled_form_size(g_inner, false, root_counter)? + 1 + needs_deref as usize;
+                    }
+                }
+
+                Ok(res)
+            }
+
             Tuple(_, b) => {            
                 *root_counter += root as usize; // Add drop instruction
 
@@ -2694,7 +3609,12 @@ impl NessaContext{
 
                 let args_types = a.iter().map(|i| self.infer_type(i)).collect::<Result<Vec<_>, _>>()?;
                 
-                let ov_id = self.cache.overloads.functions.get_checked(&(*id, args_types.clone(), t.clone())).unwrap();
+                // Dynamic calls take a single instruction
+                let ov_id = match self.cache.overloads.functions.get_checked(&(*id, args_types.clone(), t.clone())) {
+                    Some(ov_id) => ov_id,
+                    None => return Ok(self.compiled_form_body_size(a, false)? + 1)
+                };
+
                 let (opcode, offset) = self.cache.opcodes.functions.get_checked(&(*id, ov_id)).unwrap_or((CompiledNessaExpr::Halt, 0));
 
                 if root && opcode.needs_no_drop() {
@@ -2708,12 +3628,136 @@ impl NessaContext{
         }
     }
 
+    // Checks if the compiled form of an expression leaves no value on the stack (assignments and increments)
+    fn pushes_no_value(&self, expr: &NessaExpr) -> Result<bool, NessaError> {
+        let opcode = match expr {
+            NessaExpr::BinaryOperation(_, id, t, a, b) => {
+                let arg_types = vec!(self.infer_type(a)?, self.infer_type(b)?);
+                let ov_id = self.cache.overloads.binary.get_checked(&(*id, arg_types, t.clone())).unwrap();
+
+                self.cache.opcodes.binary.get_checked(&(*id, ov_id))
+            },
+
+            NessaExpr::FunctionCall(_, id, t, a) => {
+                let arg_types = a.iter().map(|i| self.infer_type(i)).collect::<Result<Vec<_>, _>>()?;
+
+                self.cache.overloads.functions.get_checked(&(*id, arg_types, t.clone()))
+                                              .and_then(|ov_id| self.cache.opcodes.functions.get_checked(&(*id, ov_id)))
+            },
+
+            _ => None
+        };
+
+        Ok(opcode.map(|(i, _)| i.needs_no_drop()).unwrap_or(false))
+    }
+
+    // Type of the reference patterns are checked against and the instructions that push it
+    fn match_access(&self, l: &Location, id: usize, e: &NessaExpr) -> Result<(Type, Vec<NessaInstruction>), NessaError> {
+        let var = NessaExpr::Variable(l.clone(), id, String::new(), self.infer_type(e)?);
+
+        Ok((self.infer_type(&var)?, self.compiled_form_expr(&var, false)?))
+    }
+
+    // Instructions that check a pattern, indices of their failure jumps and instructions that store its bindings
+    fn compiled_form_pattern(&self, pattern: &MatchPattern, t: &Type, access: &Vec<NessaInstruction>, l: &Location) -> Result<MatchPatternCode, NessaError> {
+        let mut tests = vec!();
+        let mut fails = vec!();
+        let mut binds = vec!();
+
+        let mut type_test = |tests: &mut Vec<NessaInstruction>, pt: &Type| {
+            // Skip checks that always succeed
+            if !t.deref_type().bindable_to(pt, self) {
+                tests.extend(access.iter().cloned());
+                tests.push(NessaInstruction::from(CompiledNessaExpr::MatchType(pt.clone())).set_loc(l));
+                fails.push(tests.len());
+                tests.push(NessaInstruction::from(CompiledNessaExpr::RelativeJumpIfFalse(0, false)).set_loc(l));
+            }
+        };
+
+        match pattern {
+            MatchPattern::Wildcard => {},
+
+            MatchPattern::Literal(obj) => {
+                tests.extend(access.iter().cloned());
+                tests.extend(self.compiled_form_expr(&NessaExpr::Literal(l.clone(), obj.clone()), false)?);
+                tests.push(NessaInstruction::from(CompiledNessaExpr::MatchValue).set_loc(l));
+                fails.push(tests.len());
+                tests.push(NessaInstruction::from(CompiledNessaExpr::RelativeJumpIfFalse(0, false)).set_loc(l));
+            },
+
+            MatchPattern::Type(pt) => type_test(&mut tests, pt),
+
+            MatchPattern::Class(pt, p) => {
+                type_test(&mut tests, pt);
+
+                let class_type = NessaContext::match_ref_type(t, pt.clone());
+                let attributes = self.match_class_attributes(pt).unwrap();
+
+                for (i, (sub, att)) in p.iter().zip(&attributes).enumerate() {
+                    let mut sub_access = access.clone();
+
+                    sub_access.push(NessaInstruction::from(match (&class_type, att) {
+                        (Type::MutRef(_), Type::Ref(_)) | (Type::Ref(_), _) => CompiledNessaExpr::AttributeRef(i),
+                        (_, _) => CompiledNessaExpr::AttributeMut(i)
+                    }).set_loc(l));
+
+                    let (sub_tests, sub_fails, sub_binds) = self.compiled_form_pattern(sub, &NessaContext::match_access_type(&class_type, att), &sub_access, l)?;
+
+                    fails.extend(sub_fails.into_iter().map(|i| i + tests.len()));
+                    tests.extend(sub_tests);
+                    binds.extend(sub_binds);
+                }
+            },
+
+            MatchPattern::Tuple(p) => {
+                let elements = self.match_tuple_elements(t, p.len()).unwrap();
+                let tuple_type = NessaContext::match_ref_type(t, Type::And(elements.clone()));
+
+                type_test(&mut tests, tuple_type.deref_type());
+
+                for (i, (sub, elem)) in p.iter().zip(&elements).enumerate() {
+                    let mut sub_access = access.clone();
+
+                    sub_access.push(NessaInstruction::from(match (&tuple_type, elem) {
+                        (Type::MutRef(_), Type::Ref(_)) | (Type::Ref(_), _) => CompiledNessaExpr::TupleElemRef(i),
+                        (_, _) => CompiledNessaExpr::TupleElemMut(i)
+                    }).set_loc(l));
+
+                    let (sub_tests, sub_fails, sub_binds) = self.compiled_form_pattern(sub, &NessaContext::match_access_type(&tuple_type, elem), &sub_access, l)?;
+
+                    fails.extend(sub_fails.into_iter().map(|i| i + tests.len()));
+                    tests.extend(sub_tests);
+                    binds.extend(sub_binds);
+                }
+            },
+
+            MatchPattern::CompiledBinding(id, n, bt, p) => {
+                let (sub_tests, sub_fails, sub_binds) = self.compiled_form_pattern(p, t, access, l)?;
+
+                tests = sub_tests;
+                fails = sub_fails;
+                binds = sub_binds;
+
+                binds.extend(access.iter().cloned());
+                binds.push(NessaInstruction::new_with_type(CompiledNessaExpr::StoreVariable(*id), String::new(), bt.clone()).set_loc(l).set_var_name(n));
+            },
+
+            MatchPattern::Binding(..) => unreachable!()
+        }
+
+        Ok((tests, fails, binds))
+    }
+
     pub fn compiled_form_body_size(&self, lines: &Vec<NessaExpr>, root: bool) -> Result<usize, NessaError> {
         let mut counter = 0;
         let mut res = 0;
 
         for i in lines {
             res += self.compiled_form_size(i, root, &mut counter)?;
+
+            if root && !i.propagations().is_empty() {
+                res += 2; // Stack base of the line
+            }
         }
 
         Ok(res + counter)
@@ -2792,6 +3836,75 @@ impl NessaContext{
         }
     }
 
+    // Loops over native integer ranges are compiled into counting loops with no iterator
+    pub fn is_counting_loop(&self, container: &NessaExpr) -> Result<bool, NessaError> {
+        if let NessaExpr::BinaryOperation(_, RANGE_BINOP_ID | RANGE_INCL_BINOP_ID, t, a, b) = container {
+            return Ok(t.is_empty() && *self.infer_type(a)?.deref_type() == INT && *self.infer_type(b)?.deref_type() == INT);
+        }
+
+        Ok(false)
+    }
+
+    fn compiled_form_counting_loop(&self, l: &Location, end_var_id: usize, counter_var_id: usize, container: &NessaExpr, body: &[NessaExpr]) -> Result<Vec<NessaInstruction>, NessaError> {
+        let (id, from, to) = match container {
+            NessaExpr::BinaryOperation(_, id, _, a, b) => (*id, a, b),
+            _ => unreachable!()
+        };
+
+        let mut res = vec!();
+
+        // Store copies of the bounds
+        for (bound, var_id) in [(from, counter_var_id), (to, end_var_id)] {
+            res.extend(self.compiled_form_expr(bound, false)?);
+
+            if self.infer_type(bound)?.is_ref() {
+                res.push(NessaInstruction::from(CompiledNessaExpr::Copy).set_loc(l));
+            }
+
+            res.push(NessaInstruction::from(CompiledNessaExpr::StoreVariable(var_id)).set_loc(l));
+        }
+
+        let for_body = self.compiled_form_body(body)?;
+        let for_body_len = for_body.len();
+
+        // Compare the counter with the end
+        res.push(NessaInstruction::from(CompiledNessaExpr::GetVariable(end_var_id)).set_loc(l));
+        res.push(NessaInstruction::from(CompiledNessaExpr::Deref).set_loc(l));
+        res.push(NessaInstruction::from(CompiledNessaExpr::GetVariable(counter_var_id)).set_loc(l));
+        res.push(NessaInstruction::from(CompiledNessaExpr::Deref).set_loc(l));
+
+        if id == RANGE_INCL_BINOP_ID {
+            res.push(NessaInstruction::from(CompiledNessaExpr::Lteqi).set_loc(l));
+
+        } else {
+            res.push(NessaInstruction::from(CompiledNessaExpr::Lti).set_loc(l));
+        }
+
+        // Jump to end of loop
+        res.push(NessaInstruction::from(CompiledNessaExpr::RelativeJumpIfFalse(for_body_len + 4, false)));
+
+        res.extend(for_body);
+
+        // Increment the counter and jump to the beginning of the loop
+        res.push(NessaInstruction::from(CompiledNessaExpr::GetVariable(counter_var_id)).set_loc(l));
+        res.push(NessaInstruction::from(CompiledNessaExpr::Inc).set_loc(l));
+        res.push(NessaInstruction::from(CompiledNessaExpr::RelativeJump(-(for_body_len as i32 + 8))));
+
+        // Transform breaks and continues into relative jumps
+        let length = res.len();
+
+        for (idx, i) in res.iter_mut().enumerate() {
+            if let CompiledNessaExpr::Placeholder(PlaceholderType::Break) = i.instruction {
+                i.instruction = CompiledNessaExpr::RelativeJump((length - idx) as i32);
+
+            } else if let CompiledNessaExpr::Placeholder(PlaceholderType::Continue) = i.instruction {
+                i.instruction = CompiledNessaExpr::RelativeJump((length - idx) as i32 - 3);
+            }
+        }
+
+        Ok(res)
+    }
+
     pub fn compiled_form_expr(
         &self, expr: &NessaExpr,
         root: bool
@@ -2895,6 +4008,75 @@ impl NessaContext{
                 Ok(res)
             }
 
+            NessaExpr::CompiledMatch(l, id, e, arms) => {
+                let (value_type, access) = self.match_access(l, *id, e)?;
+
+                let mut res = self.compiled_form_expr(e, false)?;
+                res.push(NessaInstruction::new_with_type(CompiledNessaExpr::StoreVariable(*id), String::new(), self.infer_type(e)?).set_loc(l));
+
+                let mut arms_code = vec!();
+
+                for (p, g, b) in arms {
+                    let (mut code, mut fails, binds) = self.compiled_form_pattern(p, &value_type, &access, l)?;
+                    code.extend(binds);
+
+                    if let Some(g_inner) = g {
+                        code.extend(self.compiled_form_expr(g_inner, false)?);
+
+                        if self.infer_type(g_inner)?.is_ref() {
+                            code.push(NessaInstruction::from(CompiledNessaExpr::Deref).set_loc(l));
+                        }
+
+                        fails.push(code.len());
+                        code.push(NessaInstruction::from(CompiledNessaExpr::RelativeJumpIfFalse(0, false)).set_loc(l));
+                    }
+
+                    code.extend(self.compiled_form_expr(b, false)?);
+
+                    if self.pushes_no_value(b)? {
+                        code.push(NessaInstruction::from(CompiledNessaExpr::Empty).set_loc(l));
+                    }
+
+                    // Failed checks jump to the next arm
+                    for i in fails {
+                        code[i].instruction = CompiledNessaExpr::RelativeJumpIfFalse(code.len() + 1 - i, false);
+                    }
+
+                    arms_code.push(code);
+                }
+
+                let mut remaining = arms_code.iter().map(|i| i.len() + 1).sum::<usize>();
+
+                for code in arms_code {
+                    remaining -= code.len() + 1;
+
+                    res.extend(code);
+                    res.push(NessaInstruction::from(CompiledNessaExpr::RelativeJump(remaining as i32 + 1)).set_loc(l));
+                }
+
+                if root { // Drop if the return value is unused
+                    res.push(NessaInstruction::from(CompiledNessaExpr::Drop).set_loc(l));
+                }
+
+                Ok(res)
+            }
+
+            NessaExpr::VariadicArgs(l, t, e) => {
+                let mut res = vec!();
+
+                for i in e.iter().rev() {
+                    res.extend(self.compiled_form_expr(i, false)?);
+                }
+
+                res.push(NessaInstruction::from(CompiledNessaExpr::Array(e.len(), t.clone())).set_loc(l));
+                
+                if root { // Drop if the return value is unused
+                    res.push(NessaInstruction::from(CompiledNessaExpr::Drop).set_loc(l));
+                }
+
+                Ok(res)
+            }
+
             NessaExpr::Tuple(l, e) => {
                 let mut res = vec!();
 
@@ -2941,13 +4123,13 @@ impl NessaContext{
                 Ok(res)
             }
 
-            NessaExpr::CompiledVariableDefinition(l, id, _, t, e) | NessaExpr::CompiledVariableAssignment(l, id, _, t, e) => {
+            NessaExpr::CompiledVariableDefinition(l, id, n, t, e) | NessaExpr::CompiledVariableAssignment(l, id, n, t, e) => {
                 let mut res = self.compiled_form_expr(e, false)?;
                 res.push(NessaInstruction::new_with_type(
                     CompiledNessaExpr::StoreVariable(*id),
                     String::new(),
                     t.clone()
-                ).set_loc(l));
+                ).set_loc(l).set_var_name(n));
 
                 Ok(res)
             },
@@ -3025,6 +4207,9 @@ impl NessaContext{
                             if b_t.is_ref() {
                                 res_a.push(NessaInstruction::from(CompiledNessaExpr::Deref).set_loc(l));
                             }
+
+                        } else if opcode.is_in_place() && b_t.is_ref() {
+                            res_a.push(NessaInstruction::from(CompiledNessaExpr::Deref).set_loc(l));
                         }
     
                         // Convert to float if necessary
@@ -3192,6 +4377,10 @@ impl NessaContext{
             },
 
             NessaExpr::CompiledFor(l, it_var_id, elem_var_id, _, c, b) => {
+                if self.is_counting_loop(c)? {
+                    return self.compiled_form_counting_loop(l, *it_var_id, *elem_var_id, c, b);
+                }
+
                 let t = self.infer_type(c)?;
 
                 let mut res = self.compiled_form_expr(c, false)?;
@@ -3275,9 +4464,62 @@ impl NessaContext{
                 }
             },
 
-            NessaExpr::Return(l, e) => {
+            NessaExpr::CompiledTry(l, b, error_idx, n, t, h) => {
+                let try_body = self.compiled_form_body(b)?;
+                let handler_body = self.compiled_form_body(h)?;
+
+                // The handler receives the error on the stack and starts right after the jump that skips it
+                let mut res = vec!(NessaInstruction::from(CompiledNessaExpr::Try(try_body.len() + 3)).set_loc(l));
+                res.extend(try_body);
+                res.push(NessaInstruction::from(CompiledNessaExpr::EndTry).set_loc(l));
+                res.push(NessaInstruction::from(CompiledNessaExpr::RelativeJump(handler_body.len() as i32 + 2)));
+
+                res.push(NessaInstruction::new_with_type(
+                    CompiledNessaExpr::StoreVariable(*error_idx),
+                    String::new(),
+                    t.clone()
+                ).set_loc(l).set_var_name(n));
+
+                res.extend(handler_body);
+
+                Ok(res)
+            },
+
+            NessaExpr::Return(l, e) => {
+                let mut res = self.compiled_form_expr(e, false)?;
+                res.push(NessaInstruction::from(CompiledNessaExpr::Return).set_loc(l));
+
+                Ok(res)
+            },
+
+            NessaExpr::CompiledPropagate(l, value_idx, e) => {
+                let value_type = self.infer_type(e)?;
+                let (failure_type, _) = value_type.propagation_split().unwrap();
+
+                // Failures are returned by value
+                let failure_access = if value_type.is_ref() {
+                    CompiledNessaExpr::CopyVariable(*value_idx)
+
+                } else {
+                    CompiledNessaExpr::CloneVariable(*value_idx)
+                };
+
                 let mut res = self.compiled_form_expr(e, false)?;
-                res.push(NessaInstruction::from(CompiledNessaExpr::Return).set_loc(l));
+
+                res.extend(vec!(
+                    NessaInstruction::from(CompiledNessaExpr::StoreVariable(*value_idx)),
+                    NessaInstruction::from(CompiledNessaExpr::CloneVariable(*value_idx)),
+                    NessaInstruction::from(CompiledNessaExpr::MatchType(failure_type)),
+                    NessaInstruction::from(CompiledNessaExpr::RelativeJumpIfFalse(4, false)),
+                    NessaInstruction::from(failure_access),
+                    NessaInstruction::from(CompiledNessaExpr::ClearStack),
+                    NessaInstruction::from(CompiledNessaExpr::Return),
+                    NessaInstruction::from(CompiledNessaExpr::CloneVariable(*value_idx))
+                ).into_iter().map(|i| i.set_loc(l)));
+
+                if root { // Drop if the return value is unused
+                    res.push(NessaInstruction::from(CompiledNessaExpr::Drop).set_loc(l));
+                }
 
                 Ok(res)
             },
@@ -3291,7 +4533,28 @@ impl NessaContext{
                 
                 let args_types = a.iter().map(|i| self.infer_type(i)).collect::<Result<Vec<_>, _>>()?;
                 
-                let ov_id = self.cache.overloads.functions.get_checked(&(*id, args_types.clone(), t.clone())).unwrap();
+                let ov_id = match self.cache.overloads.functions.get_checked(&(*id, args_types.clone(), t.clone())) {
+                    Some(ov_id) => ov_id,
+
+                    // Dynamic call, resolved using the runtime type of the first argument
+                    None => {
+                        let table = self.get_dispatch_table(*id, &args_types, l)?.into_iter().map(|(rt, args, ov_id, tm)| {
+                            match self.cache.locations.functions.get_checked(&(*id, args, tm.clone())) {
+                                Some(pos) => (rt, CompiledNessaExpr::Call(pos)),
+                                None => (rt, CompiledNessaExpr::NativeFunctionCall(*id, ov_id, tm))
+                            }
+                        }).collect();
+
+                        res.push(NessaInstruction::from(CompiledNessaExpr::DynamicCall(*id, table)).set_loc(l));
+
+                        if root { // Drop if the return value is unused
+                            res.push(NessaInstruction::from(CompiledNessaExpr::Drop).set_loc(l));
+                        }
+
+                        return Ok(res);
+                    }
+                };
+
                 let mut translated_opcode = CompiledNessaExpr::Halt; // Invalid opcode for now
 
                 if let Some(pos) = self.cache.locations.functions.get_checked(&(*id, args_types, t.clone())) {
@@ -3331,7 +4594,25 @@ impl NessaContext{
     pub fn compiled_form_body(
         &self, lines: &[NessaExpr]
     ) -> Result<Vec<NessaInstruction>, NessaError> {
-        return Ok(lines.iter().map(|i| self.compiled_form_expr(i, true)).flat_map(|i| i.unwrap()).collect());
+        let mut res = vec!();
+
+        for line in lines {
+            let code = self.compiled_form_expr(line, true)?;
+
+            // Values propagated by ? are returned without the operands that the line left on the stack
+            if line.propagations().is_empty() {
+                res.extend(code);
+
+            } else {
+                let l = line.get_location();
+
+                res.push(NessaInstruction::from(CompiledNessaExpr::StackBase(code.len() + 1)).set_loc(l));
+                res.extend(code);
+                res.push(NessaInstruction::from(CompiledNessaExpr::EndStackBase).set_loc(l));
+            }
+        }
+
+        Ok(res)
     }
 
     pub fn define_module_macro(&mut self, definition: NessaExpr, defined_macros: &mut FxHashSet<Location>) -> Result<bool, NessaError> {
@@ -3361,7 +4642,7 @@ impl NessaContext{
 
     pub fn define_module_class(&mut self, definition: NessaExpr) -> Result<(), NessaError> {
         match definition {
-            NessaExpr::ClassDefinition(l, an, n, t, a, al, p) => {
+            NessaExpr::ClassDefinition(l, an, n, t, a, al, p, is_enum) => {
                 let err = self.implicit_syntax_check(&n, &t, &a, &p);
 
                 if let Err(msg) = err {
@@ -3370,35 +4651,34 @@ impl NessaContext{
 
                 let n_templates = t.len();
                 let arg_types = a.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
+                let enum_type = al.clone().filter(|_| is_enum);
 
-                let err = if self.get_type_template(&n).is_some() {
-                    self.redefine_type(l.clone(), an.clone(), n.clone(), t, a.clone(), al, p, Some(
-                        |ctx, c_type, s| {
-                            if let Ok((_, o)) = ctx.parse_literal_type(c_type, Span::new(s.as_str()), &RefCell::default()) {
+                // Only classes with literal syntaxes can be parsed, and the whole string has to match
+                let parser: Option<ParsingFunction> = if p.is_empty() { None } else { Some(
+                    |ctx, c_type, s| {
+                        if let Ok((rest, o)) = ctx.parse_literal_type(c_type, Span::new(s.as_str()), &RefCell::default()) {
+                            if rest.is_empty() {
                                 return Ok(o);
                             }
-    
-                            Err(format!("Unable to parse {} from {}", c_type.name, s))
                         }
-                    ))
+
+                        Err(format!("Unable to parse {} from {}", c_type.name, s))
+                    }
+                )};
+
+                let err = if self.get_type_template(&n).is_some_and(|t| !self.is_standard_type(t.id)) {
+                    self.redefine_type(l.clone(), an.clone(), n.clone(), t, a.clone(), al, p, parser)
 
                 } else {
-                    self.define_type(l.clone(), an.clone(), n.clone(), t, a.clone(), al, p, Some(
-                        |ctx, c_type, s| {
-                            if let Ok((_, o)) = ctx.parse_literal_type(c_type, Span::new(s.as_str()), &RefCell::default()) {
-                                return Ok(o);
-                            }
-    
-                            Err(format!("Unable to parse {} from {}", c_type.name, s))
-                        }
-                    ))
+                    self.define_user_type(l.clone(), an.clone(), n.clone(), t, a.clone(), al, p, parser)
                 };
 
                 if let Err(msg) = err {
                     return Err(NessaError::compiler_error(msg, &l, vec!()));
                 }
 
-                let func_id = self.define_function(n.clone()).unwrap_or_default(); // Define constructor function
+                // Define constructor function (it might already exist if the class shadows a standard type)
+                let func_id = self.define_function(n.clone()).or_else(|_| self.get_function_id(n.clone())).unwrap();
                 let class_id = self.get_type_id(n).unwrap();
 
                 if n_templates == 0 {
@@ -3445,6 +4725,13 @@ impl NessaContext{
                         self.cache.opcodes.functions.insert((func_id, res.unwrap()), (CompiledNessaExpr::Construct(class_id, a.len(), vec!()), 0));
                     }
                 }
+
+                if let Some(tp) = enum_type {
+                    self.define_enum_operations(tp, n_templates).map_err(|msg| NessaError::compiler_error(msg, &l, vec!()))?;
+                }
+
+                let derived = an.iter().filter(|i| i.name == "derive").flat_map(|i| i.args.values()).collect::<Vec<_>>();
+                self.define_derived_operations(class_id, n_templates, &derived).map_err(|msg| NessaError::compiler_error(msg, &l, vec!()))?;
             },
 
             _ => unreachable!()
@@ -3453,6 +4740,304 @@ impl NessaContext{
         Ok(())
     }
 
+    // Enums are printed and compared structurally
+    fn define_enum_operations(&mut self, tp: Type, n_templates: usize) -> Result<(), String> {
+        let print_id = self.get_function_id("print".into())?;
+        let forms = [tp.clone(), tp.clone().to_ref().or(tp.to_mut())];
+
+        for f in &forms {
+            self.define_native_function_overload(print_id, n_templates, std::slice::from_ref(f), Type::Empty, |_, _, v, ctx| {
+                print!("{}", v[0].to_display_string(ctx));
+
+                Ok(Object::empty())
+            })?;
+
+            self.define_interface_impl("Printable".into(), vec!(), f.clone(), vec!())?;
+        }
+
+        for a in &forms {
+            for b in &forms {
+                self.define_native_binary_operation(EQ_BINOP_ID, n_templates, a.clone(), b.clone(), BOOL, |_, _, a, b, _| {
+                    Ok(Object::new(a.deref_if_ref() == b.deref_if_ref()))
+                })?;
+
+                self.define_native_binary_operation(NEQ_BINOP_ID, n_templates, a.clone(), b.clone(), BOOL, |_, _, a, b, _| {
+                    Ok(Object::new(a.deref_if_ref() != b.deref_if_ref()))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Derived comparisons are lowered to the operators of the attributes, so their own definitions are used
+    pub fn derived_operation_definitions(&mut self, class: &NessaExpr) -> Result<Vec<NessaExpr>, NessaError> {
+        let mut res = vec!();
+
+        if let NessaExpr::ClassDefinition(l, an, n, tm, atts, _, _, _) = class {
+            let derived = an.iter().filter(|i| i.name == "derive").collect::<Vec<_>>();
+            let derives = |c: &str| derived.iter().any(|i| i.args.values().any(|v| v == c));
+
+            if !derives("Eq") && !derives("Ord") {
+                return Ok(res);
+            }
+
+            // Unsupported attributes are reported before the generated code fails to compile
+            for a in &derived {
+                self.check_derive_annotation(a, atts, l).map_err(|msg| NessaError::compiler_error(msg, l, vec!()))?;
+            }
+
+            // Generated names must not be confused with attribute names
+            let fresh_name = |base: &str| {
+                let mut name = base.to_string();
+
+                while atts.iter().any(|(att_n, _)| *att_n == name) {
+                    name.push('_');
+                }
+
+                name
+            };
+
+            let (a_name, b_name) = (fresh_name("a"), fresh_name("b"));
+            let idx_name = fresh_name("i");
+
+            let class_id = self.get_type_id(n.clone()).unwrap();
+            let tp = if tm.is_empty() { Type::Basic(class_id) } else { Type::Template(class_id, (0..tm.len()).map(|i| Type::TemplateParam(i, vec!())).collect()) };
+            let forms = [tp.clone(), tp.clone().to_ref(), tp.to_mut()];
+
+            // Operands are compared through immutable references, so the operators defined for them are found
+            let demut_id = self.get_function_id("demut".into()).unwrap();
+
+            let operand = |name: &String, form: &Type| {
+                let var = NessaExpr::NameReference(l.clone(), name.clone());
+
+                match form {
+                    Type::Ref(_) => var,
+                    _ => NessaExpr::FunctionCall(l.clone(), demut_id, vec!(), vec!(var))
+                }
+            };
+
+            let attributes = |a: &NessaExpr, b: &NessaExpr| atts.iter().map(|(att_n, t)| {
+                let attribute = |obj: &NessaExpr| NessaExpr::BinaryOperation(
+                    l.clone(), DOT_BINOP_ID, vec!(), 
+                    Box::new(obj.clone()), 
+                    Box::new(NessaExpr::NameReference(l.clone(), att_n.clone()))
+                );
+
+                (t.clone(), attribute(a), attribute(b))
+            }).collect::<Vec<_>>();
+
+            let mut ops = vec!();
+
+            if derives("Eq") {
+                ops.extend([EQ_BINOP_ID, NEQ_BINOP_ID]);
+            }
+
+            if derives("Ord") {
+                ops.extend([LT_BINOP_ID, GT_BINOP_ID, LTEQ_BINOP_ID, GTEQ_BINOP_ID]);
+            }
+
+            for op_id in ops {
+                for a in &forms {
+                    for b in &forms {
+                        if let Err(msg) = self.define_binary_operation(l.clone(), vec!(), op_id, tm.len(), a.clone(), b.clone(), BOOL, None) {
+                            return Err(NessaError::compiler_error(msg, l, vec!()));
+                        }
+
+                        let (obj_a, obj_b) = (operand(&a_name, a), operand(&b_name, b));
+
+                        let body = match op_id {
+                            EQ_BINOP_ID => self.derived_equality(l, attributes(&obj_a, &obj_b), &idx_name, 0),
+                            NEQ_BINOP_ID => NessaExpr::UnaryOperation(l.clone(), NOT_UNOP_ID, vec!(), Box::new(self.derived_equality(l, attributes(&obj_a, &obj_b), &idx_name, 0))),
+                            LT_BINOP_ID => self.derived_ordering(l, attributes(&obj_a, &obj_b), true, &idx_name, 0),
+                            GT_BINOP_ID => self.derived_ordering(l, attributes(&obj_b, &obj_a), true, &idx_name, 0),
+                            LTEQ_BINOP_ID => self.derived_ordering(l, attributes(&obj_a, &obj_b), false, &idx_name, 0),
+                            _ => self.derived_ordering(l, attributes(&obj_b, &obj_a), false, &idx_name, 0)
+                        };
+
+                        res.push(NessaExpr::BinaryOperationDefinition(
+                            l.clone(), vec!(), op_id, tm.clone(), (a_name.clone(), a.clone()), (b_name.clone(), b.clone()), BOOL, 
+                            vec!(NessaExpr::Return(l.clone(), Box::new(body)))
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    // Conjunction of the equality of each pair of elements
+    fn derived_equality(&self, l: &Location, elems: Vec<(Type, NessaExpr, NessaExpr)>, idx_name: &str, depth: usize) -> NessaExpr {
+        elems.into_iter()
+             .map(|(t, a, b)| self.derived_element_equality(l, &t, a, b, idx_name, depth))
+             .reduce(|a, b| NessaExpr::BinaryOperation(l.clone(), AND_BINOP_ID, vec!(), Box::new(a), Box::new(b)))
+             .unwrap_or_else(|| NessaExpr::Literal(l.clone(), Object::new(true)))
+    }
+
+    // Arrays and tuples do not define operators, so their elements are compared one by one
+    fn derived_element_equality(&self, l: &Location, t: &Type, a: NessaExpr, b: NessaExpr, idx_name: &str, depth: usize) -> NessaExpr {
+        match t.deref_type() {
+            Type::And(v) => self.derived_equality(l, self.derived_tuple_elements(l, v, a, b), idx_name, depth),
+
+            Type::Template(ARR_ID, v) => {
+                let (len_a, len_b) = (self.derived_len(l, a.clone()), self.derived_len(l, b.clone()));
+                let idx = format!("{}{}", idx_name, depth);
+                let (elem_a, elem_b) = (self.derived_index(l, a, &idx), self.derived_index(l, b, &idx));
+                let eq = self.derived_element_equality(l, &v[0], elem_a, elem_b, idx_name, depth + 1);
+
+                self.derived_block(l, vec!(
+                    self.derived_return_if(l, NessaExpr::BinaryOperation(l.clone(), NEQ_BINOP_ID, vec!(), Box::new(len_a.clone()), Box::new(len_b)), false),
+                    self.derived_for(l, &idx, len_a, vec!(
+                        self.derived_return_if(l, NessaExpr::UnaryOperation(l.clone(), NOT_UNOP_ID, vec!(), Box::new(eq)), false)
+                    )),
+                    NessaExpr::Return(l.clone(), Box::new(NessaExpr::Literal(l.clone(), Object::new(true))))
+                ))
+            },
+
+            _ => NessaExpr::BinaryOperation(l.clone(), EQ_BINOP_ID, vec!(), Box::new(a), Box::new(b))
+        }
+    }
+
+    // Lexicographic comparison of the elements using only their < operators
+    fn derived_ordering(&self, l: &Location, elems: Vec<(Type, NessaExpr, NessaExpr)>, strict: bool, idx_name: &str, depth: usize) -> NessaExpr {
+        let mut lines = vec!();
+
+        for (t, a, b) in elems {
+            lines.push(self.derived_return_if(l, self.derived_element_ordering(l, &t, a.clone(), b.clone(), idx_name, depth), true));
+            lines.push(self.derived_return_if(l, self.derived_element_ordering(l, &t, b, a, idx_name, depth), false));
+        }
+
+        lines.push(NessaExpr::Return(l.clone(), Box::new(NessaExpr::Literal(l.clone(), Object::new(!strict)))));
+
+        self.derived_block(l, lines)
+    }
+
+    fn derived_element_ordering(&self, l: &Location, t: &Type, a: NessaExpr, b: NessaExpr, idx_name: &str, depth: usize) -> NessaExpr {
+        match t.deref_type() {
+            Type::And(v) => self.derived_ordering(l, self.derived_tuple_elements(l, v, a, b), true, idx_name, depth),
+
+            // Shorter arrays go first if they are a prefix of the other one
+            Type::Template(ARR_ID, v) => {
+                let (len_a, len_b) = (self.derived_len(l, a.clone()), self.derived_len(l, b.clone()));
+                let idx = format!("{}{}", idx_name, depth);
+                let idx_var = NessaExpr::NameReference(l.clone(), idx.clone());
+                let (elem_a, elem_b) = (self.derived_index(l, a, &idx), self.derived_index(l, b, &idx));
+                let out_of_b = NessaExpr::UnaryOperation(l.clone(), NOT_UNOP_ID, vec!(), Box::new(
+                    NessaExpr::BinaryOperation(l.clone(), LT_BINOP_ID, vec!(), Box::new(idx_var), Box::new(len_b.clone()))
+                ));
+
+                self.derived_block(l, vec!(
+                    self.derived_for(l, &idx, len_a.clone(), vec!(
+                        self.derived_return_if(l, out_of_b, false),
+                        self.derived_return_if(l, self.derived_element_ordering(l, &v[0], elem_a.clone(), elem_b.clone(), idx_name, depth + 1), true),
+                        self.derived_return_if(l, self.derived_element_ordering(l, &v[0], elem_b, elem_a, idx_name, depth + 1), false)
+                    )),
+                    NessaExpr::Return(l.clone(), Box::new(NessaExpr::BinaryOperation(l.clone(), LT_BINOP_ID, vec!(), Box::new(len_a), Box::new(len_b))))
+                ))
+            },
+
+            _ => NessaExpr::BinaryOperation(l.clone(), LT_BINOP_ID, vec!(), Box::new(a), Box::new(b))
+        }
+    }
+
+    fn derived_tuple_elements(&self, l: &Location, types: &[Type], a: NessaExpr, b: NessaExpr) -> Vec<(Type, NessaExpr, NessaExpr)> {
+        types.iter().enumerate().map(|(i, t)| {
+            let get_id = self.get_function_id(format!("get_{}", i)).unwrap();
+            let get = |obj: NessaExpr| NessaExpr::FunctionCall(l.clone(), get_id, vec!(), vec!(obj));
+
+            (t.clone(), get(a.clone()), get(b.clone()))
+        }).collect()
+    }
+
+    fn derived_len(&self, l: &Location, obj: NessaExpr) -> NessaExpr {
+        NessaExpr::FunctionCall(l.clone(), self.get_function_id("len".into()).unwrap(), vec!(), vec!(obj))
+    }
+
+    fn derived_index(&self, l: &Location, obj: NessaExpr, idx: &str) -> NessaExpr {
+        let idx = NessaExpr::UnaryOperation(l.clone(), DEREF_UNOP_ID, vec!(), Box::new(NessaExpr::NameReference(l.clone(), idx.into())));
+
+        NessaExpr::NaryOperation(l.clone(), IDX_OP, vec!(), Box::new(obj), vec!(idx))
+    }
+
+    fn derived_return_if(&self, l: &Location, cond: NessaExpr, value: bool) -> NessaExpr {
+        NessaExpr::If(l.clone(), Box::new(cond), vec!(NessaExpr::Return(l.clone(), Box::new(NessaExpr::Literal(l.clone(), Object::new(value))))), vec!(), None)
+    }
+
+    fn derived_for(&self, l: &Location, idx: &str, len: NessaExpr, body: Vec<NessaExpr>) -> NessaExpr {
+        let range = NessaExpr::BinaryOperation(l.clone(), RANGE_BINOP_ID, vec!(), Box::new(NessaExpr::Literal(l.clone(), Object::new(Integer::from(0)))), Box::new(len));
+
+        NessaExpr::For(l.clone(), idx.into(), Box::new(range), body)
+    }
+
+    fn derived_block(&self, l: &Location, lines: Vec<NessaExpr>) -> NessaExpr {
+        NessaExpr::DoBlock(l.clone(), lines, Type::InferenceMarker)
+    }
+
+    // Derived operations are generated from the class attributes
+    fn define_derived_operations(&mut self, class_id: usize, n_templates: usize, derived: &[&String]) -> Result<(), String> {
+        let params = self.type_templates[class_id].params.clone();
+        let stringable_t = |i| Type::TemplateParam(i, vec!(STRINGABLE));
+        let class_type = |t: fn(usize) -> Type| if n_templates == 0 { Type::Basic(class_id) } else { Type::Template(class_id, (0..n_templates).map(t).collect()) };
+
+        let tp = class_type(|i| Type::TemplateParam(i, vec!()));
+        let forms = [tp.clone(), tp.clone().to_ref(), tp.to_mut()];
+
+        for d in derived {
+            match d.as_str() {
+                "Stringable" => {
+                    let to_string_id = self.get_function_id("to_string".into())?;
+                    let tp = class_type(stringable_t);
+        
+                    for f in [tp.clone(), tp.clone().to_ref(), tp.to_mut()] {
+                        self.define_native_function_overload(to_string_id, n_templates, std::slice::from_ref(&f), STR, |_, _, v, ctx| {
+                            Ok(Object::new(v[0].to_display_string(ctx)))
+                        })?;
+        
+                        self.define_interface_impl("Stringable".into(), params.clone(), f, vec!())?;
+                    }
+                },
+
+                "Printable" => {
+                    let print_id = self.get_function_id("print".into())?;
+
+                    for f in &forms {
+                        self.define_native_function_overload(print_id, n_templates, std::slice::from_ref(f), Type::Empty, |_, _, v, ctx| {
+                            print!("{}", v[0].to_display_string(ctx));
+            
+                            Ok(Object::empty())
+                        })?;
+            
+                        self.define_interface_impl("Printable".into(), params.clone(), f.clone(), vec!())?;
+                    }
+                },
+
+                "Hash" => {
+                    let hash_id = match self.get_function_id("hash".into()) {
+                        Ok(id) => id,
+                        Err(_) => self.define_function("hash".into())?
+                    };
+
+                    for f in &forms {
+                        self.define_native_function_overload(hash_id, n_templates, std::slice::from_ref(f), INT, |_, _, v, ctx| {
+                            Ok(Object::new(Integer::from(NessaHashTable::hash_key(&v[0], ctx)?)))
+                        })?;
+            
+                        self.define_interface_impl("Hash".into(), params.clone(), f.clone(), vec!())?;
+                    }
+                },
+
+                // Comparisons are generated as operation definitions by derived_operation_definitions
+                "Eq" | "Ord" => {},
+
+                // Unknown capabilities are reported by the annotation checks
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn define_module_macros(&mut self, code: &String) -> Result<(), NessaError> {
         let mut defined_macros = FxHashSet::default();
         let mut changed = true;
@@ -3477,19 +5062,31 @@ impl NessaContext{
     pub fn define_module_classes(&mut self, code: &String) -> Result<(), NessaError> {
         if let Ok((_, i_names)) = self.nessa_interface_definition_names_parser(Span::new(code)) {
             for i_name in i_names {
-                self.define_interface(Location::none(), vec!(), i_name, vec!(), vec!(), vec!(), vec!(), vec!()).unwrap();
+                self.define_interface(Location::none(), vec!(), i_name, vec!(), vec!(), vec!(), vec!(), vec!(), vec!()).unwrap();
             }
 
             if let Ok((_, names)) = self.nessa_class_names_parser(Span::new(code)) {
                 for name in names {
-                    self.define_type(Location::none(), vec!(), name, vec!(), vec!(), None, vec!(), None).unwrap();
+                    if let Err(msg) = self.define_user_type(Location::none(), vec!(), name, vec!(), vec!(), None, vec!(), None) {
+                        return Err(NessaError::compiler_error(msg, &Location::none(), vec!()));
+                    }
                 }
     
                 let interfaces = self.nessa_interface_definition_parser(Span::new(code))?;
 
                 for i in interfaces.1 {
-                    if let NessaExpr::InterfaceDefinition(l, an, n, t, v, u, b, nr) = i {
-                        self.redefine_interface(l, an, n, t, v, u, b, nr).unwrap();
+                    if let NessaExpr::InterfaceDefinition(l, an, n, t, p, v, u, b, nr) = i {
+                        self.redefine_interface(l, an, n, t, p, v, u, b, nr).unwrap();
+                    }
+                }
+
+                // Inheritance cycles would make interface checks loop forever
+                for i in &self.interfaces {
+                    if self.inherits_from(i.id, i.id) {
+                        return Err(NessaError::compiler_error(
+                            format!("Interface {} inherits from itself", i.name.green()), 
+                            &i.location, vec!()
+                        ));
                     }
                 }
 
@@ -3578,9 +5175,14 @@ impl NessaContext{
 
     pub fn define_module_function_overloads(&mut self, lines: &Vec<NessaExpr>) -> Result<(), NessaError> {
         for i in lines {
-            if let NessaExpr::FunctionDefinition(l, an, id, t, a, r, _)  = i {
+            if let NessaExpr::FunctionDefinition(l, an, id, t, a, d, v, r, _)  = i {
+                if d.iter().skip_while(|i| i.is_none()).any(Option::is_none) {
+                    return Err(NessaError::compiler_error("Parameters with default values must come after the rest".into(), l, vec!()));
+                }
+
                 let arg_types = a.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
-                let err = self.define_function_overload(l.clone(), an.clone(), *id, t.len(), &arg_types, r.clone(), None);
+                let params = a.iter().map(|(n, _)| n.clone()).zip(d.iter().cloned()).collect();
+                let err = self.define_function_overload(l.clone(), an.clone(), *id, t.len(), &arg_types, params, *v, r.clone(), None);
 
                 if let Err(msg) = err {
                     return Err(NessaError::compiler_error(msg, l, vec!()));
@@ -3591,6 +5193,90 @@ impl NessaContext{
         Ok(())
     }
 
+    pub fn define_module_default_overloads(&mut self, lines: &mut Vec<NessaExpr>) -> Result<(), NessaError> {
+        let mut defaults = vec!();
+
+        for i in lines.iter() {
+            if let NessaExpr::InterfaceImplementation(l, tm, t, n, ts) = i {
+                let interface = match self.get_interface(n) {
+                    Some(int) if int.params.len() == ts.len() => int.clone(),
+                    _ => continue
+                };
+
+                for (an, f_n, f_tm, args, ret, body) in &interface.fns {
+                    if let Some(b) = body {
+                        // The function may not exist yet if the interface was imported
+                        let fn_id = match self.get_function(f_n) {
+                            Some(f) => f.id,
+                            None => self.define_function(f_n.clone()).map_err(|err| NessaError::compiler_error(err, l, vec!()))?
+                        };
+
+                        let f_tm = f_tm.clone().unwrap_or_default();
+
+                        // Interface parameters are replaced by the implementation arguments and the
+                        // function's own templates go after the ones of the implementation
+                        let offset = tm.len() + f_tm.len();
+
+                        let subs = ts.iter().cloned().enumerate()
+                                     .chain((0..f_tm.len()).map(|j| (ts.len() + j, Type::TemplateParam(tm.len() + j, vec!()))))
+                                     .map(|(i, tp)| (offset + i, tp))
+                                     .collect::<HashMap<_, _>>();
+
+                        let instantiate = |tp: &Type| {
+                            let mut res = tp.clone();
+                            res.offset_templates(offset);
+                            res.sub_templates(&subs).sub_self(t)
+                        };
+
+                        let args_sub = args.iter().map(|(a_n, tp)| (a_n.clone(), instantiate(tp))).collect::<Vec<_>>();
+                        let arg_types = args_sub.iter().map(|(_, tp)| tp.clone()).collect::<Vec<_>>();
+                        let ret_sub = instantiate(ret);
+
+                        // Only functions omitted by the implementation get the default body
+                        let max_tms = self.functions[fn_id].overloads.iter().map(|i| i.templates).max().unwrap_or(0);
+                        let mut offset_args = arg_types.clone();
+                        offset_args.iter_mut().for_each(|i| i.offset_templates(max_tms));
+
+                        if self.get_first_function_overload(fn_id, offset_args, None, true, l).is_ok() {
+                            continue;
+                        }
+
+                        let params = args_sub.iter().map(|(a_n, _)| (a_n.clone(), None)).collect();
+
+                        if let Err(err) = self.define_function_overload(l.clone(), an.clone(), fn_id, offset, &arg_types, params, false, ret_sub.clone(), None) {
+                            return Err(NessaError::compiler_error(err, l, vec!()));
+                        }
+
+                        let mut body_sub = b.clone();
+                        body_sub.iter_mut().for_each(|e| e.map_types(&mut |tp| *tp = instantiate(tp)));
+
+                        let templates = tm.iter().chain(&f_tm).cloned().collect();
+
+                        let arg_defaults = vec!(None; args_sub.len());
+
+                        defaults.push(NessaExpr::FunctionDefinition(l.clone(), an.clone(), fn_id, templates, args_sub, arg_defaults, false, ret_sub, body_sub));
+                    }
+                }
+            }
+        }
+
+        lines.append(&mut defaults);
+
+        Ok(())
+    }
+
+    pub fn define_module_derived_operations(&mut self, lines: &mut Vec<NessaExpr>) -> Result<(), NessaError> {
+        let mut derived = vec!();
+
+        for i in lines.iter() {
+            derived.append(&mut self.derived_operation_definitions(i)?);
+        }
+
+        lines.append(&mut derived);
+
+        Ok(())
+    }
+
     pub fn parse_nessa_module(&mut self, code: &String) -> Result<Vec<NessaExpr>, NessaError> {
         return match self.nessa_parser(Span::new(code)) {
             Ok((_, lines)) => Ok(lines),
@@ -3614,18 +5300,34 @@ impl NessaContext{
                 interface_id = f.id;
 
             } else { // Else the function needs to be defined
+                // Parents are mapped first so they get their ids before this interface
+                let mapped_parents = other_i.parents.iter().map(|c| {
+                    InterfaceConstraint::new(
+                        self.map_nessa_interface(other, c.id, id_mapper, l).unwrap(),
+                        c.args.iter().map(|t| t.map_type(self, other, id_mapper, l)).collect()
+                    )
+                }).collect::<Vec<_>>();
+
                 interface_id = self.interfaces.len();
                 id_mapper.interfaces.entry(id).or_insert(interface_id);
 
-                let mapped_fns = other_i.fns.iter().map(|(an, n, t, a, r)| {
-                    (
+                let mapped_fns = other_i.fns.iter().map(|(an, n, t, a, r, b)| {
+                    let mut mapped_body = b.clone();
+
+                    // Default bodies are stored uncompiled, so they are mapped like imported code
+                    for line in mapped_body.iter_mut().flatten() {
+                        self.map_nessa_expression(line, other, id_mapper)?;
+                    }
+
+                    Result::<_, NessaError>::Ok((
                         an.clone(),
                         n.clone(),
                         t.clone(),
                         a.iter().map(|(n, t)| (n.clone(), t.map_type(self, other, id_mapper, l))).collect(),
-                        r.map_type(self, other, id_mapper, l)
-                    )
-                }).collect::<Vec<_>>();
+                        r.map_type(self, other, id_mapper, l),
+                        mapped_body
+                    ))
+                }).collect::<Result<Vec<_>, _>>().unwrap();
 
                 let mapped_uns = other_i.uns.iter().map(|(an, id, tm, a, at, ret)| {
                     Result::<_, NessaError>::Ok((
@@ -3660,7 +5362,7 @@ impl NessaContext{
                     ))
                 }).collect::<Result<Vec<_>, _>>().unwrap();
 
-                self.define_interface(other_i.location.clone(), other_i.annotations.clone(), i_name.clone(), other_i.params.clone(), mapped_fns, mapped_uns, mapped_bin, mapped_nary)?;
+                self.define_interface(other_i.location.clone(), other_i.annotations.clone(), i_name.clone(), other_i.params.clone(), mapped_parents, mapped_fns, mapped_uns, mapped_bin, mapped_nary)?;
             }
 
             return Ok(interface_id);
@@ -3676,18 +5378,22 @@ impl NessaContext{
         if !id_mapper.classes.contains_key(&id) {
             let class_id;
 
-            // If the function has another id in the target context
-            if let Some(f) = self.get_type_template(c_name) {
+            // Standard types have the same id in every context
+            if other.is_standard_type(id) {
+                class_id = id;
+
+            // If the class has another id in the target context (standard types with the same name are shadowed)
+            } else if let Some(f) = self.get_type_template(c_name).filter(|t| !self.is_standard_type(t.id)) {
                 class_id = f.id;
 
-            } else { // Else the function needs to be defined
+            } else { // Else the class needs to be defined
                 class_id = self.type_templates.len();
                 id_mapper.classes.entry(id).or_insert(class_id);
 
                 let mapped_attrs = other_cl.attributes.iter().map(|(n, t)| (n.clone(), t.map_type(self, other, id_mapper, l))).collect();
                 let mapped_alias = other_cl.alias.as_ref().map(|i| i.map_type(self, other, id_mapper, l));
 
-                self.define_type(other_cl.location.clone(), other_cl.annotations.clone(), c_name.clone(), other_cl.params.clone(), mapped_attrs, mapped_alias, other_cl.patterns.clone(), other_cl.parser)?;
+                self.define_user_type(other_cl.location.clone(), other_cl.annotations.clone(), c_name.clone(), other_cl.params.clone(), mapped_attrs, mapped_alias, other_cl.patterns.clone(), other_cl.parser)?;
             }
 
             return Ok(class_id);
@@ -3824,10 +5530,19 @@ impl NessaContext{
                 self.map_nessa_expression(e, ctx, id_mapper)?;
             }
 
+            NessaExpr::DestructuringDefinition(l, p, e) => {
+                for t in p.types_mut() {
+                    *t = t.map_type(self, ctx, id_mapper, l);
+                }
+
+                self.map_nessa_expression(e, ctx, id_mapper)?;
+            }
+
             NessaExpr::VariableAssignment(_, _, e) => {
                 self.map_nessa_expression(e, ctx, id_mapper)?;
             }
 
+            NessaExpr::VariadicArgs(_, _, b) |
             NessaExpr::Tuple(_, b) => {
                 for arg in b {
                     self.map_nessa_expression(arg, ctx, id_mapper)?;
@@ -3859,6 +5574,13 @@ impl NessaContext{
                 self.map_nessa_expression(b, ctx, id_mapper)?;
             }
 
+            NessaExpr::CompoundAssignment(l, id, a, b) => {
+                *id = self.map_nessa_binary_operator(ctx, *id, id_mapper, l)?;
+
+                self.map_nessa_expression(a, ctx, id_mapper)?;
+                self.map_nessa_expression(b, ctx, id_mapper)?;
+            }
+
             NessaExpr::NaryOperation(l, id, t, a, b) => {
                 *id = self.map_nessa_nary_operator(ctx, *id, id_mapper, l)?;
 
@@ -3881,6 +5603,22 @@ impl NessaContext{
                 }
             }
 
+            NessaExpr::Match(l, e, arms) => {
+                self.map_nessa_expression(e, ctx, id_mapper)?;
+
+                for (p, g, b) in arms {
+                    for t in p.types_mut() {
+                        *t = t.map_type(self, ctx, id_mapper, l);
+                    }
+
+                    if let Some(g_inner) = g {
+                        self.map_nessa_expression(g_inner, ctx, id_mapper)?;
+                    }
+
+                    self.map_nessa_expression(b, ctx, id_mapper)?;
+                }
+            }
+
             NessaExpr::If(_, ih, ib, ei, eb) => {
                 self.map_nessa_expression(ih, ctx, id_mapper)?;
 
@@ -3912,7 +5650,18 @@ impl NessaContext{
                 }
             }
 
-            NessaExpr::Return(_, e) => {
+            NessaExpr::Try(l, b, _, t, h) => {
+                *t = t.map_type(self, ctx, id_mapper, l);
+
+                for line in b.iter_mut().chain(h) {
+                    self.map_nessa_expression(line, ctx, id_mapper)?;
+                }
+            }
+
+            NessaExpr::Return(_, e) |
+            NessaExpr::Propagate(_, e) |
+            NessaExpr::Interpolation(_, e) |
+            NessaExpr::NamedArgument(_, _, e) => {
                 self.map_nessa_expression(e, ctx, id_mapper)?;
             }
 
@@ -3967,19 +5716,33 @@ impl NessaContext{
                     }
                 }
 
-                NessaExpr::InterfaceDefinition(l, an, n, t, fns, uns, bin, nary) => {
+                NessaExpr::InterfaceDefinition(l, an, n, t, p, fns, uns, bin, nary) => {
                     if needs_import(module, ImportType::Interface, n, imports, &mut self.cache.imports.interface_def, (n.clone(), t.clone())) {
                         self.map_nessa_interface(ctx, ctx.get_interface_id(n.clone()).unwrap(), &mut id_mapper, l).unwrap();
 
-                        let mapped_fns = fns.iter().map(|(an, n, t, a, r)| {
-                            (
+                        let mapped_parents = p.iter().map(|c| {
+                            InterfaceConstraint::new(
+                                self.map_nessa_interface(ctx, c.id, &mut id_mapper, l).unwrap(),
+                                c.args.iter().map(|t| t.map_type(self, ctx, &mut id_mapper, l)).collect()
+                            )
+                        }).collect::<Vec<_>>();
+
+                        let mapped_fns = fns.iter().map(|(an, n, t, a, r, b)| {
+                            let mut mapped_body = b.clone();
+
+                            for line in mapped_body.iter_mut().flatten() {
+                                self.map_nessa_expression(line, ctx, &mut id_mapper)?;
+                            }
+
+                            Result::<_, NessaError>::Ok((
                                 an.clone(),
                                 n.clone(),
                                 t.clone(),
                                 a.iter().map(|(n, t)| (n.clone(), t.map_type(self, ctx, &mut id_mapper, l))).collect(),
-                                r.map_type(self, ctx, &mut id_mapper, l)
-                            )
-                        }).collect();
+                                r.map_type(self, ctx, &mut id_mapper, l),
+                                mapped_body
+                            ))
+                        }).collect::<Result<Vec<_>, _>>()?;
 
                         let mapped_uns = uns.iter().map(|(an, id, tm, a, at, ret)| {
                             Result::<_, NessaError>::Ok((
@@ -4014,18 +5777,18 @@ impl NessaContext{
                             ))
                         }).collect::<Result<Vec<_>, _>>().unwrap();
 
-                        let mapped_expr = NessaExpr::InterfaceDefinition(l.clone(), an.clone(), n.clone(), t.clone(), mapped_fns, mapped_uns, mapped_bin, mapped_nary);
+                        let mapped_expr = NessaExpr::InterfaceDefinition(l.clone(), an.clone(), n.clone(), t.clone(), mapped_parents, mapped_fns, mapped_uns, mapped_bin, mapped_nary);
 
                         res.push(mapped_expr);
                         new_source.push(module.clone());
                     }
                 }
 
-                NessaExpr::ClassDefinition(l, an, n, t, atts, al, p) => {
+                NessaExpr::ClassDefinition(l, an, n, t, atts, al, p, is_enum) => {
                     if needs_import(module, ImportType::Class, n, imports, &mut self.cache.imports.classes, (n.clone(), t.clone())) {
                         let mapped_atts = atts.iter().map(|(n, t)| (n.clone(), t.map_type(self, ctx, &mut id_mapper, l))).collect();
                         let mapped_al = al.clone().map(|i| i.map_type(self, ctx, &mut id_mapper, l));
-                        let mapped_expr = NessaExpr::ClassDefinition(l.clone(), an.clone(), n.clone(), t.clone(), mapped_atts, mapped_al, p.clone());
+                        let mapped_expr = NessaExpr::ClassDefinition(l.clone(), an.clone(), n.clone(), t.clone(), mapped_atts, mapped_al, p.clone(), *is_enum);
 
                         self.define_module_class(mapped_expr.clone())?;
                         
@@ -4034,7 +5797,7 @@ impl NessaContext{
                     }
                 }
 
-                NessaExpr::FunctionDefinition(l, an, id, t, a, r, b) => {
+                NessaExpr::FunctionDefinition(l, an, id, t, a, d, v, r, b) => {
                     let f_name = &ctx.functions[*id].name;
                     let fn_id = self.map_nessa_function(ctx, *id, &mut id_mapper, l)?;
 
@@ -4049,14 +5812,22 @@ impl NessaContext{
                             self.map_nessa_expression(line, ctx, &mut id_mapper)?;
                         }
 
+                        let mut mapped_defaults = d.clone();
+
+                        // Map the default values of the parameters
+                        for default in mapped_defaults.iter_mut().flatten() {
+                            self.map_nessa_expression(default, ctx, &mut id_mapper)?;
+                        }
+
                         let arg_types = mapped_args.iter().map(|(_, t)| t.clone()).collect::<Vec<_>>();
+                        let params = mapped_args.iter().map(|(n, _)| n.clone()).zip(mapped_defaults.iter().cloned()).collect();
 
-                        if let Err(err) = self.define_function_overload(l.clone(), an.clone(), fn_id, t.len(), &arg_types, mapped_return.clone(), None) {
+                        if let Err(err) = self.define_function_overload(l.clone(), an.clone(), fn_id, t.len(), &arg_types, params, *v, mapped_return.clone(), None) {
                             return Err(NessaError::compiler_error(err, l, vec!()));
                         }
 
                         // Add the mapped function to the list of new expressions
-                        res.push(NessaExpr::FunctionDefinition(l.clone(), an.clone(), fn_id, t.clone(), mapped_args.clone(), mapped_return, mapped_body));
+                        res.push(NessaExpr::FunctionDefinition(l.clone(), an.clone(), fn_id, t.clone(), mapped_args.clone(), mapped_defaults, *v, mapped_return, mapped_body));
                         new_source.push(module.clone());
                     }
                 }
@@ -4305,9 +6076,11 @@ impl NessaContext{
         self.define_module_functions(code)?;
         self.define_module_operations(code)?;
 
-        let lines = self.parse_nessa_module(code)?;
+        let mut lines = self.parse_nessa_module(code)?;
 
         self.define_module_function_overloads(&lines)?;
+        self.define_module_default_overloads(&mut lines)?;
+        self.define_module_derived_operations(&mut lines)?;
 
         Ok(lines)
     }
@@ -4318,8 +6091,11 @@ impl NessaContext{
         // Static checks before doing anything else
         for expr in lines.iter_mut() {
             self.static_check(expr)?;
+            NessaContext::main_propagation_check(expr)?;
         }
 
+        self.move_check_body(lines)?;
+
         // Get every function and operation call in the program
         self.get_template_calls_body(lines)?;
 
@@ -4397,6 +6173,8 @@ mod tests {
     use crate::object::*;
     use crate::parser::*;
     use crate::context::*;
+    use crate::functions::*;
+    use crate::compilation::CompiledNessaExpr;
 
     #[test]
     fn function_names_and_calls() {
@@ -4444,4 +6222,30 @@ mod tests {
 
         assert!(compiled_code.is_ok());
     }
+
+    #[test]
+    fn counting_loop_compiled_form() {
+        let mut ctx = standard_ctx();
+
+        let code_str = "
+            let s = 0;
+
+            for i in 0..10 {
+                s = s + i;
+            }
+        ";
+
+        let compiled_code = ctx.parse_and_compile(&code_str.into()).unwrap();
+        let instructions = compiled_code.iter().map(|i| &i.instruction).collect::<Vec<_>>();
+
+        assert!(instructions.contains(&&CompiledNessaExpr::Lti));
+        assert!(instructions.contains(&&CompiledNessaExpr::Inc));
+
+        // No iterator is created or advanced
+        assert!(!instructions.iter().any(|i| matches!(
+            i,
+            CompiledNessaExpr::NativeFunctionCall(id, _, _) | CompiledNessaExpr::NativeFunctionCallNoRet(id, _, _)
+            if [ITERATOR_FUNC_ID, NEXT_FUNC_ID, IS_CONSUMED_FUNC_ID].contains(id)
+        )));
+    }
 }
\ No newline at end of file
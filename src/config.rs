@@ -584,6 +584,87 @@ pub fn precompile_nessa_module_with_config(path: &String, all_modules: VersionMo
     Ok((module.ctx, module.code))
 }
 
+fn parse_nessa_module_dependencies(path: &String, all_modules: &VersionModCache, file_cache: &FileCache, optimize: bool) -> Result<HashMap<String, NessaModule>, NessaError> {
+    let (config_yml, _, _, _) = file_cache.get(&normalize_path(Path::new(path))?).unwrap();
+    let mut already_compiled = HashMap::new();
+
+    // Every module that the project could import, along with its own dependencies
+    for (name, info) in &config_yml.modules {
+        let mut module_config = config_yml.clone();
+        module_config.module_name = name.clone();
+        module_config.version = info.version.clone();
+
+        for dep in module_config.get_imports_topological_order(all_modules)? {
+            if !already_compiled.contains_key(&dep) {
+                let module = all_modules.get(&dep).unwrap();
+                let compiled_module = parse_nessa_module_with_config(&module.path, &mut already_compiled, all_modules, file_cache, optimize, false)?;
+
+                already_compiled.entry(dep).or_insert(compiled_module);
+            }
+        }
+    }
+
+    Ok(already_compiled.into_iter().map(|((name, _), module)| (name, module)).collect())
+}
+
+// Project used by the interactive tools. Its modules are only parsed the first time that they are imported
+pub struct NessaProject {
+    pub path: String,
+    pub module_name: String,
+    all_modules: VersionModCache,
+    file_cache: FileCache,
+    modules: Option<HashMap<String, NessaModule>>
+}
+
+impl NessaProject {
+    pub fn new(path: &String) -> Result<Self, NessaError> {
+        let (all_modules, file_cache) = get_all_modules_cascade(Path::new(path), None)?;
+        let module_name = file_cache.get(&normalize_path(Path::new(path))?).unwrap().0.module_name.clone();
+
+        Ok(NessaProject { path: path.clone(), module_name, all_modules, file_cache, modules: None })
+    }
+
+    pub fn parse_with_imports(&mut self, ctx: &mut NessaContext, code: &String) -> Result<(Vec<NessaExpr>, Vec<String>), NessaError> {
+        let imports = nessa_module_imports_parser(Span::new(code), ctx.module_name.clone()).unwrap().1;
+
+        if self.modules.is_none() {
+            self.modules = Some(parse_nessa_module_dependencies(&self.path, &self.all_modules, &self.file_cache, false)?);
+        }
+
+        let modules = self.modules.as_ref().unwrap();
+
+        for module in imports.keys() {
+            if !modules.contains_key(module) {
+                return Err(NessaError::module_error(format!("Module with name {} was not found", module.green())));
+            }
+        }
+
+        let module_refs = modules.iter().map(|(n, m)| (n.clone(), m)).collect();
+        let module_name = ctx.module_name.to_string();
+
+        ctx.parse_with_dependencies(&module_name, code, &module_refs)
+    }
+
+    // Local files are stored as paths to the file and the rest of the modules as paths to their folder
+    pub fn module_file(&self, module_name: &str) -> Option<PathBuf> {
+        let path = if module_name == self.module_name {
+            &self.path
+
+        } else {
+            &self.all_modules.iter().find(|((n, _), _)| n == module_name)?.1.path
+        };
+
+        let path = Path::new(path);
+
+        if path.extension().is_some_and(|i| i == "nessa") {
+            Some(path.to_path_buf())
+
+        } else {
+            Some(path.join("main.nessa"))
+        }
+    }
+}
+
 pub fn generate_docs(path: &String) -> Result<(), NessaError> {
     let project_path = &normalize_path(Path::new(path))?;
 
@@ -642,9 +723,7 @@ pub fn read_compiled_cache(path: &String) -> Option<CompiledNessaModule> {
         return None;
     }
 
-    let code = CompiledNessaModule::from_file(&code_path);
-
-    Some(code)
+    CompiledNessaModule::from_file_checked(&code_path)
 }
 
 pub fn save_compiled_cache(path: &String, module: &CompiledNessaModule) -> Result<(), NessaError> {
@@ -19,7 +19,7 @@ use crate::interfaces::InterfaceNaryOpHeader;
 use crate::interfaces::InterfaceUnaryOpHeader;
 use crate::interfaces::standard_interfaces;
 use crate::macros::NessaMacro;
-use crate::parser::Location;
+use crate::parser::{Location, NessaExpr};
 use crate::translation::load_optimized_opcodes;
 use crate::types::*;
 use crate::operations::*;
@@ -33,6 +33,16 @@ use crate::patterns::*;
                                                   ╘══════════════════╛
 */
 
+// Destination of the warnings emitted during compilation
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum WarningSink {
+    #[default]
+    Stdout,
+
+    // Used by the language server, which needs stdout for the protocol
+    Stderr
+}
+
 #[derive(Default, Clone)]
 pub struct NessaContext {
     pub type_templates: Vec<TypeTemplate>, 
@@ -58,6 +68,7 @@ pub struct NessaContext {
     pub cache: NessaCache,
 
     pub optimize: bool,
+    pub warning_sink: WarningSink,
     
     pub module_name: Arc<String>,
     pub module_path: String,
@@ -73,8 +84,15 @@ impl NessaContext {
         ╘════════════════════════════╛
     */
 
+    pub fn is_standard_type(&self, id: usize) -> bool {
+        id < *NUM_STD_TYPES.lock().unwrap().borrow()
+    }
+
     pub fn redefine_type(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, attributes: Vec<(String, Type)>, alias: Option<Type>, patterns: Vec<Pattern>, parser: Option<ParsingFunction>) -> Result<(), String> {
-        for t in self.type_templates.iter_mut() {
+        let num_std_types = *NUM_STD_TYPES.lock().unwrap().borrow();
+
+        // Standard types are never redefined, only shadowed
+        for t in self.type_templates.iter_mut().skip(num_std_types) {
             if t.name == representation {
                 *t = TypeTemplate {
                     id: t.id,
@@ -102,6 +120,27 @@ impl NessaContext {
             }
         }
 
+        self.push_type(l, annotations, representation, params, attributes, alias, patterns, parser);
+
+        Ok(())
+    }
+
+    // Types defined in user code shadow standard types with the same name, which are still used by id
+    pub fn define_user_type(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, attributes: Vec<(String, Type)>, alias: Option<Type>, patterns: Vec<Pattern>, parser: Option<ParsingFunction>) -> Result<(), String> {
+        let num_std_types = *NUM_STD_TYPES.lock().unwrap().borrow();
+
+        for t in self.type_templates.iter().skip(num_std_types) {
+            if t.name == representation {
+                return Err(format!("Type \"{}\" is already defined", representation))
+            }
+        }
+
+        self.push_type(l, annotations, representation, params, attributes, alias, patterns, parser);
+
+        Ok(())
+    }
+
+    fn push_type(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, attributes: Vec<(String, Type)>, alias: Option<Type>, patterns: Vec<Pattern>, parser: Option<ParsingFunction>) {
         self.cache.class_id.insert(representation.clone(), Ok(self.type_templates.len()));
 
         self.type_templates.push(TypeTemplate {
@@ -115,17 +154,16 @@ impl NessaContext {
             patterns,
             parser
         });
-
-        Ok(())
     }
 
-    pub fn redefine_interface(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, fns: Vec<InterfaceFunctionHeader>, uns: Vec<InterfaceUnaryOpHeader>, bin: Vec<InterfaceBinaryOpHeader>, nary: Vec<InterfaceNaryOpHeader>) -> Result<(), String> {
+    pub fn redefine_interface(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, parents: Vec<InterfaceConstraint>, fns: Vec<InterfaceFunctionHeader>, uns: Vec<InterfaceUnaryOpHeader>, bin: Vec<InterfaceBinaryOpHeader>, nary: Vec<InterfaceNaryOpHeader>) -> Result<(), String> {
         for i in self.interfaces.iter_mut() {
             if i.name == representation {
                 *i = Interface {
                     id: i.id,
                     name: representation,
                     params,
+                    parents,
                     location: l,
                     annotations,
                     fns,
@@ -141,7 +179,7 @@ impl NessaContext {
         Err(format!("Interface {} was not defined", representation))
     }
 
-    pub fn define_interface(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, fns: Vec<InterfaceFunctionHeader>, uns: Vec<InterfaceUnaryOpHeader>, bin: Vec<InterfaceBinaryOpHeader>, nary: Vec<InterfaceNaryOpHeader>) -> Result<(), String> {
+    pub fn define_interface(&mut self, l: Location, annotations: Vec<Annotation>, representation: String, params: Vec<String>, parents: Vec<InterfaceConstraint>, fns: Vec<InterfaceFunctionHeader>, uns: Vec<InterfaceUnaryOpHeader>, bin: Vec<InterfaceBinaryOpHeader>, nary: Vec<InterfaceNaryOpHeader>) -> Result<(), String> {
         for i in &self.interfaces {
             if i.name == representation {
                 return Err(format!("Interface \"{}\" is already defined", representation))
@@ -154,6 +192,7 @@ impl NessaContext {
             id: self.interfaces.len(),
             name: representation,
             params,
+            parents,
             location: l,
             annotations,
             fns,
@@ -193,10 +232,24 @@ impl NessaContext {
     const FREE_TEMPLATE_OFFSET: usize = 10_000_000;
 
     pub fn implements_interface(&self, t: &Type, constraint: &InterfaceConstraint, t_assignments: &mut HashMap<usize, Type>, t_deps: &mut HashMap<usize, HashSet<usize>>) -> bool {
+        self.implements_interface_with(t, constraint, true, t_assignments, t_deps)
+    }
+
+    // Dyn values are dispatched at runtime, where generic implementations cannot be instantiated
+    pub fn implements_interface_dynamically(&self, t: &Type, constraint: &InterfaceConstraint, t_assignments: &mut HashMap<usize, Type>, t_deps: &mut HashMap<usize, HashSet<usize>>) -> bool {
+        self.implements_interface_with(t, constraint, false, t_assignments, t_deps)
+    }
+
+    fn implements_interface_with(&self, t: &Type, constraint: &InterfaceConstraint, generic_impls: bool, t_assignments: &mut HashMap<usize, Type>, t_deps: &mut HashMap<usize, HashSet<usize>>) -> bool {
+        // Dyn values implement their interface and its parents
+        if let Type::Dyn(_) = t {
+            return t.template_bindable_to(&Type::Dyn(constraint.clone()), t_assignments, t_deps, self);
+        }
+
         let cons_and = Type::And(constraint.args.clone());
 
         for i in &self.interface_impls {
-            if i.interface_id == constraint.id {
+            if i.interface_id == constraint.id && (generic_impls || !i.is_generic()) {
                 let mut int_type = i.interface_type.clone();
                 let mut int_args = Type::And(i.args.clone());
 
@@ -213,8 +266,13 @@ impl NessaContext {
 
                 let args_match = int_args.template_bindable_to(&cons_and, &mut t_assignments_cpy, &mut t_deps_cpy, self);
                 let type_matches = int_type.template_bindable_to(t, &mut t_assignments_cpy, &mut t_deps_cpy, self);
+
+                // Parent interfaces have to be implemented too
+                let parents_match = args_match && type_matches && self.interface_parents(constraint).iter().all(|p| {
+                    self.implements_interface(t, p, &mut t_assignments_cpy, &mut t_deps_cpy)
+                });
     
-                if args_match && type_matches {
+                if parents_match {
                     *t_assignments = t_assignments_cpy;
                     *t_deps = t_deps_cpy;
                 
@@ -226,6 +284,44 @@ impl NessaContext {
         false
     }
 
+    // Parents of an interface with its parameters replaced by the arguments of the constraint
+    pub fn interface_parents(&self, constraint: &InterfaceConstraint) -> Vec<InterfaceConstraint> {
+        let offset = (Type::And(constraint.args.clone()).max_template() + 1) as usize;
+        let subs = constraint.args.iter().cloned().enumerate().map(|(i, t)| (offset + i, t)).collect::<HashMap<_, _>>();
+
+        self.interfaces[constraint.id].parents.iter().map(|p| {
+            InterfaceConstraint::new(p.id, p.args.iter().map(|t| {
+                let mut res = t.clone();
+                res.offset_templates(offset);
+                res.sub_templates(&subs)
+            }).collect())
+        }).collect()
+    }
+
+    // Whether a constraint implies another one because of interface inheritance
+    pub fn constraint_implies(&self, constraint: &InterfaceConstraint, target: &InterfaceConstraint) -> bool {
+        constraint == target || self.interface_parents(constraint).iter().any(|p| self.constraint_implies(p, target))
+    }
+
+    pub fn inherits_from(&self, id: usize, parent_id: usize) -> bool {
+        let mut visited = HashSet::new();
+        let mut pending = vec!(id);
+
+        while let Some(i) = pending.pop() {
+            for p in &self.interfaces[i].parents {
+                if p.id == parent_id {
+                    return true;
+                }
+
+                if visited.insert(p.id) {
+                    pending.push(p.id);
+                }
+            }
+        }
+
+        false
+    }
+
     /*
         ╒═════════════════════════════╕
         │ Unary operator manipulation │
@@ -516,37 +612,52 @@ impl NessaContext {
     }
 
     pub fn define_native_function_overload(&mut self, id: usize, templates: usize, args: &[Type], ret: Type, f: FunctionOverloadFn) -> Result<usize, String> {
-        self.define_function_overload(Location::none(), vec!(), id, templates, args, ret, Some(f))
+        self.define_function_overload(Location::none(), vec!(), id, templates, args, vec!(), false, ret, Some(f))
     }
 
-    pub fn define_function_overload(&mut self, l: Location, annot: Vec<Annotation>, id: usize, templates: usize, args: &[Type], ret: Type, f: OptFunctionOverloadFn) -> Result<usize, String> {
-        let and = Type::And(args.to_vec());
-        let func = &self.functions[id];
-
-        for f_ov in &func.overloads{ // Check subsumption
-            if let Type::And(v) = &f_ov.args {
-                if and.bindable_to(&f_ov.args, self) {
-                    return Err(format!("Function overload {}({}) is subsumed by {}({}), so it cannot be defined", 
-                                        func.name.green(), args.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", "), 
-                                        func.name.green(), v.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", ")));
-                }
-
-                if f_ov.args.bindable_to(&and, self) {
-                    return Err(format!("Function overload {}({}) subsumes {}({}), so it cannot be defined", 
-                                        func.name.green(), args.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", "), 
-                                        func.name.green(), v.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", ")));
-                }
-            }
-        }
+    // The last argument must be an array, which receives the rest of the arguments of a call
+    pub fn define_native_variadic_function_overload(&mut self, id: usize, templates: usize, args: &[Type], ret: Type, f: FunctionOverloadFn) -> Result<usize, String> {
+        self.define_function_overload(Location::none(), vec!(), id, templates, args, vec!(), true, ret, Some(f))
+    }
 
-        self.functions[id].overloads.push(FunctionOverload { 
+    pub fn define_function_overload(&mut self, l: Location, annot: Vec<Annotation>, id: usize, templates: usize, args: &[Type], params: Vec<(String, Option<NessaExpr>)>, variadic: bool, ret: Type, f: OptFunctionOverloadFn) -> Result<usize, String> {
+        let new_ov = FunctionOverload { 
             location: l,
             annotations: annot, 
             templates: templates, 
             args: Type::And(args.to_vec()), 
+            params: params,
+            variadic: variadic,
             ret: ret, 
             function: f 
-        });
+        };
+
+        let func = &self.functions[id];
+
+        // Variadic overloads can be called with any number of arguments, so every length up to one after the longest one is checked
+        let max_args = func.overloads.iter().map(FunctionOverload::arity).chain(std::iter::once(args.len())).max().unwrap() + 1;
+
+        for f_ov in &func.overloads{ // Check subsumption for every number of arguments both overloads can be called with
+            if let Type::And(v) = &f_ov.args {
+                for n in new_ov.required_args()..=max_args {
+                    for (new_args, ov_args) in new_ov.call_args(n).into_iter().flat_map(|a| f_ov.call_args(n).into_iter().map(move |b| (a.clone(), b))) {
+                        if new_args.bindable_to(&ov_args, self) {
+                            return Err(format!("Function overload {}({}) is subsumed by {}({}), so it cannot be defined", 
+                                                func.name.green(), args.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", "), 
+                                                func.name.green(), v.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", ")));
+                        }
+
+                        if ov_args.bindable_to(&new_args, self) {
+                            return Err(format!("Function overload {}({}) subsumes {}({}), so it cannot be defined", 
+                                                func.name.green(), args.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", "), 
+                                                func.name.green(), v.iter().map(|i| i.get_name(self)).collect::<Vec<_>>().join(", ")));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.functions[id].overloads.push(new_ov);
 
         Ok(self.functions[id].overloads.len() - 1)
     }
@@ -16,6 +16,9 @@ pub struct DebugInfo {
     
     #[builder(default)]
     pub var_type: Option<Type>,
+
+    #[builder(default)]
+    pub var_name: Option<String>,
     
     #[builder(default)]
     pub labels: FxHashSet<usize>,
@@ -55,6 +58,7 @@ impl NessaContext {
 
             NessaExpr::Literal(_, obj) => obj.to_debug_string().magenta().to_string(),
             NessaExpr::Tuple(_, args) => format!("({})", args.iter().map(|i| self.to_string(i)).collect::<Vec<_>>().join(", ")),
+            NessaExpr::VariadicArgs(_, t, args) => format!("<{}>[{}]", t.get_name(self), args.iter().map(|i| self.to_string(i)).collect::<Vec<_>>().join(", ")),
 
             NessaExpr::FunctionCall(_, id, t, args) => {
                 let temp = if t.is_empty() {
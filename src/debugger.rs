@@ -0,0 +1,315 @@
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::compilation::CompiledNessaExpr;
+use crate::context::NessaContext;
+use crate::debug::DebugInfo;
+use crate::execution::{backtrace, frame_location};
+use crate::object::{Object, ObjectBlock};
+
+/*
+                                                  ╒══════════════════╕
+    ============================================= │  IMPLEMENTATION  │ =============================================
+                                                  ╘══════════════════╛
+*/
+
+const HELP: &str = "Commands:
+  break [module] <line>    Set a breakpoint (b)
+  delete [module] <line>   Remove a breakpoint (d)
+  continue                 Run until the next breakpoint (c)
+  step                     Stop at the next line, entering calls (s)
+  next                     Stop at the next line of this frame or its callers (n)
+  out                      Stop after returning from this frame (o)
+  vars                     Show the variables of this frame (v)
+  backtrace                Show the active calls (bt)
+  quit                     Stop the execution (q)";
+
+#[derive(Clone, Copy, PartialEq)]
+enum StepMode {
+    Run,
+    Into,
+    Over(usize),
+    Out(usize),
+    Detached
+}
+
+#[derive(Default)]
+struct DebuggerFrame {
+    line: Option<(Arc<String>, usize)>,
+    names: FxHashMap<usize, String>
+}
+
+pub struct NessaDebugger<'a> {
+    input: Box<dyn BufRead + 'a>,
+    output: Box<dyn Write + 'a>,
+
+    breakpoints: FxHashSet<(Arc<String>, usize)>,
+    mode: StepMode,
+
+    // Last line and known variable names of every active call
+    frames: Vec<DebuggerFrame>
+}
+
+impl<'a> NessaDebugger<'a> {
+    pub fn new<R: BufRead + 'a, W: Write + 'a>(input: R, output: W) -> Self {
+        NessaDebugger {
+            input: Box::new(input),
+            output: Box::new(output),
+            breakpoints: FxHashSet::default(),
+            mode: StepMode::Into,
+            frames: vec!()
+        }
+    }
+
+    pub fn stdio() -> NessaDebugger<'static> {
+        NessaDebugger::new(io::stdin().lock(), io::stdout())
+    }
+
+    // Called before every instruction, returns false if the execution has to stop
+    pub fn before_instruction(&mut self, ctx: &NessaContext, program: &[CompiledNessaExpr], debug_info: &[DebugInfo], ip: usize, call_stack: &[(i32, usize, i32)], offset: usize) -> bool {
+        if self.mode == StepMode::Detached {
+            return true;
+        }
+
+        let depth = call_stack.len();
+
+        self.frames.truncate(depth);
+        self.frames.resize_with(depth, DebuggerFrame::default);
+
+        let frame = self.frames.last_mut().unwrap();
+
+        if let (CompiledNessaExpr::StoreVariable(idx), Some(info)) = (&program[ip], debug_info.get(ip)) {
+            if let Some(name) = &info.var_name {
+                frame.names.insert(*idx, name.clone());
+            }
+        }
+
+        let (function, line) = match frame_location(debug_info, ip) {
+            Some((f, Some(l))) => (f, l),
+            _ => return true
+        };
+
+        // Only the first instruction of each line is a stopping point
+        if frame.line.as_ref() == Some(&line) {
+            return true;
+        }
+
+        frame.line = Some(line.clone());
+
+        let stop = self.breakpoints.contains(&line) || match self.mode {
+            StepMode::Into => true,
+            StepMode::Over(d) => depth <= d,
+            StepMode::Out(d) => depth < d,
+            StepMode::Run | StepMode::Detached => false
+        };
+
+        if !stop {
+            return true;
+        }
+
+        self.write(format!("Stopped at module {}, line {} ({})", line.0, line.1, function));
+
+        loop {
+            self.prompt();
+
+            let mut command = String::new();
+
+            // Scripts that end without quitting let the program run to completion
+            if self.input.read_line(&mut command).unwrap_or(0) == 0 {
+                self.mode = StepMode::Detached;
+                return true;
+            }
+
+            let args = command.split_whitespace().collect::<Vec<_>>();
+
+            match args.as_slice() {
+                [] => {},
+
+                ["continue" | "c"] => {
+                    self.mode = StepMode::Run;
+                    return true;
+                },
+
+                ["step" | "s"] => {
+                    self.mode = StepMode::Into;
+                    return true;
+                },
+
+                ["next" | "n"] => {
+                    self.mode = StepMode::Over(depth);
+                    return true;
+                },
+
+                ["out" | "o"] => {
+                    self.mode = StepMode::Out(depth);
+                    return true;
+                },
+
+                ["quit" | "q"] => {
+                    self.write("Execution stopped".into());
+                    return false;
+                },
+
+                ["break" | "b" | "delete" | "d", location @ ..] => match self.parse_breakpoint(ctx, location) {
+                    Some(bp) => {
+                        let msg = format!("module {}, line {}", bp.0, bp.1);
+
+                        if args[0].starts_with('b') {
+                            self.breakpoints.insert(bp);
+                            self.write(format!("Breakpoint set at {}", msg));
+
+                        } else if self.breakpoints.remove(&bp) {
+                            self.write(format!("Breakpoint removed from {}", msg));
+
+                        } else {
+                            self.write(format!("No breakpoint at {}", msg));
+                        }
+                    },
+
+                    None => self.write("Usage: break [module] <line>".into())
+                },
+
+                ["vars" | "v"] => {
+                    let max_var = call_stack[depth - 1].2;
+                    let names = &self.frames[depth - 1].names;
+
+                    let mut lines = vec!();
+
+                    for idx in 0..(max_var + 1).max(0) as usize {
+                        if let Some(obj) = ctx.variables.get(offset + idx) {
+                            if let Some((t, v)) = describe(ctx, obj) {
+                                let name = names.get(&idx).cloned().unwrap_or_else(|| format!("#{}", idx));
+                                lines.push(format!("  {}: {} = {}", name, t, v));
+                            }
+                        }
+                    }
+
+                    if lines.is_empty() {
+                        self.write("No variables in this frame".into());
+
+                    } else {
+                        self.write(lines.join("\n"));
+                    }
+                },
+
+                ["backtrace" | "bt"] => {
+                    let trace = backtrace(debug_info, ip, call_stack).into_iter().map(|i| format!("  {}", i)).collect::<Vec<_>>();
+                    self.write(trace.join("\n"));
+                },
+
+                ["help" | "h"] => self.write(HELP.into()),
+
+                _ => self.write(format!("Unknown command \"{}\" (use help to list them)", command.trim()))
+            }
+        }
+    }
+
+    fn parse_breakpoint(&self, ctx: &NessaContext, args: &[&str]) -> Option<(Arc<String>, usize)> {
+        match args {
+            [line] => Some((ctx.module_name.clone(), line.parse().ok()?)),
+            [module, line] => Some((Arc::new(module.to_string()), line.parse().ok()?)),
+            _ => None
+        }
+    }
+
+    fn prompt(&mut self) {
+        write!(self.output, "(debug) ").unwrap();
+        self.output.flush().unwrap();
+    }
+
+    fn write(&mut self, msg: String) {
+        writeln!(self.output, "{}", msg).unwrap();
+        self.output.flush().unwrap();
+    }
+}
+
+// Type and value of an object, or None if it has been moved
+fn describe(ctx: &NessaContext, obj: &Object) -> Option<(String, String)> {
+    let inner = obj.inner.borrow();
+
+    let target_moved = match inner {
+        ObjectBlock::NoValue => true,
+        ObjectBlock::Ref(r) | ObjectBlock::Mut(r) => r.borrow().is_moved(),
+        _ => false
+    };
+
+    if target_moved {
+        return None;
+    }
+
+    Some((inner.get_type().get_name_plain(ctx), inner.to_display_string(ctx)))
+}
+
+/*
+                                                  ╒═════════╕
+    ============================================= │  TESTS  │ =============================================
+                                                  ╘═════════╛
+*/
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::context::standard_ctx;
+    use crate::debugger::NessaDebugger;
+
+    fn debug_session(code: &str, commands: &str) -> String {
+        let mut ctx = standard_ctx();
+        let mut output = vec!();
+
+        ctx.module_name = Arc::new("main".into());
+
+        let (instructions, debug_info): (Vec<_>, Vec<_>) = ctx.parse_and_compile(&code.into()).unwrap().into_iter().map(|i| (i.instruction, i.debug_info)).unzip();
+
+        let mut debugger = NessaDebugger::new(commands.as_bytes(), &mut output);
+        ctx.execute_compiled_code_with_debugger(&instructions, &debug_info, &mut debugger).unwrap();
+        drop(debugger);
+
+        String::from_utf8(output).unwrap()
+    }
+
+    const CODE: &str = "
+let a = 5;
+
+fn double(n: Int) -> Int {
+    let res = n * 2;
+    return *res;
+}
+
+let b = double(*a);
+let c = b + 1;
+";
+
+    #[test]
+    fn breakpoints_and_variables() {
+        let out = debug_session(CODE, "break 6\ncontinue\nvars\nbacktrace\ncontinue\n");
+
+        assert!(out.contains("Stopped at module main, line 2 (main)"));
+        assert!(out.contains("Breakpoint set at module main, line 6"));
+        assert!(out.contains("Stopped at module main, line 6 (fn double(Int) -> Int)"));
+        assert!(out.contains("  n: Int = 5\n  res: Int = 10"));
+        assert!(out.contains("  fn double(Int) -> Int (module main, line 6)\n  main (module main, line 9)"));
+    }
+
+    #[test]
+    fn stepping() {
+        let out = debug_session(CODE, "next\nstep\nnext\nout\nvars\nquit\n");
+
+        let stops = out.lines()
+                       .filter_map(|l| l.split("Stopped at module main, ").nth(1))
+                       .collect::<Vec<_>>();
+
+        assert_eq!(stops, vec!(
+            "line 2 (main)",
+            "line 9 (main)",
+            "line 5 (fn double(Int) -> Int)",
+            "line 6 (fn double(Int) -> Int)",
+            "line 10 (main)"
+        ));
+
+        assert!(out.contains("  a: Int = 5\n  b: Int = 10"));
+        assert!(out.contains("Execution stopped"));
+    }
+}
@@ -2,8 +2,9 @@ use std::fs::OpenOptions;
 use std::{fs::File, path::Path};
 use std::io::Write;
 
-use crate::interfaces::Interface;
+use crate::interfaces::{Interface, InterfaceConstraint};
 use crate::operations::Operator;
+use crate::parser::NessaExpr;
 use crate::types::TypeTemplate;
 use crate::{annotations::Annotation, config::NessaModule, html_ext::HTMLColorable, types::Type};
 
@@ -41,7 +42,7 @@ pub fn create_markdown_file(base: &String, name: &str) -> File {
     file
 }
 
-pub fn write_args_and_ret(file: &mut File, annot: &Annotation) {
+pub fn write_args_and_ret(file: &mut File, annot: &Annotation, params: &[(String, Option<NessaExpr>)]) {
     write!(
         file,
         "### Parameters\n\n",
@@ -49,9 +50,16 @@ pub fn write_args_and_ret(file: &mut File, annot: &Annotation) {
 
     for arg in &annot.args {
         if arg.0.parse::<usize>().is_err() {
+            // Parameters with default values show the expression as it was written
+            let default = params.iter()
+                                .find(|(n, _)| n == arg.0)
+                                .and_then(|(_, d)| d.as_ref())
+                                .map(|d| format!(" (default: `{}`)", d.get_location().span))
+                                .unwrap_or_default();
+
             write!(
                 file, 
-                "* `{}`: {}\n", arg.0, arg.1
+                "* `{}`: {}{}\n", arg.0, arg.1, default
             ).expect("Error while writing to docs file");
         }
     }
@@ -69,7 +77,7 @@ pub fn write_args_and_ret(file: &mut File, annot: &Annotation) {
     ).expect("Error while writing to docs file");
 }
 
-pub fn write_function_overload_docs(file: &mut File, module: &NessaModule, f: &str, t: usize, args: &Type, ret: &Type, annot: &Annotation) {
+pub fn write_function_overload_docs(file: &mut File, module: &NessaModule, f: &str, t: usize, args: &Type, params: &[(String, Option<NessaExpr>)], ret: &Type, annot: &Annotation) {
     write!(
         file, 
         "## {} {}{}{} -> {}\n\n", 
@@ -84,7 +92,7 @@ pub fn write_function_overload_docs(file: &mut File, module: &NessaModule, f: &s
         args.get_name_html(&module.ctx), ret.get_name_html(&module.ctx)
     ).expect("Error while writing to docs file");
 
-    write_args_and_ret(file, annot);
+    write_args_and_ret(file, annot, params);
 }
 
 pub fn write_unary_operation_docs(file: &mut File, module: &NessaModule, op: &str, t: usize, args: &Type, ret: &Type, annot: &Annotation, prefix: bool) {
@@ -120,7 +128,7 @@ pub fn write_unary_operation_docs(file: &mut File, module: &NessaModule, op: &st
         ).expect("Error while writing to docs file");    
     }
 
-    write_args_and_ret(file, annot);
+    write_args_and_ret(file, annot, &[]);
 }
 
 pub fn write_binary_operation_docs(file: &mut File, module: &NessaModule, op: &str, t: usize, args: &Type, ret: &Type, annot: &Annotation) {
@@ -145,7 +153,7 @@ pub fn write_binary_operation_docs(file: &mut File, module: &NessaModule, op: &s
         unreachable!()
     }
 
-    write_args_and_ret(file, annot);
+    write_args_and_ret(file, annot, &[]);
 }
 
 pub fn write_nary_operation_docs(file: &mut File, module: &NessaModule, op_open: &str, op_close: &str, t: usize, args: &Type, ret: &Type, annot: &Annotation) {
@@ -177,7 +185,7 @@ pub fn write_nary_operation_docs(file: &mut File, module: &NessaModule, op_open:
         unreachable!()
     }
 
-    write_args_and_ret(file, annot);
+    write_args_and_ret(file, annot, &[]);
 }
 
 pub fn write_class_docs(file: &mut File, template: &TypeTemplate, annot: &Annotation) {
@@ -225,6 +233,17 @@ pub fn write_syntax_docs(file: &mut File, name: &String, annot: &Annotation) {
     ).expect("Error while writing to docs file");
 }
 
+fn write_interface_hierarchy(file: &mut File, module: &NessaModule, constraint: &InterfaceConstraint, depth: usize) {
+    writeln!(
+        file, 
+        "{}* {}", "  ".repeat(depth), constraint.get_name_html(&module.ctx)
+    ).expect("Error while writing to docs file");
+
+    for p in module.ctx.interface_parents(constraint) {
+        write_interface_hierarchy(file, module, &p, depth + 1);
+    }
+}
+
 pub fn write_interface_docs(file: &mut File, module: &NessaModule, interface: &Interface, annot: &Annotation) {
     write!(
         file,
@@ -239,13 +258,23 @@ pub fn write_interface_docs(file: &mut File, module: &NessaModule, interface: &I
         annot.args.get("0").unwrap()
     ).expect("Error while writing to docs file");
 
+    if !interface.parents.is_empty() {
+        write!(file, "## Parents\n\n").expect("Error while writing to docs file");
+
+        for p in &interface.parents {
+            write_interface_hierarchy(file, module, p, 0);
+        }
+
+        writeln!(file).expect("Error while writing to docs file");
+    }
+
     for f in &interface.fns {
         for a in &f.0 {
             if a.name == "doc" {
                 let args = Type::And(f.3.iter().map(|(_, i)| i).cloned().collect());
                 let templates = f.2.as_ref().map(Vec::len).unwrap_or_default();
 
-                write_function_overload_docs(file, module, &f.1, templates, &args, &f.4, a);
+                write_function_overload_docs(file, module, &f.1, templates, &args, &[], &f.4, a);
                 break;
             }
         }
@@ -297,7 +326,7 @@ pub fn generate_all_function_overload_docs(project_path: &String, module: &Nessa
             if ov.location.module == module.ctx.module_name {
                 for annot in &ov.annotations {
                     if annot.name == "doc" {
-                        write_function_overload_docs(&mut functions_file, &module, &f.name, ov.templates, &ov.args, &ov.ret, annot);
+                        write_function_overload_docs(&mut functions_file, &module, &f.name, ov.templates, &ov.args, &ov.params, &ov.ret, annot);
                         break;
                     }    
                 }
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -10,10 +11,11 @@ use malachite::rounding_modes::RoundingMode;
 
 use crate::config::{precompile_nessa_module_with_config, read_compiled_cache, save_compiled_cache, compute_project_hash};
 use crate::debug::DebugInfo;
+use crate::debugger::NessaDebugger;
 use crate::functions::FunctionOverload;
 use crate::integer_ext::{is_valid_index, to_usize, ONE};
 use crate::nessa_warning;
-use crate::types::Type;
+use crate::types::{Type, ERROR_ID};
 use crate::object::{NessaArray, NessaLambda, NessaTuple, Object, TypeInstance};
 use crate::context::NessaContext;
 use crate::operations::Operator;
@@ -37,7 +39,9 @@ impl NessaContext {
             println!("{:<3} {}", idx, i.to_string(self));
         }
         
-        self.execute_compiled_code::<false>(&compiled_code.into_iter().map(|i| i.instruction).collect::<Vec<_>>(), &[])
+        let (instructions, debug_info): (Vec<_>, Vec<_>) = compiled_code.into_iter().map(|i| (i.instruction, i.debug_info)).unzip();
+
+        self.execute_compiled_code::<false>(&instructions, &debug_info)
     }
 
     pub fn parse_and_execute_nessa_project_inner<const DEBUG: bool>(path: String, macro_code: Option<String>, force_recompile: bool, optimize: bool, test: bool, program_input: &[String]) -> Result<ExecutionInfo, NessaError> {
@@ -110,6 +114,17 @@ impl NessaContext {
     pub fn parse_and_execute_nessa_project<const DEBUG: bool>(path: String, force_recompile: bool, optimize: bool, test: bool, program_input: &[String]) -> Result<ExecutionInfo, NessaError> {        
         Self::parse_and_execute_nessa_project_inner::<DEBUG>(path, None, force_recompile, optimize, test, program_input)
     }
+
+    // Debugging always recompiles without optimizations so every instruction keeps its debug information
+    pub fn debug_nessa_project(path: String, program_input: &[String], debugger: &mut NessaDebugger) -> Result<ExecutionInfo, NessaError> {
+        let (_, all_modules, file_cache) = compute_project_hash(&path, None, false, false)?;
+        let (mut ctx, code) = precompile_nessa_module_with_config(&path, all_modules, file_cache, false, false, true)?;
+
+        let (instructions, debug_info): (Vec<_>, Vec<_>) = ctx.compiled_form(&code)?.into_iter().map(|i| (i.instruction, i.debug_info)).unzip();
+
+        ctx.program_input = program_input.to_vec();
+        ctx.execute_compiled_code_with_debugger(&instructions, &debug_info, debugger)
+    }
 }
 
 #[derive(Serialize)]
@@ -125,18 +140,105 @@ pub struct ExecutionInfo {
     pub captured_output: String
 }
 
+// Function and line of an instruction, used to locate execution errors and debugger stops
+type FrameLocation = (String, Option<(Arc<String>, usize)>);
+
+pub fn frame_location(debug_info: &[DebugInfo], ip: usize) -> Option<FrameLocation> {
+    debug_info.get(ip).map(|info| (
+        info.functions.iter().min().cloned().unwrap_or_else(|| "main".into()),
+        info.lines.iter().min().cloned()
+    ))
+}
+
+pub fn backtrace(debug_info: &[DebugInfo], ip: usize, call_stack: &[(i32, usize, i32)]) -> Vec<String> {
+    // Each caller is stopped at the instruction right before the return address of its callee
+    std::iter::once(ip)
+        .chain(call_stack[1..].iter().rev().map(|(ret, _, _)| *ret as usize - 1))
+        .filter_map(|i| frame_location(debug_info, i))
+        .map(|(f, l)| match l {
+            Some((module, line)) => format!("{} (module {}, line {})", f, module, line),
+            None => f
+        })
+        .collect()
+}
+
+// Execution errors are located at the failing instruction and traced back through every active call
+fn located_execution_error(message: String, debug_info: &[DebugInfo], ip: i32, call_stack: &[(i32, usize, i32)]) -> NessaError {
+    match frame_location(debug_info, ip as usize) {
+        Some((_, Some((module, line)))) => NessaError::located_execution_error(message, module, line, backtrace(debug_info, ip as usize, call_stack)),
+        _ => NessaError::execution_error(message)
+    }
+}
+
+// Checks if the frame at a given depth is running (or calling from) an instruction of a block
+fn block_is_active(start: i32, end: i32, depth: usize, ip: i32, call_stack: &[(i32, usize, i32)]) -> bool {
+    let frame_ip = match depth.cmp(&call_stack.len()) {
+        Ordering::Equal => ip,
+        Ordering::Less => call_stack[depth].0 - 1,
+        Ordering::Greater => return false
+    };
+
+    (start..end).contains(&frame_ip)
+}
+
+// Try block that is currently being executed
+struct TryHandler {
+    start: i32,
+    handler: i32,
+    depth: usize,
+    stack_len: usize,
+    offset: usize
+}
+
+impl TryHandler {
+    fn is_active(&self, ip: i32, call_stack: &[(i32, usize, i32)]) -> bool {
+        block_is_active(self.start, self.handler, self.depth, ip, call_stack)
+    }
+}
+
+// Line that propagates values with ?, along with the stack size before its operands
+struct PropagationBase {
+    start: i32,
+    end: i32,
+    depth: usize,
+    stack_len: usize
+}
+
+impl PropagationBase {
+    fn is_active(&self, ip: i32, call_stack: &[(i32, usize, i32)]) -> bool {
+        block_is_active(self.start, self.end, self.depth, ip, call_stack)
+    }
+}
+
 impl NessaContext {
     pub fn execute_compiled_code<const DEBUG: bool>(&mut self, program: &[CompiledNessaExpr], debug_info: &[DebugInfo]) -> Result<ExecutionInfo, NessaError> {
+        self.execute_compiled_code_from::<DEBUG>(program, debug_info, 0, -1)
+    }
+
+    // Starts at an arbitrary instruction with a main frame that already holds variables up to max_var (used by the REPL)
+    pub fn execute_compiled_code_from<const DEBUG: bool>(&mut self, program: &[CompiledNessaExpr], debug_info: &[DebugInfo], start: usize, max_var: i32) -> Result<ExecutionInfo, NessaError> {
+        self.execute_compiled_code_inner::<DEBUG, false>(program, debug_info, start, max_var, None)
+    }
+
+    pub fn execute_compiled_code_with_debugger(&mut self, program: &[CompiledNessaExpr], debug_info: &[DebugInfo], debugger: &mut NessaDebugger) -> Result<ExecutionInfo, NessaError> {
+        self.execute_compiled_code_inner::<false, true>(program, debug_info, 0, -1, Some(debugger))
+    }
+
+    // The debugger is only checked when STEP is set, so regular executions do not pay for it
+    fn execute_compiled_code_inner<const DEBUG: bool, const STEP: bool>(&mut self, program: &[CompiledNessaExpr], debug_info: &[DebugInfo], start: usize, max_var: i32, mut debugger: Option<&mut NessaDebugger>) -> Result<ExecutionInfo, NessaError> {
         use CompiledNessaExpr::*;
 
         const MAX_STACK_FRAMES: usize = 100000;
 
-        let mut ip: i32 = 0;
+        let mut ip: i32 = start as i32;
         let mut offset: usize = 0;
         
         let mut call_stack: Vec<(i32, usize, i32)> = Vec::with_capacity(1000);
         let mut stack: Vec<Object> = Vec::with_capacity(1000);
 
+        let mut handlers: Vec<TryHandler> = vec!();
+        let mut stack_bases: Vec<PropagationBase> = vec!();
+
         let mut instr_count = FxHashMap::<&str, usize>::default();
         let mut instr_time = FxHashMap::<&str, u128>::default();
         let mut loc_time = FxHashMap::<Arc<String>, FxHashMap<usize, u128>>::default();
@@ -180,6 +282,26 @@ impl NessaContext {
             };
         }
 
+        macro_rules! in_place_op {
+            ($name: expr, $a: ident, $b: ident, $t: ty, $op: expr) => {
+                nessa_instruction!($name, {
+                    let _a = tos!();
+                    let mut _b = tos!();
+
+                    // Both operands are the same value on x += x
+                    if _a.points_to(&_b) {
+                        _b = _b.deep_clone();
+                    }
+
+                    let $a = _a.deref::<$t>();
+                    let $b = &*_b.get::<$t>();
+                        
+                    $op;
+                    ip += 1;
+                })
+            };
+        }
+
         macro_rules! nessa_instruction {
             ($name: expr, $expr: expr) => {
                 if DEBUG {                    
@@ -210,6 +332,29 @@ impl NessaContext {
             };
         }
 
+        macro_rules! located_error {
+            ($msg: expr) => {
+                // Unwind to the innermost active try block and hand it the error
+                match handlers.iter().rposition(|h| h.is_active(ip, &call_stack)) {
+                    Some(idx) => {
+                        handlers.truncate(idx + 1);
+                        let h = handlers.pop().unwrap();
+
+                        call_stack.truncate(h.depth);
+                        stack.truncate(h.stack_len);
+                        stack.push(Object::instance(vec!(Object::new($msg)), vec!(), ERROR_ID));
+
+                        ip = h.handler;
+                        offset = h.offset;
+
+                        continue;
+                    },
+
+                    None => return Err(located_execution_error($msg, debug_info, ip, &call_stack))
+                }
+            };
+        }
+
         macro_rules! idx_op {
             ($deref_arr: ident, $ref_method: ident) => {
                 let arr = tos!();
@@ -219,13 +364,13 @@ impl NessaContext {
                 let idx = &*first.get::<Integer>();
 
                 if !is_valid_index(idx) {
-                    return Err(NessaError::execution_error(format!("{} is not a valid index", idx)));
+                    located_error!(format!("{} is not a valid index", idx));
                 
                 } else {
                     let native_idx = to_usize(idx);
                     
                     if arr.elements.len() <= native_idx {
-                        return Err(NessaError::execution_error(format!("{} is higher than the length of the array ({})", idx, arr.elements.len())));
+                        located_error!(format!("{} is higher than the length of the array ({})", idx, arr.elements.len()));
     
                     } else {
                         stack.push(arr.elements[native_idx].$ref_method());
@@ -239,7 +384,7 @@ impl NessaContext {
         macro_rules! check_call_stack_limit {
             () => {
                 if call_stack.len() > MAX_STACK_FRAMES {
-                    return Err(NessaError::execution_error(format!("Too many stack frames (max. of {})", MAX_STACK_FRAMES)));
+                    located_error!(format!("Too many stack frames (max. of {})", MAX_STACK_FRAMES));
                 }
             }
         }
@@ -284,6 +429,28 @@ impl NessaContext {
             }
         }
 
+        macro_rules! native_call {
+            ($func_id: expr, $ov_id: expr, $type_args: expr) => {
+                if let FunctionOverload { args: Type::And(v), ret: r, function: Some(f), .. } = &self.functions[*$func_id].overloads[*$ov_id] {
+                    let mut args = Vec::with_capacity(v.len());
+
+                    for _ in v {
+                        args.push(tos!());
+                    }
+
+                    match f($type_args, r, args, self) {
+                        Ok(obj) => stack.push(obj),
+                        Err(msg) => located_error!(msg)
+                    };
+
+                    ip += 1;
+                
+                } else {
+                    unreachable!();
+                }
+            };
+        }
+
         macro_rules! lambda_call {
             ($lambda_ref: ident) => {
                 let arg = tos!();
@@ -295,9 +462,17 @@ impl NessaContext {
             };
         }
 
-        call_stack.push((0, 0, -1));
+        call_stack.push((0, 0, max_var));
 
         loop {
+            if STEP {
+                if let Some(d) = debugger.as_deref_mut() {
+                    if !d.before_instruction(self, program, debug_info, ip as usize, &call_stack, offset) {
+                        break;
+                    }
+                }
+            }
+
             match fetch_opcode!() {
                 Empty => nessa_instruction!("Empty", {
                     stack.push(Object::empty());
@@ -433,6 +608,73 @@ impl NessaContext {
                     ip += 1;
                 }),
 
+                MatchType(t) => nessa_instruction!("MatchType", {
+                    let a = tos!();
+                    stack.push(Object::new(a.deref_if_ref().get_type().bindable_to(t, self)));
+                    ip += 1;
+                }),
+
+                MatchValue => nessa_instruction!("MatchValue", {
+                    let a = tos!();
+                    let b = tos!();
+                    stack.push(Object::new(*a.deref_if_ref().inner.borrow() == *b.deref_if_ref().inner.borrow()));
+                    ip += 1;
+                }),
+
+                Try(to) => nessa_instruction!("Try", {
+                    // Jumps out of a try body (break, continue or returns from do blocks) leave stale handlers behind
+                    while handlers.last().is_some_and(|h| !h.is_active(ip, &call_stack)) {
+                        handlers.pop();
+                    }
+
+                    handlers.push(TryHandler {
+                        start: ip + 1,
+                        handler: ip + *to as i32,
+                        depth: call_stack.len(),
+                        stack_len: stack.len(),
+                        offset
+                    });
+
+                    ip += 1;
+                }),
+
+                EndTry => nessa_instruction!("EndTry", {
+                    // Pop every stale handler on top of the one of this block
+                    while handlers.pop().is_some_and(|h| h.start > ip || h.handler <= ip) {}
+                    ip += 1;
+                }),
+
+                StackBase(to) => nessa_instruction!("StackBase", {
+                    // Lines left by jumps or tail calls (and earlier runs of this one) leave stale bases behind
+                    while stack_bases.last().is_some_and(|b| !b.is_active(ip, &call_stack) || (b.start == ip + 1 && b.depth == call_stack.len())) {
+                        stack_bases.pop();
+                    }
+
+                    stack_bases.push(PropagationBase {
+                        start: ip + 1,
+                        end: ip + *to as i32,
+                        depth: call_stack.len(),
+                        stack_len: stack.len()
+                    });
+
+                    ip += 1;
+                }),
+
+                EndStackBase => nessa_instruction!("EndStackBase", {
+                    while stack_bases.pop().is_some_and(|b| b.end != ip || b.depth != call_stack.len()) {}
+                    ip += 1;
+                }),
+
+                ClearStack => nessa_instruction!("ClearStack", {
+                    // Keep the propagated value and drop the operands of its line
+                    let value = tos!();
+                    let base = stack_bases.iter().rev().find(|b| b.is_active(ip, &call_stack)).unwrap();
+
+                    stack.truncate(base.stack_len);
+                    stack.push(value);
+                    ip += 1;
+                }),
+
                 IdxMove => nessa_instruction!("IdxMove", { idx_op!(get, move_contents_if_ref); }),
                 IdxRef => nessa_instruction!("IdxRef", { idx_op!(deref, get_ref_nostack); }),
                 IdxMut => nessa_instruction!("IdxMut", { idx_op!(deref, get_mut_nostack); }),
@@ -503,7 +745,7 @@ impl NessaContext {
                     let b = tos!();
 
                     if let Err(msg) = a.assign(b, self) {
-                        return Err(NessaError::execution_error(msg));
+                        located_error!(msg);
                     }
 
                     ip += 1;
@@ -514,7 +756,7 @@ impl NessaContext {
                     let value = tos!();
 
                     if let Err(msg) = var.assign_direct(value, self) {
-                        return Err(NessaError::execution_error(msg));
+                        located_error!(msg);
                     }
 
                     ip += 1;
@@ -525,7 +767,7 @@ impl NessaContext {
                     let value = tos!();
 
                     if let Err(msg) = var.assign(value, self) {
-                        return Err(NessaError::execution_error(msg));
+                        located_error!(msg);
                     }
 
                     ip += 1;
@@ -588,25 +830,30 @@ impl NessaContext {
 
                     ip = prev_ip;
                     offset = prev_offset;
-                }), 
 
-                NativeFunctionCall(func_id, ov_id, type_args) => nessa_instruction!("NativeFunctionCall", {
-                    if let FunctionOverload { args: Type::And(v), ret: r, function: Some(f), .. } = &self.functions[*func_id].overloads[*ov_id] {
-                        let mut args = Vec::with_capacity(v.len());
+                    // Try blocks and lines left by returning from inside them
+                    while handlers.last().is_some_and(|h| h.depth > call_stack.len()) {
+                        handlers.pop();
+                    }
 
-                        for _ in v {
-                            args.push(tos!());
-                        }
+                    while stack_bases.last().is_some_and(|b| b.depth > call_stack.len()) {
+                        stack_bases.pop();
+                    }
+                }), 
 
-                        match f(type_args, r, args, self) {
-                            Ok(obj) => stack.push(obj),
-                            Err(msg) => return Err(NessaError::execution_error(msg))
-                        };
+                NativeFunctionCall(func_id, ov_id, type_args) => nessa_instruction!("NativeFunctionCall", { native_call!(func_id, ov_id, type_args); }),
 
-                        ip += 1;
-                    
-                    } else {
-                        unreachable!();
+                DynamicCall(func_id, table) => nessa_instruction!("DynamicCall", {
+                    let arg_type = stack.last().unwrap().deref_if_ref().get_type();
+
+                    match table.iter().find(|(t, _)| *t == arg_type) {
+                        Some((_, Call(to))) => { add_stack_frame!(*to as i32); },
+                        Some((_, NativeFunctionCall(func_id, ov_id, type_args))) => { native_call!(func_id, ov_id, type_args); },
+                        
+                        _ => located_error!(format!(
+                            "Unable to find an implementation of {} for values of type {}", 
+                            self.functions[*func_id].name, arg_type.get_name_plain(self)
+                        ))
                     }
                 }),
 
@@ -619,7 +866,7 @@ impl NessaContext {
                         }
 
                         if let Err(msg) = f(type_args, r, args, self) {
-                            return Err(NessaError::execution_error(msg));
+                            located_error!(msg);
                         };
 
                         ip += 1;
@@ -637,7 +884,7 @@ impl NessaContext {
 
                         match ov.operation.unwrap()(type_args, &ov.ret, obj) {
                             Ok(obj) => stack.push(obj),
-                            Err(msg) => return Err(NessaError::execution_error(msg))
+                            Err(msg) => located_error!(msg)
                         };
 
                         ip += 1;
@@ -654,7 +901,7 @@ impl NessaContext {
                         let ov = &operations[*ov_id];
 
                         if let Err(msg) = ov.operation.unwrap()(type_args, &ov.ret, obj) {
-                            return Err(NessaError::execution_error(msg));
+                            located_error!(msg);
                         };
 
                         ip += 1;
@@ -673,7 +920,7 @@ impl NessaContext {
 
                         match ov.operation.unwrap()(type_args, &ov.ret, a, b, self) {
                             Ok(obj) => stack.push(obj),
-                            Err(msg) => return Err(NessaError::execution_error(msg))
+                            Err(msg) => located_error!(msg)
                         };
                         
                         ip += 1;
@@ -691,7 +938,7 @@ impl NessaContext {
                         let ov = &operations[*ov_id];
 
                         if let Err(msg) = ov.operation.unwrap()(type_args, &ov.ret, a, b, self) {
-                            return Err(NessaError::execution_error(msg));
+                            located_error!(msg);
                         };
                         
                         ip += 1;
@@ -707,7 +954,7 @@ impl NessaContext {
                         let res = op_ov.operation.unwrap()((&mut stack, &mut offset, &mut call_stack, &mut ip), type_args, &op_ov.ret);
 
                         if let Err(msg) = res {
-                            return Err(NessaError::execution_error(msg));
+                            located_error!(msg);
                         }
 
                     } else {
@@ -780,6 +1027,18 @@ impl NessaContext {
 
                 AddStr => bin_op!("AddStr", a, b, get, get, String, format!("{}{}", a, b)),
 
+                AddAssigni => in_place_op!("AddAssigni", a, b, Integer, *a += b),
+                SubAssigni => in_place_op!("SubAssigni", a, b, Integer, *a -= b),
+                MulAssigni => in_place_op!("MulAssigni", a, b, Integer, *a *= b),
+                DivAssigni => in_place_op!("DivAssigni", a, b, Integer, *a /= b),
+                ModAssigni => in_place_op!("ModAssigni", a, b, Integer, *a %= b),
+                AddAssignf => in_place_op!("AddAssignf", a, b, f64, *a += b),
+                SubAssignf => in_place_op!("SubAssignf", a, b, f64, *a -= b),
+                MulAssignf => in_place_op!("MulAssignf", a, b, f64, *a *= b),
+                DivAssignf => in_place_op!("DivAssignf", a, b, f64, *a /= b),
+                ModAssignf => in_place_op!("ModAssignf", a, b, f64, *a %= b),
+                AddAssignStr => in_place_op!("AddAssignStr", a, b, String, a.push_str(b)),
+
                 NotB => unary_op!("NotB", a, get, Integer, !a),
                 AndB => bin_op!("AndB", a, b, get, get, Integer, a & b),
                 OrB => bin_op!("OrB", a, b, get, get, Integer, a | b),
@@ -1469,4 +1728,37 @@ mod tests {
 
         assert_eq!(ctx.variables[2], Object::new(Integer::from(25)));
     }
+
+    #[test]
+    fn execution_error_locations() {
+        let mut ctx = standard_ctx();
+        
+        let code_str = "
+        fn get(a: @Array<Int>, i: Int) -> Int {
+            return *a[*i];
+        }
+
+        fn outer(a: Array<Int>) -> Int {
+            return get(a, 10);
+        }
+
+        let arr = arr<Int>();
+        arr.push(1);
+
+        let b = outer(*arr);
+        ".to_string();
+
+        let err = match ctx.parse_and_execute_nessa_module(&code_str) {
+            Err(err) => err,
+            Ok(_) => panic!("Execution should fail")
+        };
+
+        assert_eq!(err.line, 3);
+        assert_eq!(err.module, ctx.module_name);
+        assert_eq!(err.backtrace.len(), 3);
+        assert!(err.backtrace[0].starts_with("fn get("));
+        assert!(err.backtrace[1].starts_with("fn outer("));
+        assert!(err.backtrace[2].starts_with("main ("));
+        assert!(err.backtrace[2].ends_with("line 13)"));
+    }
 }
\ No newline at end of file
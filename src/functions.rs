@@ -4,6 +4,8 @@ use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
 use rand::Rng;
+use regex::Match;
+use regex::Regex;
 use seq_macro::seq;
 use malachite::Integer;
 use malachite::num::arithmetic::traits::Abs;
@@ -13,9 +15,14 @@ use serde::Serialize;
 use crate::annotations::Annotation;
 use crate::compilation::CompiledNessaExpr;
 use crate::integer_ext::*;
+use crate::interfaces::STRINGABLE;
 use crate::parser::Location;
+use crate::parser::NessaExpr;
 use crate::ARR_IT_OF;
 use crate::ARR_OF;
+use crate::HASH_IT_OF;
+use crate::MAP_OF;
+use crate::SET_OF;
 use crate::types::*;
 use crate::object::*;
 use crate::context::NessaContext;
@@ -36,12 +43,63 @@ pub struct FunctionOverload {
     pub annotations: Vec<Annotation>,
     pub templates: usize,
     pub args: Type,
+    pub params: Vec<(String, Option<NessaExpr>)>, // Names and default values of the arguments (empty for native functions)
+    pub variadic: bool, // The last argument is an array that collects the rest of the arguments of a call
     pub ret: Type,
 
     #[serde(skip)]
     pub function: OptFunctionOverloadFn
 }
 
+impl FunctionOverload {
+    pub fn arity(&self) -> usize {
+        match &self.args {
+            Type::And(v) => v.len(),
+            _ => unreachable!()
+        }
+    }
+
+    // Minimum number of arguments of a call, since trailing parameters with default values can be omitted and variadic ones can be empty
+    pub fn required_args(&self) -> usize {
+        self.arity() - self.params.iter().filter(|(_, d)| d.is_some()).count() - self.variadic as usize
+    }
+
+    // Type of the elements collected by the variadic argument
+    pub fn variadic_type(&self) -> Option<&Type> {
+        match &self.args {
+            Type::And(v) if self.variadic => match v.last() {
+                Some(Type::Template(ARR_ID, t)) => Some(&t[0]),
+                _ => unreachable!()
+            },
+
+            _ => None
+        }
+    }
+
+    // Argument types that a call with n arguments can match, in order of preference
+    pub fn call_args(&self, n: usize) -> Vec<Type> {
+        let mut res = vec!();
+
+        if let Type::And(v) = &self.args {
+            // Arrays can be passed directly to variadic arguments
+            if v.len() == n {
+                res.push(self.args.clone());
+            }
+
+            if let Some(t) = self.variadic_type() {
+                if n + 1 >= v.len() {
+                    res.push(Type::And(v[..v.len() - 1].iter().cloned().chain(std::iter::repeat_n(t.clone(), n + 1 - v.len())).collect()));
+                }
+
+            } else if self.required_args() <= n && n < v.len() {
+                res.push(Type::And(v[..n].to_vec()));
+            }
+        }
+
+        res
+    }
+}
+
 pub type FunctionOverloads = Vec<FunctionOverload>;
 
 const EMPTY_FUNC: FunctionOverloadFn = |_, _, _, _| Ok(Object::empty());
@@ -111,6 +169,146 @@ macro_rules! define_binary_function_overloads {
     };
 }
 
+macro_rules! define_function_with_args {
+    ($ctx: ident, $id: expr, $inner_type: expr, [$($arg_type: expr),*], $return_type: expr, $unwrap_type: ident, $a: ident, $deref: ident, $args: ident, $result: expr) => {
+        $ctx.define_native_function_overload(
+            $id, 0,
+            &[$inner_type, $($arg_type),*], 
+            $return_type,
+            |_, _, $args, _| {
+                let $a = $args[0].$deref::<$unwrap_type>();
+                return Ok(Object::new($result));
+            }
+        ).unwrap();
+    };
+}
+
+// Only the first argument gets reference overloads, the rest are read from the argument vector
+macro_rules! define_function_with_args_overloads {
+    ($ctx: ident, $id: expr, $inner_type: expr, [$($arg_type: expr),*], $return_type: expr, $unwrap_type: ident, $a: ident, $args: ident, $result: expr) => {
+        define_function_with_args!($ctx, $id, $inner_type, [$($arg_type),*], $return_type, $unwrap_type, $a, get, $args, $result);
+        define_function_with_args!($ctx, $id, Type::Ref(Box::new($inner_type)), [$($arg_type),*], $return_type, $unwrap_type, $a, deref, $args, $result);
+        define_function_with_args!($ctx, $id, Type::MutRef(Box::new($inner_type)), [$($arg_type),*], $return_type, $unwrap_type, $a, deref, $args, $result);
+    };
+}
+
+// Size in bytes of the largest string that repeat can build
+const MAX_REPEAT_SIZE: usize = 1 << 30;
+
+// Reads a string argument that can be passed by value or by reference
+fn str_arg(obj: &Object) -> &String {
+    match obj.inner.borrow() {
+        ObjectBlock::Ref(_) | ObjectBlock::Mut(_) => obj.deref::<String>(),
+        _ => obj.get::<String>()
+    }
+}
+
+fn str_array(elements: Vec<String>) -> NessaArray {
+    NessaArray { elements: elements.into_iter().map(Object::new).collect(), elem_type: Box::new(STR) }
+}
+
+// Formats a single argument using a specifier of the form :[<>^][width][.precision]
+fn format_arg(obj: &Object, spec: &str, ctx: &NessaContext) -> Result<String, String> {
+    let inner = match spec.strip_prefix(':') {
+        Some(i) => i,
+        None if spec.is_empty() => spec,
+        None => return Err(format!("Invalid format specifier {{{}}}", spec))
+    };
+
+    let (align, inner) = match inner.chars().next() {
+        Some(a @ ('<' | '>' | '^')) => (Some(a), &inner[1..]),
+        _ => (None, inner)
+    };
+
+    let (width, precision) = match inner.split_once('.') {
+        Some((w, p)) => (w, Some(p)),
+        None => (inner, None)
+    };
+
+    let parse_num = |n: &str| n.parse::<usize>().map_err(|_| format!("Invalid format specifier {{{}}}", spec));
+    let width = if width.is_empty() { 0 } else { parse_num(width)? };
+    let precision = precision.map(parse_num).transpose()?;
+
+    let obj = obj.deref_if_ref();
+
+    let (text, numeric) = match obj.inner.borrow() {
+        ObjectBlock::Int(i) => (i.to_string(), true),
+        ObjectBlock::Float(f) => (precision.map(|p| format!("{:.*}", p, f)).unwrap_or_else(|| f.to_string()), true),
+        ObjectBlock::Str(s) => (precision.map(|p| s.chars().take(p).collect()).unwrap_or_else(|| s.clone()), false),
+        _ => (obj.to_display_string(ctx), false)
+    };
+
+    let pad = width.saturating_sub(text.chars().count());
+
+    // Numbers are aligned to the right by default
+    Ok(match align.unwrap_or(if numeric { '>' } else { '<' }) {
+        '<' => format!("{}{}", text, " ".repeat(pad)),
+        '>' => format!("{}{}", " ".repeat(pad), text),
+        _ => format!("{}{}{}", " ".repeat(pad / 2), text, " ".repeat(pad - pad / 2))
+    })
+}
+
+fn format_string(template: &str, args: &[Object], ctx: &NessaContext) -> Result<String, String> {
+    let mut res = String::new();
+    let mut chars = template.chars().peekable();
+    let mut idx = 0;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => { chars.next(); res.push('{'); },
+            '}' if chars.peek() == Some(&'}') => { chars.next(); res.push('}'); },
+
+            '{' => {
+                let mut spec = String::new();
+                let mut closed = false;
+
+                for i in chars.by_ref() {
+                    if i == '}' {
+                        closed = true;
+                        break;
+                    }
+
+                    spec.push(i);
+                }
+
+                if !closed {
+                    return Err("Unclosed { in format string".into());
+                }
+
+                if let Some(arg) = args.get(idx) {
+                    res.push_str(&format_arg(arg, &spec, ctx)?);
+                }
+
+                idx += 1;
+            },
+
+            '}' => return Err("Unmatched } in format string".into()),
+
+            _ => res.push(c)
+        }
+    }
+
+    if idx != args.len() {
+        return Err(format!("Format string expects {} arguments (got {})", idx, args.len()));
+    }
+
+    Ok(res)
+}
+
+// Builds a (start, end, text) tuple in code points. The last known (byte, code point) position is 
+// kept in offset so matches do not have to be counted from the start of the string
+fn regex_match(string: &str, m: &Match, offset: &mut (usize, usize)) -> Object {
+    let start = offset.1 + string[offset.0..m.start()].chars().count();
+    let end = start + m.as_str().chars().count();
+
+    *offset = (m.start(), start);
+
+    Object::tuple(
+        vec!(Object::new(Integer::from(start as u64)), Object::new(Integer::from(end as u64)), Object::new(m.as_str().to_string())),
+        vec!(INT, INT, STR)
+    )
+}
+
 // Constant identifiers
 pub const ITERATOR_FUNC_ID: usize = 10;
 pub const NEXT_FUNC_ID: usize = 11;
@@ -387,6 +585,12 @@ pub fn standard_functions(ctx: &mut NessaContext) {
         }
     ).unwrap();
 
+    let idx = ctx.define_function("Error".into()).unwrap();
+
+    ctx.define_native_function_overload(idx, 0, &[STR], ERROR, |_, _, v, _| {
+        Ok(Object::instance(v, vec!(), ERROR_ID))
+    }).unwrap();
+
     let idx = ctx.define_function("len".into()).unwrap();
 
     ctx.define_native_function_overload(
@@ -497,6 +701,27 @@ pub fn standard_functions(ctx: &mut NessaContext) {
     define_unary_function_overloads!(ctx, idx, INT, INT, Integer, a, a.clone().abs());
     define_unary_function_overloads!(ctx, idx, FLOAT, FLOAT, f64, a, a.abs());
 
+    // Variadic, the rest of the arguments are collected in an array
+    let idx = ctx.define_function("max".into()).unwrap();
+
+    ctx.define_native_variadic_function_overload(idx, 0, &[INT, ARR_OF!(INT)], INT, |_, _, v, _| {
+        Ok(Object::new(v[1].get::<NessaArray>().elements.iter().map(|i| i.get::<Integer>()).fold(v[0].get::<Integer>(), |a, b| a.max(b)).clone()))
+    }).unwrap();
+
+    ctx.define_native_variadic_function_overload(idx, 0, &[FLOAT, ARR_OF!(FLOAT)], FLOAT, |_, _, v, _| {
+        Ok(Object::new(v[1].get::<NessaArray>().elements.iter().map(|i| *i.get::<f64>()).fold(*v[0].get::<f64>(), f64::max)))
+    }).unwrap();
+
+    let idx = ctx.define_function("min".into()).unwrap();
+
+    ctx.define_native_variadic_function_overload(idx, 0, &[INT, ARR_OF!(INT)], INT, |_, _, v, _| {
+        Ok(Object::new(v[1].get::<NessaArray>().elements.iter().map(|i| i.get::<Integer>()).fold(v[0].get::<Integer>(), |a, b| a.min(b)).clone()))
+    }).unwrap();
+
+    ctx.define_native_variadic_function_overload(idx, 0, &[FLOAT, ARR_OF!(FLOAT)], FLOAT, |_, _, v, _| {
+        Ok(Object::new(v[1].get::<NessaArray>().elements.iter().map(|i| *i.get::<f64>()).fold(*v[0].get::<f64>(), f64::min)))
+    }).unwrap();
+
     let idx = ctx.define_function("rand".into()).unwrap();
 
     ctx.define_native_function_overload(idx, 0, &[], FLOAT, |_, _, _, _| Ok(Object::new(rand::thread_rng().gen_range(0.0..1.0)))).unwrap();
@@ -516,7 +741,17 @@ pub fn standard_functions(ctx: &mut NessaContext) {
         1,
         &[Type::Wildcard], 
         BOOL, 
-        |t, _, v, ctx| Ok(Object::new(v[0].get_type().bindable_to(&t[0], ctx)))
+        |t, _, v, ctx| {
+            let obj_type = v[0].get_type();
+
+            // Types that are not references are checked against the referenced value
+            if t[0].is_ref() {
+                Ok(Object::new(obj_type.bindable_to(&t[0], ctx)))
+
+            } else {
+                Ok(Object::new(obj_type.deref_type().bindable_to(&t[0], ctx)))
+            }
+        }
     ).unwrap();
 
     let idx = ctx.define_function("as".into()).unwrap();
@@ -771,16 +1006,59 @@ pub fn standard_functions(ctx: &mut NessaContext) {
 
     let idx = ctx.define_function("to_string".into()).unwrap();
 
-    ctx.define_native_function_overload(idx, 0, &[INT], STR, |_, _, mut v, _| {
-        let obj = v.pop().unwrap().get::<Integer>().to_string();
+    define_unary_function_overloads!(ctx, idx, INT, STR, Integer, a, a.to_string());
+    define_unary_function_overloads!(ctx, idx, FLOAT, STR, f64, a, a.to_string());
+    define_unary_function_overloads!(ctx, idx, BOOL, STR, bool, a, a.to_string());
+    define_unary_function_overloads!(ctx, idx, STR, STR, String, a, a.clone());
+
+    // Containers and the rest of built-in types are converted structurally as long as their contents are Stringable
+    let stringable_t = |i| Type::TemplateParam(i, vec!(STRINGABLE));
+    let mut containers = vec!(
+        (1, ARR_OF!(stringable_t(0))),
+        (2, MAP_OF!(stringable_t(0), stringable_t(1))),
+        (1, SET_OF!(stringable_t(0))),
+        (0, RANGE),
+        (0, REGEX),
+        (0, ERROR),
+        (0, Type::Empty)
+    );
 
-        Ok(ObjectBlock::Str(obj).to_obj())
-    }).unwrap();
+    // Max tuple size is 10 for now
+    containers.extend((2..10).map(|n| (n, Type::And((0..n).map(stringable_t).collect()))));
+
+    for (n, t) in containers {
+        for f in [t.clone(), t.clone().to_ref(), t.to_mut()] {
+            ctx.define_native_function_overload(idx, n, &[f], STR, |_, _, v, ctx| {
+                Ok(Object::new(v[0].to_display_string(ctx)))
+            }).unwrap();
+        }
+    }
+
+    let idx = ctx.define_function("format".into()).unwrap();
+
+    for n in 0..10 {
+        let args = std::iter::once(Type::Or(vec!(STR, STR.to_ref(), STR.to_mut()))).chain((0..n).map(stringable_t)).collect::<Vec<_>>();
+
+        ctx.define_native_function_overload(idx, n, &args, STR, |_, _, v, ctx| {
+            Ok(Object::new(format_string(str_arg(&v[0]), &v[1..], ctx)?))
+        }).unwrap();
+    }
+
+    let idx = ctx.define_function("parse".into()).unwrap();
 
-    ctx.define_native_function_overload(idx, 0, &[FLOAT], STR, |_, _, mut v, _| {
-        let obj = v.pop().unwrap().get::<f64>().to_string();
+    ctx.define_native_function_overload(idx, 1, &[Type::Or(vec!(STR, STR.to_ref(), STR.to_mut()))], T_0.or(ERROR), |t, _, v, ctx| {
+        match &t[0] {
+            Type::Basic(id) if ctx.type_templates[*id].parser.is_some() => {
+                let parser = ctx.type_templates[*id].parser.unwrap();
 
-        Ok(ObjectBlock::Str(obj).to_obj())
+                // Invalid formats are returned as values so they can be handled by the caller
+                Ok(parser(ctx, &ctx.type_templates[*id], str_arg(&v[0])).unwrap_or_else(|msg| {
+                    Object::instance(vec!(Object::new(msg)), vec!(), ERROR_ID)
+                }))
+            },
+
+            t => Err(format!("Unable to parse values of type {}", t.get_name_plain(ctx)))
+        }
     }).unwrap();
 
     // String functions
@@ -875,6 +1153,165 @@ pub fn standard_functions(ctx: &mut NessaContext) {
         }
     }).unwrap();
 
+    // String library, indices are measured in code points
+    let str_arg_t = Type::Or(vec!(STR, STR.to_ref(), STR.to_mut()));
+
+    let idx = ctx.define_function("split".into()).unwrap();
+
+    define_binary_function_overloads!(ctx, idx, STR, ARR_OF!(STR), String, a, b, {
+        if b.is_empty() {
+            return Err("Unable to split a string using an empty separator".into());
+        }
+
+        str_array(a.split(b.as_str()).map(str::to_string).collect())
+    });
+
+    let idx = ctx.define_function("join".into()).unwrap();
+
+    define_function_with_args_overloads!(ctx, idx, ARR_OF!(STR), [str_arg_t.clone()], STR, NessaArray, a, v, {
+        a.elements.iter().map(|i| i.get::<String>().as_str()).collect::<Vec<_>>().join(str_arg(&v[1]))
+    });
+
+    let idx = ctx.define_function("find".into()).unwrap();
+
+    // Returns -1 if the pattern is not found
+    define_binary_function_overloads!(ctx, idx, STR, INT, String, a, b, {
+        Integer::from(a.find(b.as_str()).map(|i| a[..i].chars().count() as i64).unwrap_or(-1))
+    });
+
+    let idx = ctx.define_function("replace".into()).unwrap();
+
+    define_function_with_args_overloads!(ctx, idx, STR, [str_arg_t.clone(), str_arg_t.clone()], STR, String, a, v, {
+        a.replace(str_arg(&v[1]).as_str(), str_arg(&v[2]))
+    });
+
+    let idx = ctx.define_function("trim".into()).unwrap();
+    define_unary_function_overloads!(ctx, idx, STR, STR, String, a, a.trim().to_string());
+
+    let idx = ctx.define_function("trim_start".into()).unwrap();
+    define_unary_function_overloads!(ctx, idx, STR, STR, String, a, a.trim_start().to_string());
+
+    let idx = ctx.define_function("trim_end".into()).unwrap();
+    define_unary_function_overloads!(ctx, idx, STR, STR, String, a, a.trim_end().to_string());
+
+    let idx = ctx.define_function("starts_with".into()).unwrap();
+    define_binary_function_overloads!(ctx, idx, STR, BOOL, String, a, b, a.starts_with(b.as_str()));
+
+    let idx = ctx.define_function("ends_with".into()).unwrap();
+    define_binary_function_overloads!(ctx, idx, STR, BOOL, String, a, b, a.ends_with(b.as_str()));
+
+    let idx = ctx.define_function("contains".into()).unwrap();
+    define_binary_function_overloads!(ctx, idx, STR, BOOL, String, a, b, a.contains(b.as_str()));
+
+    let idx = ctx.define_function("substring".into()).unwrap();
+
+    define_function_with_args_overloads!(ctx, idx, STR, [INT, INT], STR, String, a, v, {
+        let start = v[1].get::<Integer>();
+        let end = v[2].get::<Integer>();
+
+        if !is_valid_index(start) || !is_valid_index(end) || start > end {
+            return Err(format!("[{}, {}) is not a valid substring range", start, end));
+        }
+
+        let length = a.chars().count();
+
+        if to_usize(end) > length {
+            return Err(format!("{} is higher than the number of code points in the string ({})", end, length));
+        }
+
+        a.chars().skip(to_usize(start)).take(to_usize(end) - to_usize(start)).collect::<String>()
+    });
+
+    let idx = ctx.define_function("to_upper".into()).unwrap();
+    define_unary_function_overloads!(ctx, idx, STR, STR, String, a, a.to_uppercase());
+
+    let idx = ctx.define_function("to_lower".into()).unwrap();
+    define_unary_function_overloads!(ctx, idx, STR, STR, String, a, a.to_lowercase());
+
+    let idx = ctx.define_function("repeat".into()).unwrap();
+
+    define_function_with_args_overloads!(ctx, idx, STR, [INT], STR, String, a, v, {
+        let times = v[1].get::<Integer>();
+
+        if !is_valid_index(times) {
+            return Err(format!("Unable to repeat a string {} times", times));
+        }
+
+        // Huge results have to fail before allocating instead of aborting the process
+        let mut res = String::new();
+        let size = a.len().checked_mul(to_usize(times)).filter(|s| *s <= MAX_REPEAT_SIZE);
+
+        if size.is_none_or(|s| res.try_reserve_exact(s).is_err()) {
+            return Err(format!("Unable to repeat a string {} times", times));
+        }
+
+        for _ in 0..to_usize(times) {
+            res.push_str(a);
+        }
+
+        res
+    });
+
+    let idx = ctx.define_function("lines".into()).unwrap();
+    define_unary_function_overloads!(ctx, idx, STR, ARR_OF!(STR), String, a, str_array(a.lines().map(str::to_string).collect()));
+
+    // Regular expressions
+    let match_t = Type::And(vec!(INT, INT, STR));
+
+    let idx = ctx.define_function("regex".into()).unwrap();
+
+    define_unary_function_overloads!(ctx, idx, STR, REGEX, String, a, {
+        match Regex::new(a) {
+            Ok(regex) => NessaRegex { regex },
+            Err(err) => return Err(err.to_string())
+        }
+    });
+
+    let idx = ctx.define_function("is_match".into()).unwrap();
+
+    define_function_with_args_overloads!(ctx, idx, REGEX, [str_arg_t.clone()], BOOL, NessaRegex, a, v, a.regex.is_match(str_arg(&v[1])));
+
+    let idx = ctx.define_function("find_all".into()).unwrap();
+
+    define_function_with_args_overloads!(ctx, idx, REGEX, [str_arg_t.clone()], ARR_OF!(match_t.clone()), NessaRegex, a, v, {
+        let string = str_arg(&v[1]);
+        let mut offset = (0, 0);
+
+        NessaArray { 
+            elements: a.regex.find_iter(string).map(|m| regex_match(string, &m, &mut offset)).collect(), 
+            elem_type: Box::new(Type::And(vec!(INT, INT, STR)))
+        }
+    });
+
+    let idx = ctx.define_function("captures".into()).unwrap();
+
+    // Groups that do not participate in a match are returned as ()
+    define_function_with_args_overloads!(ctx, idx, REGEX, [str_arg_t.clone()], ARR_OF!(ARR_OF!(match_t.clone().or(Type::Empty))), NessaRegex, a, v, {
+        let string = str_arg(&v[1]);
+        let group_t = Type::And(vec!(INT, INT, STR)).or(Type::Empty);
+        let mut offset = (0, 0);
+
+        let elements = a.regex.captures_iter(string).map(|caps| {
+            regex_match(string, &caps.get(0).unwrap(), &mut offset);
+
+            let groups = caps.iter().map(|g| match g {
+                Some(g) => regex_match(string, &g, &mut offset.clone()),
+                None => Object::empty()
+            }).collect();
+
+            Object::arr(groups, group_t.clone())
+        }).collect();
+
+        NessaArray { elements, elem_type: Box::new(ARR_OF!(group_t)) }
+    });
+
+    let idx = ctx.define_function("replace_all".into()).unwrap();
+
+    // Replacements can reference groups using the $name syntax
+    define_function_with_args_overloads!(ctx, idx, REGEX, [str_arg_t.clone(), str_arg_t.clone()], STR, NessaRegex, a, v, {
+        a.regex.replace_all(str_arg(&v[1]), str_arg(&v[2]).as_str()).into_owned()
+    });
+
     let idx = ctx.define_function("truncate".into()).unwrap();
 
     ctx.define_native_function_overload(idx, 0, &[INT], INT, |_, _, v, _| {
@@ -1001,6 +1438,249 @@ pub fn standard_functions(ctx: &mut NessaContext) {
         }
     ).unwrap();
 
+    // Hash tables
+    let key_t = |t: Type| t.clone().to_ref().or(t.clone().to_mut()).or(t);
+
+    let idx = ctx.define_function("Map".into()).unwrap();
+
+    ctx.define_native_function_overload(idx, 2, &[], MAP_OF!(T_0, T_1), |t, _, _, _| Ok(Object::map(t[0].clone(), t[1].clone()))).unwrap();
+
+    let idx = ctx.define_function("Set".into()).unwrap();
+
+    ctx.define_native_function_overload(idx, 1, &[], SET_OF!(T_0), |t, _, _, _| Ok(Object::set(t[0].clone()))).unwrap();
+
+    let idx = ctx.get_function_id("insert".into()).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        2,
+        &[MAP_OF!(T_0, T_1).to_mut(), T_0, T_1], 
+        Type::Empty, 
+        |_, _, v, ctx| {
+            let map = v[0].deref::<NessaMap>();
+            map.table.insert(v[1].clone(), Some(v[2].clone()), ctx)?;
+
+            Ok(Object::empty())
+        }
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        1,
+        &[SET_OF!(T_0).to_mut(), T_0], 
+        Type::Empty, 
+        |_, _, v, ctx| {
+            let set = v[0].deref::<NessaSet>();
+            set.table.insert(v[1].clone(), None, ctx)?;
+
+            Ok(Object::empty())
+        }
+    ).unwrap();
+
+    let idx = ctx.define_function("get".into()).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        2,
+        &[MAP_OF!(T_0, T_1).to_ref(), key_t(T_0)], 
+        T_1.to_ref(), 
+        |_, _, v, ctx| {
+            let map = v[0].deref::<NessaMap>();
+
+            match map.table.find(&v[1], ctx)? {
+                Some(i) => Ok(map.table.values[i].get_ref()),
+                None => Err(format!("Key {} was not found in map", v[1].to_display_string(ctx)))
+            }
+        }
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        2,
+        &[MAP_OF!(T_0, T_1).to_mut(), key_t(T_0)], 
+        T_1.to_mut(), 
+        |_, _, v, ctx| {
+            let map = v[0].deref::<NessaMap>();
+
+            match map.table.find(&v[1], ctx)? {
+                Some(i) => Ok(map.table.values[i].get_mut()),
+                None => Err(format!("Key {} was not found in map", v[1].to_display_string(ctx)))
+            }
+        }
+    ).unwrap();
+
+    let idx = ctx.get_function_id("contains".into()).unwrap();
+
+    for map_t in [MAP_OF!(T_0, T_1).to_ref(), MAP_OF!(T_0, T_1).to_mut()] {
+        ctx.define_native_function_overload(idx, 2, &[map_t, key_t(T_0)], BOOL, |_, _, v, ctx| {
+            Ok(Object::new(v[0].deref::<NessaMap>().table.find(&v[1], ctx)?.is_some()))
+        }).unwrap();
+    }
+
+    for set_t in [SET_OF!(T_0).to_ref(), SET_OF!(T_0).to_mut()] {
+        ctx.define_native_function_overload(idx, 1, &[set_t, key_t(T_0)], BOOL, |_, _, v, ctx| {
+            Ok(Object::new(v[0].deref::<NessaSet>().table.find(&v[1], ctx)?.is_some()))
+        }).unwrap();
+    }
+
+    let idx = ctx.get_function_id("remove".into()).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        2,
+        &[MAP_OF!(T_0, T_1).to_mut(), key_t(T_0)], 
+        BOOL, 
+        |_, _, v, ctx| Ok(Object::new(v[0].deref::<NessaMap>().table.remove(&v[1], ctx)?))
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        1,
+        &[SET_OF!(T_0).to_mut(), key_t(T_0)], 
+        BOOL, 
+        |_, _, v, ctx| Ok(Object::new(v[0].deref::<NessaSet>().table.remove(&v[1], ctx)?))
+    ).unwrap();
+
+    let idx = ctx.get_function_id("len".into()).unwrap();
+
+    for map_t in [MAP_OF!(Type::Wildcard, Type::Wildcard).to_ref(), MAP_OF!(Type::Wildcard, Type::Wildcard).to_mut()] {
+        ctx.define_native_function_overload(idx, 0, &[map_t], INT, |_, _, v, _| {
+            Ok(Object::new(Integer::from(v[0].deref::<NessaMap>().table.len() as u64)))
+        }).unwrap();
+    }
+
+    for set_t in [SET_OF!(Type::Wildcard).to_ref(), SET_OF!(Type::Wildcard).to_mut()] {
+        ctx.define_native_function_overload(idx, 0, &[set_t], INT, |_, _, v, _| {
+            Ok(Object::new(Integer::from(v[0].deref::<NessaSet>().table.len() as u64)))
+        }).unwrap();
+    }
+
+    // Maps are iterated through their keys, which cannot be modified
+    ctx.define_native_function_overload(
+        ITERATOR_FUNC_ID, 
+        2,
+        &[MAP_OF!(T_0, T_1).to_ref().or(MAP_OF!(T_0, T_1).to_mut())], 
+        HASH_IT_OF!(T_0.to_ref()), 
+        |t, _, v, _| Ok(Object::hash_it(t[0].clone().to_ref(), v[0].inner.borrow().dereference().clone(), 0))
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        ITERATOR_FUNC_ID, 
+        2,
+        &[MAP_OF!(T_0, T_1)], 
+        HASH_IT_OF!(T_0.to_ref()), 
+        |t, _, v, _| Ok(Object::hash_it(t[0].clone().to_ref(), v[0].inner.clone(), 0))
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        ITERATOR_FUNC_ID, 
+        1,
+        &[SET_OF!(T_0).to_ref().or(SET_OF!(T_0).to_mut())], 
+        HASH_IT_OF!(T_0.to_ref()), 
+        |t, _, v, _| Ok(Object::hash_it(t[0].clone().to_ref(), v[0].inner.borrow().dereference().clone(), 0))
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        ITERATOR_FUNC_ID, 
+        1,
+        &[SET_OF!(T_0)], 
+        HASH_IT_OF!(T_0.to_ref()), 
+        |t, _, v, _| Ok(Object::hash_it(t[0].clone().to_ref(), v[0].inner.clone(), 0))
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        ITERATOR_FUNC_ID, 
+        1,
+        &[HASH_IT_OF!(T_0)], 
+        HASH_IT_OF!(T_0), 
+        |_, _, mut v, _| Ok(v.pop().unwrap())
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        NEXT_FUNC_ID, 
+        1,
+        &[HASH_IT_OF!(T_0).to_mut()], 
+        T_0, 
+        |_, _, v, _| {
+            let iterator = v[0].deref::<NessaHashIt>();
+
+            // The table might have been consumed or modified since the last call
+            let item = match iterator.table().keys.get(iterator.pos) {
+                Some(k) => k.get_ref(),
+                None => return Err("Iterator has no more elements".into())
+            };
+
+            iterator.pos += 1;
+
+            Ok(item)
+        }
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        IS_CONSUMED_FUNC_ID, 
+        1,
+        &[HASH_IT_OF!(T_0).to_mut()], 
+        BOOL, 
+        |_, _, v, _| {
+            let iterator = v[0].deref::<NessaHashIt>();
+
+            Ok(Object::new(iterator.pos >= iterator.table().len()))
+        }
+    ).unwrap();
+
+    // Ranges are iterated from a copy, so they can be traversed more than once
+    ctx.define_native_function_overload(
+        ITERATOR_FUNC_ID, 
+        0,
+        &[Type::Or(vec!(RANGE, RANGE.to_ref(), RANGE.to_mut()))], 
+        RANGE, 
+        |_, _, v, _| Ok(v[0].deref_deep_clone())
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        NEXT_FUNC_ID, 
+        0,
+        &[RANGE.to_mut()], 
+        INT, 
+        |_, _, v, _| {
+            let range = v[0].deref::<NessaRange>();
+            let item = range.current.clone();
+
+            range.current += &range.step;
+
+            Ok(Object::new(item))
+        }
+    ).unwrap();
+
+    ctx.define_native_function_overload(
+        IS_CONSUMED_FUNC_ID, 
+        0,
+        &[RANGE.to_mut()], 
+        BOOL, 
+        |_, _, v, _| Ok(Object::new(v[0].deref::<NessaRange>().is_consumed()))
+    ).unwrap();
+
+    let idx = ctx.define_function("step".into()).unwrap();
+
+    ctx.define_native_function_overload(
+        idx, 
+        0,
+        &[Type::Or(vec!(RANGE, RANGE.to_ref(), RANGE.to_mut())), Type::Or(vec!(INT, INT.to_ref(), INT.to_mut()))], 
+        RANGE, 
+        |_, _, v, _| {
+            let step = v[1].deref_if_ref().get::<Integer>().clone();
+
+            if step == 0 {
+                return Err("Range step cannot be zero".into());
+            }
+
+            let range = v[0].deref_if_ref();
+            let range = range.get::<NessaRange>();
+
+            Ok(ObjectBlock::Range(NessaRange { current: range.start.clone(), step, ..range.clone() }).to_obj())
+        }
+    ).unwrap();
+
     let idx = ctx.define_function("time".into()).unwrap();
 
     ctx.define_native_function_overload(
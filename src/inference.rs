@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use colored::Colorize;
 
 use crate::compilation::NessaError;
@@ -5,10 +7,15 @@ use crate::context::NessaContext;
 use crate::interfaces::ITERABLE_ID;
 use crate::parser::Location;
 use crate::parser::NessaExpr;
+use crate::parser::MatchPattern;
 use crate::functions::*;
 use crate::operations::*;
+use crate::ARR_OF;
 use crate::types::Type;
 
+// Runtime type of the first argument, arguments, overload id and templates of each target of a dynamic call
+pub type DispatchTable = Vec<(Type, Vec<Type>, usize, Vec<Type>)>;
+
 impl NessaContext {
     pub fn get_first_unary_op(&self, id: usize, arg_type: Type, call_templates: Option<Vec<Type>>, sub_t: bool, l: &Location) -> Result<(usize, Type, bool, Vec<Type>), NessaError> {
         if let Operator::Unary{operations, ..} = &self.unary_ops[id] {
@@ -206,7 +213,11 @@ impl NessaContext {
         let t = Type::And(arg_type.clone());
 
         'outer: for (i, f_ov) in self.functions[id].overloads.iter().enumerate() {
-            if let (true, subs) = t.bindable_to_subtitutions(&f_ov.args, self) { // Take first that matches
+            let subs = f_ov.call_args(arg_type.len()).into_iter()
+                           .map(|args| t.bindable_to_subtitutions(&args, self))
+                           .find(|(valid, _)| *valid);
+
+            if let Some((true, subs)) = subs { // Take first that matches
                 if let Some(call_t) = &call_templates {
                     for (i, t) in call_t.iter().enumerate() {
                         if let Some(s_t) = subs.get(&i) {
@@ -234,12 +245,96 @@ impl NessaContext {
         ), l, vec!()))
     }
 
+    // Return type of an interface function called on a dyn value, which is resolved at runtime using its first argument
+    pub fn get_dynamic_function_overload(&self, id: usize, arg_type: &[Type]) -> Option<Type> {
+        let constraint = match arg_type.first().map(Type::deref_type) {
+            Some(Type::Dyn(c)) => c,
+            _ => return None
+        };
+
+        let name = &self.functions[id].name;
+        let args_and = Type::And(arg_type.to_vec());
+        let offset = (args_and.max_template() + 1) as usize;
+
+        // Functions of the interface and of its parents
+        let mut pending = vec!(constraint.clone());
+
+        while let Some(c) = pending.pop() {
+            let subs = c.args.iter().cloned().enumerate().map(|(i, t)| (offset + i, t)).collect::<HashMap<_, _>>();
+            let dyn_type = Type::Dyn(c.clone());
+
+            let instantiate = |t: &Type| {
+                let mut res = t.clone();
+                res.offset_templates(offset);
+                res.sub_templates(&subs).sub_self(&dyn_type)
+            };
+
+            for (_, f_n, _, args, ret, _) in &self.interfaces[c.id].fns {
+                // Only the first argument can depend on the implementation
+                let dispatchable = !args.is_empty() && *args[0].1.deref_type() == Type::SelfType && !args[1..].iter().any(|(_, t)| t.has_self());
+
+                if f_n == name && args.len() == arg_type.len() && dispatchable {
+                    let f_args = Type::And(args.iter().map(|(_, t)| instantiate(t)).collect());
+
+                    if let (true, t_subs) = args_and.bindable_to_subtitutions(&f_args, self) {
+                        return Some(instantiate(ret).sub_templates(&t_subs));
+                    }
+                }
+            }
+
+            pending.extend(self.interface_parents(&c));
+        }
+
+        None
+    }
+
+    pub fn get_dispatch_table(&self, id: usize, arg_type: &[Type], l: &Location) -> Result<DispatchTable, NessaError> {
+        let constraint = match arg_type[0].deref_type() {
+            Type::Dyn(c) => c,
+            _ => unreachable!()
+        };
+
+        let mut res: DispatchTable = vec!();
+
+        for i in &self.interface_impls {
+            // Generic implementations cannot be instantiated at runtime
+            if i.interface_id != constraint.id || i.is_generic() {
+                continue;
+            }
+
+            if !self.implements_interface(&i.interface_type, constraint, &mut HashMap::new(), &mut HashMap::new()) {
+                continue;
+            }
+
+            // Values are dispatched by their own type, so implementations for references are not needed
+            for t in self.match_alternatives(&i.interface_type) {
+                if t.is_ref() || res.iter().any(|(other, ..)| *other == t) {
+                    continue;
+                }
+
+                let mut args = arg_type.to_vec();
+
+                args[0] = match &arg_type[0] {
+                    Type::Ref(_) => t.clone().to_ref(),
+                    Type::MutRef(_) => t.clone().to_mut(),
+                    _ => t.clone()
+                };
+
+                let (ov_id, _, _, templates) = self.get_first_function_overload(id, args.clone(), None, true, l)?;
+
+                res.push((t, args, ov_id, templates));
+            }
+        }
+
+        Ok(res)
+    }
+
     pub fn is_function_overload_ambiguous(&self, id: usize, arg_type: Vec<Type>) -> Option<Vec<(Type, Type)>> {
-        let t = Type::And(arg_type);
+        let t = Type::And(arg_type.clone());
 
         let overloads = self.functions[id].overloads.iter()
-                            .map(|f_ov| (f_ov.args.clone(), f_ov.ret.clone()))
-                            .filter(|(a, _)| t.bindable_to(a, self)).collect::<Vec<_>>();
+                            .filter_map(|f_ov| Some((f_ov.call_args(arg_type.len()).into_iter().find(|a| t.bindable_to(a, self))?, f_ov.ret.clone())))
+                            .collect::<Vec<_>>();
 
         // Return Some(overloads) if the call is ambiguous, else return None
         if overloads.len() > 1 {
@@ -270,11 +365,130 @@ impl NessaContext {
         self.get_first_function_overload(NEXT_FUNC_ID, vec!(it_mut.clone()), None, true, l)
     }
 
+    // Concrete types a value of type t can have at runtime, expanding aliases and unions
+    pub fn match_alternatives(&self, t: &Type) -> Vec<Type> {
+        match t {
+            Type::Basic(id) if self.type_templates[*id].is_structural() => {
+                self.match_alternatives(self.type_templates[*id].alias.as_ref().unwrap())
+            },
+
+            Type::Template(id, ts) if self.type_templates[*id].is_structural() => {
+                let alias = self.type_templates[*id].alias.as_ref().unwrap();
+                self.match_alternatives(&alias.sub_templates(&ts.iter().cloned().enumerate().collect()))
+            },
+
+            Type::Or(v) => v.iter().flat_map(|i| self.match_alternatives(i)).collect(),
+
+            _ => vec!(t.clone())
+        }
+    }
+
+    // Attribute types of a class type, with its template parameters substituted
+    pub fn match_class_attributes(&self, t: &Type) -> Option<Vec<Type>> {
+        match t {
+            Type::Basic(id) if self.type_templates[*id].is_nominal() => {
+                Some(self.type_templates[*id].attributes.iter().map(|(_, t)| t.clone()).collect())
+            },
+
+            Type::Template(id, ts) if self.type_templates[*id].is_nominal() => {
+                let templates = ts.iter().cloned().enumerate().collect();
+                Some(self.type_templates[*id].attributes.iter().map(|(_, t)| t.sub_templates(&templates)).collect())
+            },
+
+            _ => None
+        }
+    }
+
+    // Element types of the only tuple type of the given length that t can hold
+    pub fn match_tuple_elements(&self, t: &Type, len: usize) -> Option<Vec<Type>> {
+        let mut tuples = self.match_alternatives(t.deref_type()).into_iter().filter_map(|i| match i {
+            Type::And(v) if v.len() == len => Some(v),
+            _ => None
+        });
+
+        match (tuples.next(), tuples.next()) {
+            (Some(v), None) => Some(v),
+            _ => None
+        }
+    }
+
+    // Type of a component of a value accessed through a reference of type t, following the attribute access rules
+    pub fn match_access_type(t: &Type, inner: &Type) -> Type {
+        match (t, inner) {
+            (Type::MutRef(_), Type::Ref(_) | Type::MutRef(_)) => inner.clone(),
+            (Type::MutRef(_), _) => Type::MutRef(Box::new(inner.clone())),
+
+            (Type::Ref(_), Type::MutRef(i)) => Type::Ref(i.clone()),
+            (Type::Ref(_), Type::Ref(_)) => inner.clone(),
+            (_, _) => Type::Ref(Box::new(inner.clone()))
+        }
+    }
+
+    // Wraps a type with the reference kind of t
+    pub fn match_ref_type(t: &Type, inner: Type) -> Type {
+        match t {
+            Type::MutRef(_) => Type::MutRef(Box::new(inner)),
+            _ => Type::Ref(Box::new(inner))
+        }
+    }
+
+    // Type of the values that match a compiled pattern when checked against a reference of type t
+    pub fn match_narrowed_type(&self, pattern: &MatchPattern, t: &Type) -> Type {
+        match pattern {
+            MatchPattern::Wildcard => t.clone(),
+
+            MatchPattern::Literal(obj) => NessaContext::match_ref_type(t, obj.get_type()),
+
+            MatchPattern::Type(pt) |
+            MatchPattern::Class(pt, _) => NessaContext::match_ref_type(t, pt.clone()),
+
+            MatchPattern::Tuple(p) => match self.match_tuple_elements(t, p.len()) {
+                Some(v) => NessaContext::match_ref_type(t, Type::And(v)),
+                None => t.clone()
+            },
+
+            MatchPattern::Binding(_, p) |
+            MatchPattern::CompiledBinding(_, _, _, p) => self.match_narrowed_type(p, t)
+        }
+    }
+
     pub fn infer_type(&self, expr: &NessaExpr) -> Result<Type, NessaError> {
         return match expr {
             NessaExpr::Literal(_, obj) => Ok(obj.get_type()),
 
             NessaExpr::DoBlock(_, _, t) => Ok(t.clone()),
+            NessaExpr::VariadicArgs(_, t, _) => Ok(ARR_OF!(t.clone())),
+
+            NessaExpr::CompiledPropagate(l, _, e) => {
+                let t = self.infer_type(e)?;
+
+                match t.propagation_split() {
+                    Some((_, success)) => Ok(success),
+                    None => Err(NessaError::compiler_error(format!("Unable to use ? on a value of type {}", t.get_name(self)), l, vec!()))
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, _, _, arms) => {
+                let mut res: Option<Type> = None;
+
+                // Merge the types of every arm
+                for (_, _, b) in arms {
+                    let t = self.infer_type(b)?;
+
+                    res = Some(match res {
+                        Some(r) if t.bindable_to(&r, self) => r,
+                        Some(r) if r.bindable_to(&t, self) => t,
+                        Some(Type::Or(mut v)) => {
+                            v.push(t);
+                            Type::Or(v)
+                        },
+                        Some(r) => Type::Or(vec!(r, t)),
+                        None => t
+                    });
+                }
+
+                Ok(res.unwrap_or(Type::Empty))
+            },
 
             NessaExpr::AttributeAccess(_, e, att_idx) => {
                 use Type::*;
@@ -382,9 +596,12 @@ impl NessaContext {
                                            .collect::<Result<Vec<_>, NessaError>>()?
                                            .into_iter()
                                            .map(|i| i.sub_templates(&t_sub_call))
-                                           .collect();
+                                           .collect::<Vec<_>>();
 
-                let (_, r, _, subs) = self.get_first_function_overload(*id, arg_types, None, true, l)?;
+                let (_, r, _, subs) = match self.get_first_function_overload(*id, arg_types.clone(), None, true, l) {
+                    Ok(res) => res,
+                    Err(err) => return self.get_dynamic_function_overload(*id, &arg_types).map(|r| r.sub_templates(&t_sub_call)).ok_or(err)
+                };
 
                 let t_sub_ov = subs.iter().cloned().enumerate().collect();
 
@@ -443,18 +660,26 @@ impl NessaContext {
             NessaExpr::CompiledVariableDefinition(l, _, _, _, _) |
             NessaExpr::CompiledVariableAssignment(l, _, _, _, _) |
             NessaExpr::CompiledFor(l, _, _, _, _, _) |
+            NessaExpr::CompiledTry(l, _, _, _, _, _) |
             NessaExpr::Macro(l, _, _, _, _, _) |
+            NessaExpr::Match(l, _, _) |
+            NessaExpr::Propagate(l, _) |
+            NessaExpr::Interpolation(l, _) |
+            NessaExpr::NamedArgument(l, _, _) |
+            NessaExpr::CompoundAssignment(l, _, _, _) |
+            NessaExpr::Try(l, _, _, _, _) |
             NessaExpr::Lambda(l, _, _, _, _) |
             NessaExpr::NameReference(l, _) |
             NessaExpr::VariableDefinition(l, _, _, _) |
+            NessaExpr::DestructuringDefinition(l, _, _) |
             NessaExpr::VariableAssignment(l, _, _) |
-            NessaExpr::FunctionDefinition(l, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(l, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(l, _, _) |
             NessaExpr::PostfixOperatorDefinition(l, _, _) |
             NessaExpr::BinaryOperatorDefinition(l, _, _, _) |
             NessaExpr::NaryOperatorDefinition(l, _, _, _) |
-            NessaExpr::ClassDefinition(l, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(l, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(l, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(l, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(l, _, _, _, _, _, _, _) |
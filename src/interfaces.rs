@@ -1,9 +1,9 @@
 use colored::Colorize;
 use serde::{Serialize, Deserialize};
 
-use crate::{annotations::Annotation, context::NessaContext, html_ext::HTMLColorable, parser::Location, types::{Type, BOOL, FLOAT, INT, STR, T_0, T_1, T_2}, ARR_IT_OF, ARR_OF};
+use crate::{annotations::Annotation, context::NessaContext, html_ext::HTMLColorable, parser::{Location, NessaExpr}, types::{Type, BOOL, ERROR, FLOAT, INT, RANGE, REGEX, STR, T_0, T_1, T_2, T_3}, ARR_IT_OF, ARR_OF, HASH_IT_OF, MAP_OF, SET_OF};
 
-pub type InterfaceFunctionHeader = (Vec<Annotation>, String, Option<Vec<String>>, Vec<(String, Type)>, Type);
+pub type InterfaceFunctionHeader = (Vec<Annotation>, String, Option<Vec<String>>, Vec<(String, Type)>, Type, Option<Vec<NessaExpr>>);
 pub type InterfaceUnaryOpHeader = (Vec<Annotation>, usize, Vec<String>, String, Type, Type);
 pub type InterfaceBinaryOpHeader = (Vec<Annotation>, usize, Vec<String>, (String, Type), (String, Type), Type);
 pub type InterfaceNaryOpHeader = (Vec<Annotation>, usize, Vec<String>, (String, Type), Vec<(String, Type)>, Type);
@@ -13,6 +13,7 @@ pub struct Interface {
     pub id: usize,
     pub name: String,
     pub params: Vec<String>,
+    pub parents: Vec<InterfaceConstraint>,
     pub location: Location,
     pub annotations: Vec<Annotation>,
     pub fns: Vec<InterfaceFunctionHeader>,
@@ -28,7 +29,13 @@ pub struct InterfaceImpl {
     pub interface_type: Type
 }
 
-#[derive(Clone, Hash, Debug, PartialEq, Serialize, Deserialize)]
+impl InterfaceImpl {
+    pub fn is_generic(&self) -> bool {
+        self.interface_type.has_templates() || self.args.iter().any(Type::has_templates)
+    }
+}
+
+#[derive(Clone, Hash, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InterfaceConstraint {
     pub id: usize,
     pub args: Vec<Type>
@@ -66,7 +73,7 @@ impl InterfaceConstraint {
             )
 
         } else {
-            format!("{}", ctx.interfaces[self.id].name.green())
+            ctx.interfaces[self.id].name.html_green()
         }
     }
 
@@ -93,8 +100,12 @@ impl InterfaceConstraint {
 // Constants for common interfaces
 pub const ITERABLE_ID: usize = 0;
 pub const PRINTABLE_ID: usize = 1;
+pub const STRINGABLE_ID: usize = 2;
+pub const HASH_ID: usize = 3;
 
 pub const PRINTABLE: InterfaceConstraint = InterfaceConstraint { id: PRINTABLE_ID, args: vec!() };
+pub const STRINGABLE: InterfaceConstraint = InterfaceConstraint { id: STRINGABLE_ID, args: vec!() };
+pub const HASH: InterfaceConstraint = InterfaceConstraint { id: HASH_ID, args: vec!() };
 
 #[macro_export]
 macro_rules! ITERABLE_OF { ($t: expr) => { InterfaceConstraint::new($t) }; }
@@ -103,14 +114,23 @@ macro_rules! ITERABLE_OF { ($t: expr) => { InterfaceConstraint::new($t) }; }
 pub fn standard_interfaces(ctx: &mut NessaContext) {
     
     // Definitions
-    ctx.define_interface(Location::none(), vec!(), "Iterable".into(), vec!("Iter".into(), "Elem".into()), vec!(
-        (vec!(), "iterator".into(), None, vec!(("".into(), Type::SelfType)), T_0),
-        (vec!(), "next".into(), None, vec!(("".into(), T_0.to_mut())), T_1),
-        (vec!(), "is_consumed".into(), None, vec!(("".into(), T_0.to_mut())), BOOL)        
+    ctx.define_interface(Location::none(), vec!(), "Iterable".into(), vec!("Iter".into(), "Elem".into()), vec!(), vec!(
+        (vec!(), "iterator".into(), None, vec!(("".into(), Type::SelfType)), T_0, None),
+        (vec!(), "next".into(), None, vec!(("".into(), T_0.to_mut())), T_1, None),
+        (vec!(), "is_consumed".into(), None, vec!(("".into(), T_0.to_mut())), BOOL, None)        
+    ), vec!(), vec!(), vec!()).unwrap();
+
+    ctx.define_interface(Location::none(), vec!(), "Printable".into(), vec!(), vec!(), vec!(
+        (vec!(), "print".into(), None, vec!(("".into(), Type::SelfType)), Type::Empty, None)
+    ), vec!(), vec!(), vec!()).unwrap();
+
+    ctx.define_interface(Location::none(), vec!(), "Stringable".into(), vec!(), vec!(), vec!(
+        (vec!(), "to_string".into(), None, vec!(("".into(), Type::SelfType)), STR, None)
     ), vec!(), vec!(), vec!()).unwrap();
 
-    ctx.define_interface(Location::none(), vec!(), "Printable".into(), vec!(), vec!(
-        (vec!(), "print".into(), None, vec!(("".into(), Type::SelfType)), Type::Empty)
+    // Implemented by classes that derive it
+    ctx.define_interface(Location::none(), vec!(), "Hash".into(), vec!(), vec!(), vec!(
+        (vec!(), "hash".into(), None, vec!(("".into(), Type::SelfType)), INT, None)
     ), vec!(), vec!(), vec!()).unwrap();
 
     // Implementations
@@ -121,6 +141,20 @@ pub fn standard_interfaces(ctx: &mut NessaContext) {
     ctx.define_interface_impl("Iterable".into(), vec!("T".into()), ARR_IT_OF!(T_2.to_mut()), vec!(ARR_IT_OF!(T_2.to_mut()), T_2.to_mut())).unwrap();
     ctx.define_interface_impl("Iterable".into(), vec!("T".into()), ARR_IT_OF!(T_2.to_ref()), vec!(ARR_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
 
+    ctx.define_interface_impl("Iterable".into(), vec!("K".into(), "V".into()), MAP_OF!(T_2, T_3), vec!(HASH_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
+    ctx.define_interface_impl("Iterable".into(), vec!("K".into(), "V".into()), MAP_OF!(T_2, T_3).to_ref(), vec!(HASH_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
+    ctx.define_interface_impl("Iterable".into(), vec!("K".into(), "V".into()), MAP_OF!(T_2, T_3).to_mut(), vec!(HASH_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
+
+    ctx.define_interface_impl("Iterable".into(), vec!("T".into()), SET_OF!(T_2), vec!(HASH_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
+    ctx.define_interface_impl("Iterable".into(), vec!("T".into()), SET_OF!(T_2).to_ref(), vec!(HASH_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
+    ctx.define_interface_impl("Iterable".into(), vec!("T".into()), SET_OF!(T_2).to_mut(), vec!(HASH_IT_OF!(T_2.to_ref()), T_2.to_ref())).unwrap();
+
+    ctx.define_interface_impl("Iterable".into(), vec!("T".into()), HASH_IT_OF!(T_2), vec!(HASH_IT_OF!(T_2), T_2)).unwrap();
+
+    ctx.define_interface_impl("Iterable".into(), vec!(), RANGE, vec!(RANGE, INT)).unwrap();
+    ctx.define_interface_impl("Iterable".into(), vec!(), RANGE.to_ref(), vec!(RANGE, INT)).unwrap();
+    ctx.define_interface_impl("Iterable".into(), vec!(), RANGE.to_mut(), vec!(RANGE, INT)).unwrap();
+
     ctx.define_interface_impl("Printable".into(), vec!(), BOOL, vec!()).unwrap();
     ctx.define_interface_impl("Printable".into(), vec!(), INT, vec!()).unwrap();
     ctx.define_interface_impl("Printable".into(), vec!(), FLOAT, vec!()).unwrap();
@@ -129,4 +163,29 @@ pub fn standard_interfaces(ctx: &mut NessaContext) {
     ctx.define_interface_impl("Printable".into(), vec!(), INT.to_ref().or(INT.to_mut()), vec!()).unwrap();
     ctx.define_interface_impl("Printable".into(), vec!(), FLOAT.to_ref().or(FLOAT.to_mut()), vec!()).unwrap();
     ctx.define_interface_impl("Printable".into(), vec!(), STR.to_ref().or(STR.to_mut()), vec!()).unwrap();
+
+    for t in [BOOL, INT, FLOAT, STR] {
+        ctx.define_interface_impl("Stringable".into(), vec!(), t.clone().to_ref(), vec!()).unwrap();
+        ctx.define_interface_impl("Stringable".into(), vec!(), t.clone().to_mut(), vec!()).unwrap();
+        ctx.define_interface_impl("Stringable".into(), vec!(), t, vec!()).unwrap();
+    }
+
+    let stringable_t = |i| Type::TemplateParam(i, vec!(STRINGABLE));
+    let mut containers = vec!(
+        (vec!("T".into()), ARR_OF!(stringable_t(0))),
+        (vec!("K".into(), "V".into()), MAP_OF!(stringable_t(0), stringable_t(1))),
+        (vec!("T".into()), SET_OF!(stringable_t(0))),
+        (vec!(), RANGE),
+        (vec!(), REGEX),
+        (vec!(), ERROR),
+        (vec!(), Type::Empty)
+    );
+
+    containers.extend((2..10).map(|n| ((0..n).map(|i| format!("T{}", i)).collect(), Type::And((0..n).map(stringable_t).collect()))));
+
+    for (params, t) in containers {
+        ctx.define_interface_impl("Stringable".into(), params.clone(), t.clone().to_ref(), vec!()).unwrap();
+        ctx.define_interface_impl("Stringable".into(), params.clone(), t.clone().to_mut(), vec!()).unwrap();
+        ctx.define_interface_impl("Stringable".into(), params, t, vec!()).unwrap();
+    }
 } 
\ No newline at end of file
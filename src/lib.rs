@@ -27,6 +27,9 @@ pub mod checks;
 pub mod compilation;
 pub mod optimization;
 pub mod execution;
+pub mod debugger;
+pub mod repl;
+pub mod lsp;
 pub mod translation;
 pub mod serialization;
 
@@ -269,6 +272,51 @@ mod integration {
         integration_test("test/string_manip.nessa");
     }
 
+    #[test]
+    fn string_library() {
+        integration_test("test/string_library.nessa");
+    }
+
+    #[test]
+    fn regex() {
+        integration_test("test/regex.nessa");
+    }
+
+    #[test]
+    fn interpolation() {
+        integration_test("test/interpolation.nessa");
+    }
+
+    #[test]
+    fn stringable() {
+        integration_test("test/stringable.nessa");
+    }
+
+    #[test]
+    fn parse() {
+        integration_test("test/parse.nessa");
+    }
+
+    #[test]
+    fn derive() {
+        integration_test("test/derive.nessa");
+    }
+
+    #[test]
+    fn interface_defaults() {
+        integration_test("test/interface_defaults.nessa");
+    }
+
+    #[test]
+    fn interface_inheritance() {
+        integration_test("test/interface_inheritance.nessa");
+    }
+
+    #[test]
+    fn dyn_interfaces() {
+        integration_test("test/dyn_interfaces.nessa");
+    }
+
     #[test]
     fn ambiguous_impl() {
         integration_test("test/ambiguous_impl.nessa");
@@ -329,6 +377,61 @@ mod integration {
         integration_test("test/exp_floats.nessa");
     }
 
+    #[test]
+    fn enums() {
+        integration_test("test/enums.nessa");
+    }
+
+    #[test]
+    fn match_expressions() {
+        integration_test("test/match.nessa");
+    }
+
+    #[test]
+    fn destructuring() {
+        integration_test("test/destructuring.nessa");
+    }
+
+    #[test]
+    fn default_args() {
+        integration_test("test/default_args.nessa");
+    }
+
+    #[test]
+    fn variadic() {
+        integration_test("test/variadic.nessa");
+    }
+
+    #[test]
+    fn compound_assignment() {
+        integration_test("test/compound_assignment.nessa");
+    }
+
+    #[test]
+    fn ranges() {
+        integration_test("test/ranges.nessa");
+    }
+
+    #[test]
+    fn try_catch() {
+        integration_test("test/try_catch.nessa");
+    }
+
+    #[test]
+    fn propagation() {
+        integration_test("test/propagation.nessa");
+    }
+
+    #[test]
+    fn narrowing() {
+        integration_test("test/narrowing.nessa");
+    }
+
+    #[test]
+    fn collections() {
+        integration_test("test/collections.nessa");
+    }
+
     #[test]
     fn moving() {
         integration_test_batch("test/batches/moving/*.nessa");
@@ -339,6 +442,36 @@ mod integration {
         integration_test_batch("test/batches/interfaces/*.nessa");
     }
 
+    #[test]
+    fn enums_batch() {
+        integration_test_batch("test/batches/enums/*.nessa");
+    }
+
+    #[test]
+    fn match_batch() {
+        integration_test_batch("test/batches/match/*.nessa");
+    }
+
+    #[test]
+    fn interpolation_batch() {
+        integration_test_batch("test/batches/interpolation/*.nessa");
+    }
+
+    #[test]
+    fn stringable_batch() {
+        integration_test_batch("test/batches/stringable/*.nessa");
+    }
+
+    #[test]
+    fn parse_batch() {
+        integration_test_batch("test/batches/parse/*.nessa");
+    }
+
+    #[test]
+    fn derive_batch() {
+        integration_test_batch("test/batches/derive/*.nessa");
+    }
+
     #[test]
     fn macros() {
         integration_test_batch("test/batches/macros/*.nessa");
@@ -349,6 +482,36 @@ mod integration {
         integration_test_batch("test/batches/stack/*.nessa");
     }
 
+    #[test]
+    fn destructuring_batch() {
+        integration_test_batch("test/batches/destructuring/*.nessa");
+    }
+
+    #[test]
+    fn default_args_batch() {
+        integration_test_batch("test/batches/default_args/*.nessa");
+    }
+
+    #[test]
+    fn variadic_batch() {
+        integration_test_batch("test/batches/variadic/*.nessa");
+    }
+
+    #[test]
+    fn compound_assignment_batch() {
+        integration_test_batch("test/batches/compound_assignment/*.nessa");
+    }
+
+    #[test]
+    fn ranges_batch() {
+        integration_test_batch("test/batches/ranges/*.nessa");
+    }
+
+    #[test]
+    fn shadowing_batch() {
+        integration_test_batch("test/batches/shadowing/*.nessa");
+    }
+
     #[test]
     fn sum() {
         module_test("test/modules/sum");
@@ -0,0 +1,791 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::compilation::NessaError;
+use crate::config::NessaProject;
+use crate::context::{standard_ctx, NessaContext, WarningSink};
+use crate::functions::FunctionOverload;
+use crate::parser::{nessa_module_imports_parser, Location, NessaExpr, Span};
+use crate::types::Type;
+
+/*
+                                                  ╒══════════════════╕
+    ============================================= │  IMPLEMENTATION  │ =============================================
+                                                  ╘══════════════════╛
+*/
+
+lazy_static! {
+    static ref ANSI_ESCAPE: Regex = Regex::new(r"\x1b\[[0-9;]*m").unwrap();
+}
+
+// LSP constants
+const METHOD_NOT_FOUND: i64 = -32601;
+const PARSE_ERROR: i64 = -32700;
+
+const SEVERITY_ERROR: u64 = 1;
+const SEVERITY_WARNING: u64 = 2;
+
+const KIND_FUNCTION: u64 = 3;
+const KIND_CLASS: u64 = 7;
+const KIND_INTERFACE: u64 = 8;
+
+struct Document {
+    text: String,
+    ctx: NessaContext,
+    lines: Vec<NessaExpr>,
+    diagnostics: Vec<Value>
+}
+
+pub struct NessaLanguageServer {
+    documents: HashMap<String, Document>,
+    project: Option<NessaProject>
+}
+
+impl Default for NessaLanguageServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NessaLanguageServer {
+    pub fn new() -> Self {
+        NessaLanguageServer {
+            documents: HashMap::new(),
+            project: None
+        }
+    }
+
+    pub fn with_project(path: &String) -> Result<Self, NessaError> {
+        let mut res = NessaLanguageServer::new();
+        res.project = Some(NessaProject::new(path)?);
+
+        Ok(res)
+    }
+
+    /*
+        ╒═══════════════════╕
+        │ Document analysis │
+        ╘═══════════════════╛
+    */
+
+    fn parse_document(ctx: &mut NessaContext, project: &mut Option<NessaProject>, code: &String) -> Result<Vec<NessaExpr>, NessaError> {
+        let (_, imports) = nessa_module_imports_parser(Span::new(code), ctx.module_name.clone())?;
+
+        if imports.is_empty() {
+            return ctx.parse_without_precompiling(code);
+        }
+
+        match project {
+            Some(project) => Ok(project.parse_with_imports(ctx, code)?.0),
+            None => Err(NessaError::module_error("Imports are only available when the language server is started inside a project".into()))
+        }
+    }
+
+    fn analyze(&mut self, text: String) -> Document {
+        let mut ctx = standard_ctx();
+        ctx.warning_sink = WarningSink::Stderr;
+
+        if let Some(project) = &self.project {
+            ctx.module_name = project.module_name.clone().into();
+            ctx.module_path = project.path.clone();
+        }
+
+        let project = &mut self.project;
+        let mut lines = vec!();
+
+        // Lines are kept even if the precompilation fails so the parts that were compiled can still be inspected
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            lines = Self::parse_document(&mut ctx, project, &text)?;
+            ctx.precompile_module(&mut lines)
+
+        })).unwrap_or_else(|_| {
+            Err(NessaError::execution_error("Internal compiler error".into()))
+        });
+
+        let mut diagnostics = vec!();
+
+        if let Err(err) = res {
+            diagnostics.push(error_diagnostic(&text, &ctx.module_name, &err));
+        }
+
+        lines.retain(|i| i.get_location().module == ctx.module_name);
+
+        for line in &lines {
+            for (l, warn) in ctx.format_warnings(line).into_iter().chain(ctx.reference_warnings(line)) {
+                diagnostics.push(json!({
+                    "range": lsp_range(&text, &l),
+                    "severity": SEVERITY_WARNING,
+                    "source": "nessa",
+                    "message": strip_colors(&warn)
+                }));
+            }
+        }
+
+        Document { text, ctx, lines, diagnostics }
+    }
+
+    fn update_document(&mut self, uri: &str, text: String) -> Value {
+        let document = self.analyze(text);
+        let diagnostics = document.diagnostics.clone();
+
+        self.documents.insert(uri.to_string(), document);
+
+        publish_diagnostics(uri, diagnostics)
+    }
+
+    /*
+        ╒══════════╕
+        │ Features │
+        ╘══════════╛
+    */
+
+    fn document_position<'a>(&'a self, params: &'a Value) -> Option<(&'a str, &'a Document, (usize, usize))> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let document = self.documents.get(uri)?;
+        let position = nessa_position(&document.text, &params["position"])?;
+
+        Some((uri, document, position))
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (_, document, position) = self.document_position(params)?;
+        let ctx = &document.ctx;
+
+        let mut path = vec!();
+        find_path(ctx, document.lines.iter().collect(), position, &mut path);
+
+        let contents = match *path.last()? {
+            NessaExpr::Variable(_, _, n, t) |
+            NessaExpr::CompiledVariableDefinition(_, _, n, t, _) |
+            NessaExpr::CompiledVariableAssignment(_, _, n, t, _) => format!("{}: {}", n, t.get_name_plain(ctx)),
+
+            NessaExpr::FunctionCall(l, id, ts, args) => {
+                let (ov, _) = function_call_overload(ctx, l, *id, ts, args)?;
+                overload_signature(ctx, &ctx.functions[*id].name, &ctx.functions[*id].overloads[ov])
+            }
+
+            e if e.is_expr() => match ctx.infer_type(e).ok()? {
+                Type::Empty => return None,
+                t => t.get_name_plain(ctx)
+            },
+
+            _ => return None
+        };
+
+        Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```nessa\n{}\n```", contents)
+            },
+            "range": lsp_range(&document.text, path.last()?.get_location())
+        }))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (uri, document, position) = self.document_position(params)?;
+        let ctx = &document.ctx;
+
+        let mut path = vec!();
+        find_path(ctx, document.lines.iter().collect(), position, &mut path);
+
+        let location = match path.last() {
+            Some(NessaExpr::FunctionCall(l, id, ts, args)) => {
+                let (ov, _) = function_call_overload(ctx, l, *id, ts, args)?;
+                Some(ctx.functions[*id].overloads[ov].location.clone())
+            },
+
+            Some(NessaExpr::Variable(_, idx, n, _)) => variable_definition(&document.lines, &path, *idx, n, position),
+
+            Some(NessaExpr::AttributeAccess(_, e, _)) if !contains(e.get_location(), position) => {
+                match ctx.infer_type(e).ok()?.deref_type() {
+                    Type::Basic(id) | Type::Template(id, _) => Some(ctx.type_templates[*id].location.clone()),
+                    _ => None
+                }
+            }
+
+            _ => None
+        };
+
+        // Names that are not part of an expression (such as types) are looked up directly
+        let location = location.filter(|l| l.line > 0).or_else(|| {
+            let name = word_at(&document.text, position)?;
+
+            ctx.type_templates.iter().rfind(|t| t.name == name).map(|t| t.location.clone())
+                .or_else(|| ctx.interfaces.iter().find(|i| i.name == name).map(|i| i.location.clone()))
+                .or_else(|| ctx.functions.iter().find(|f| f.name == name)?.overloads.iter().find(|o| o.location.line > 0).map(|o| o.location.clone()))
+        })?;
+
+        self.lsp_location(uri, document, &location)
+    }
+
+    fn completion(&self, params: &Value) -> Option<Value> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let ctx = &self.documents.get(uri)?.ctx;
+
+        let mut items = vec!();
+
+        // Class constructors are covered by the classes themselves
+        for f in ctx.functions.iter().filter(|f| ctx.type_templates.iter().all(|t| t.name != f.name)) {
+            if let Some(ov) = f.overloads.first() {
+                let detail = match f.overloads.len() {
+                    1 => overload_signature(ctx, &f.name, ov),
+                    n => format!("{} (+{} overloads)", overload_signature(ctx, &f.name, ov), n - 1)
+                };
+
+                items.push(json!({ "label": f.name, "kind": KIND_FUNCTION, "detail": detail }));
+            }
+        }
+
+        for t in &ctx.type_templates {
+            items.push(json!({ "label": t.name, "kind": KIND_CLASS, "detail": "class" }));
+        }
+
+        for i in &ctx.interfaces {
+            items.push(json!({ "label": i.name, "kind": KIND_INTERFACE, "detail": "interface" }));
+        }
+
+        Some(Value::Array(items))
+    }
+
+    fn lsp_location(&self, uri: &str, document: &Document, l: &Location) -> Option<Value> {
+        if l.line == 0 {
+            return None; // Native definition
+        }
+
+        if l.module == document.ctx.module_name {
+            return Some(json!({ "uri": uri, "range": lsp_range(&document.text, l) }));
+        }
+
+        let path = fs::canonicalize(self.project.as_ref()?.module_file(&l.module)?).ok()?;
+        let text = fs::read_to_string(&path).ok()?;
+
+        Some(json!({ "uri": format!("file://{}", path.to_str()?), "range": lsp_range(&text, l) }))
+    }
+
+    /*
+        ╒═══════════╕
+        │ Messaging │
+        ╘═══════════╛
+    */
+
+    pub fn handle(&mut self, msg: &Value) -> Vec<Value> {
+        if msg.is_null() {
+            return vec!(error_response(Value::Null, PARSE_ERROR, "Unable to parse message"));
+        }
+
+        let method = msg["method"].as_str().unwrap_or_default();
+        let params = &msg["params"];
+
+        match (method, msg.get("id").cloned()) {
+            ("initialize", Some(id)) => vec!(response(id, json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "completionProvider": {}
+                },
+                "serverInfo": {
+                    "name": "nessa",
+                    "version": env!("CARGO_PKG_VERSION")
+                }
+            }))),
+
+            ("shutdown", Some(id)) => vec!(response(id, Value::Null)),
+            ("textDocument/hover", Some(id)) => vec!(response(id, self.hover(params).unwrap_or_default())),
+            ("textDocument/definition", Some(id)) => vec!(response(id, self.definition(params).unwrap_or_default())),
+            ("textDocument/completion", Some(id)) => vec!(response(id, self.completion(params).unwrap_or_default())),
+
+            ("textDocument/didOpen", None) => match (params["textDocument"]["uri"].as_str(), params["textDocument"]["text"].as_str()) {
+                (Some(uri), Some(text)) => vec!(self.update_document(uri, text.to_string())),
+                _ => vec!()
+            },
+
+            // Only full document synchronization is supported
+            ("textDocument/didChange", None) => match (params["textDocument"]["uri"].as_str(), params["contentChanges"].as_array().and_then(|i| i.last())) {
+                (Some(uri), Some(change)) => vec!(self.update_document(uri, change["text"].as_str().unwrap_or_default().to_string())),
+                _ => vec!()
+            },
+
+            ("textDocument/didClose", None) => match params["textDocument"]["uri"].as_str() {
+                Some(uri) => {
+                    self.documents.remove(uri);
+                    vec!(publish_diagnostics(uri, vec!()))
+                },
+
+                None => vec!()
+            },
+
+            (m, Some(id)) => vec!(error_response(id, METHOD_NOT_FOUND, &format!("Method {} is not supported", m))),
+
+            _ => vec!()
+        }
+    }
+
+    pub fn serve<R: BufRead, W: Write>(&mut self, input: &mut R, output: &mut W) {
+        while let Some(msg) = read_message(input) {
+            if msg["method"] == "exit" {
+                break;
+            }
+
+            for res in self.handle(&msg) {
+                write_message(output, &res);
+            }
+        }
+    }
+
+    pub fn run(&mut self) {
+        self.serve(&mut io::stdin().lock(), &mut io::stdout().lock());
+    }
+}
+
+/*
+    ╒═════════════════╕
+    │ JSON-RPC access │
+    ╘═════════════════╛
+*/
+
+fn read_message<R: BufRead>(input: &mut R) -> Option<Value> {
+    let mut length = None;
+
+    loop {
+        let mut header = String::new();
+
+        if input.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+
+        let header = header.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(l) = header.strip_prefix("Content-Length:") {
+            length = l.trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec!(0; length?);
+    input.read_exact(&mut body).ok()?;
+
+    Some(serde_json::from_slice(&body).unwrap_or_default())
+}
+
+fn write_message<W: Write>(output: &mut W, msg: &Value) {
+    let body = msg.to_string();
+
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body).expect("Unable to write message");
+    output.flush().expect("Unable to flush output");
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics }
+    })
+}
+
+/*
+    ╒═══════════════════════╕
+    │ Positions and helpers │
+    ╘═══════════════════════╛
+*/
+
+fn strip_colors(text: &str) -> String {
+    ANSI_ESCAPE.replace_all(text, "").to_string()
+}
+
+// LSP positions are zero-based and count UTF-16 code units, while locations are one-based and count bytes
+fn lsp_position(text: &str, line: usize, column: usize) -> Value {
+    let line_text = text.split('\n').nth(line.saturating_sub(1)).unwrap_or_default();
+    let mut end = column.saturating_sub(1).min(line_text.len());
+
+    while !line_text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    json!({ "line": line.saturating_sub(1), "character": line_text[..end].encode_utf16().count() })
+}
+
+fn nessa_position(text: &str, position: &Value) -> Option<(usize, usize)> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+    let line_text = text.split('\n').nth(line)?;
+
+    let mut units = 0;
+    let mut column = line_text.len();
+
+    for (i, c) in line_text.char_indices() {
+        if units >= character {
+            column = i;
+            break;
+        }
+
+        units += c.len_utf16();
+    }
+
+    Some((line + 1, column + 1))
+}
+
+fn location_end(l: &Location) -> (usize, usize) {
+    match l.span.rfind('\n') {
+        Some(pos) => (l.line + l.span.matches('\n').count(), l.span.len() - pos),
+        None => (l.line, l.column + l.span.len())
+    }
+}
+
+fn lsp_range(text: &str, l: &Location) -> Value {
+    let (end_line, end_column) = location_end(l);
+
+    json!({ "start": lsp_position(text, l.line, l.column), "end": lsp_position(text, end_line, end_column) })
+}
+
+fn contains(l: &Location, position: (usize, usize)) -> bool {
+    l.line > 0 && (l.line, l.column) <= position && position < location_end(l)
+}
+
+fn expr_contains(ctx: &NessaContext, expr: &NessaExpr, position: (usize, usize)) -> bool {
+    match expr {
+        // Calls are located at their arguments, so the name of the function has to be checked separately
+        NessaExpr::FunctionCall(l, id, _, _) if l.line == position.0 => {
+            contains(l, position) || (l.column.saturating_sub(ctx.functions[*id].name.len()) <= position.1 && position.1 < l.column)
+        }
+
+        _ => contains(expr.get_location(), position)
+    }
+}
+
+fn error_diagnostic(text: &str, module_name: &str, err: &NessaError) -> Value {
+    let mut message = format!("{}: {}", err.err_type, strip_colors(&err.message));
+
+    for s in &err.suggestions {
+        message.push_str(&format!("\n • {}", strip_colors(s)));
+    }
+
+    let range = if err.has_location && *err.module == module_name {
+        let fragment = err.fragment.split('\n').next().unwrap_or_default().trim_end();
+
+        json!({ "start": lsp_position(text, err.line, err.column), "end": lsp_position(text, err.line, err.column + fragment.len()) })
+
+    } else {
+        if err.has_location {
+            message = format!("{} (module {}, line {})", message, err.module, err.line);
+        }
+
+        json!({ "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } })
+    };
+
+    json!({ "range": range, "severity": SEVERITY_ERROR, "source": "nessa", "message": message })
+}
+
+fn word_at(text: &str, (line, column): (usize, usize)) -> Option<&str> {
+    let line_text = text.split('\n').nth(line - 1)?;
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let start = line_text[..column - 1].rfind(|c| !is_word(c)).map(|i| i + 1).unwrap_or(0);
+    let end = line_text[column - 1..].find(|c| !is_word(c)).map(|i| i + column - 1).unwrap_or(line_text.len());
+
+    if start < end { Some(&line_text[start..end]) } else { None }
+}
+
+fn subexpressions(expr: &NessaExpr) -> Vec<&NessaExpr> {
+    match expr {
+        NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
+        NessaExpr::CompiledVariableAssignment(_, _, _, _, e) |
+        NessaExpr::VariableDefinition(_, _, _, e) |
+        NessaExpr::DestructuringDefinition(_, _, e) |
+        NessaExpr::VariableAssignment(_, _, e) |
+        NessaExpr::AttributeAccess(_, e, _) |
+        NessaExpr::UnaryOperation(_, _, _, e) |
+        NessaExpr::Propagate(_, e) |
+        NessaExpr::CompiledPropagate(_, _, e) |
+        NessaExpr::Interpolation(_, e) |
+        NessaExpr::NamedArgument(_, _, e) |
+        NessaExpr::Return(_, e) => vec!(e),
+
+        NessaExpr::AttributeAssignment(_, a, b, _) |
+        NessaExpr::CompoundAssignment(_, _, a, b) |
+        NessaExpr::BinaryOperation(_, _, _, a, b) => vec!(a, b),
+
+        NessaExpr::FunctionCall(_, _, _, b) |
+        NessaExpr::VariadicArgs(_, _, b) |
+        NessaExpr::Tuple(_, b) |
+        NessaExpr::DoBlock(_, b, _) |
+        NessaExpr::Lambda(_, _, _, _, b) |
+        NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, b) |
+        NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, b) |
+        NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, b) |
+        NessaExpr::BinaryOperationDefinition(_, _, _, _, _, _, _, b) |
+        NessaExpr::NaryOperationDefinition(_, _, _, _, _, _, _, b) => b.iter().collect(),
+
+        NessaExpr::NaryOperation(_, _, _, e, b) |
+        NessaExpr::CompiledFor(_, _, _, _, e, b) |
+        NessaExpr::For(_, _, e, b) |
+        NessaExpr::While(_, e, b) => std::iter::once(&**e).chain(b).collect(),
+
+        NessaExpr::CompiledLambda(_, _, c, _, _, b) => c.iter().map(|(_, e)| e).chain(b).collect(),
+
+        NessaExpr::Try(_, b, _, _, h) |
+        NessaExpr::CompiledTry(_, b, _, _, _, h) => b.iter().chain(h).collect(),
+
+        NessaExpr::Match(_, e, arms) |
+        NessaExpr::CompiledMatch(_, _, e, arms) => {
+            let mut res = vec!(&**e);
+
+            for (_, g, b) in arms {
+                res.extend(g);
+                res.push(b);
+            }
+
+            res
+        }
+
+        NessaExpr::If(_, ih, ib, ei, eb) => {
+            let mut res = vec!(&**ih);
+            res.extend(ib);
+
+            for (h, b) in ei {
+                res.push(h);
+                res.extend(b);
+            }
+
+            res.extend(eb.iter().flatten());
+
+            res
+        }
+
+        _ => vec!()
+    }
+}
+
+// Innermost expression that contains a position, preceded by the ones that enclose it. Children are
+// always checked because operations are located at their operator and do not contain their first operand
+fn find_path<'a>(ctx: &NessaContext, exprs: Vec<&'a NessaExpr>, position: (usize, usize), path: &mut Vec<&'a NessaExpr>) -> bool {
+    for e in exprs {
+        path.push(e);
+
+        if find_path(ctx, subexpressions(e), position, path) || expr_contains(ctx, e, position) {
+            return true;
+        }
+
+        path.pop();
+    }
+
+    false
+}
+
+fn variable_definition(lines: &[NessaExpr], path: &[&NessaExpr], idx: usize, name: &String, position: (usize, usize)) -> Option<Location> {
+    let is_definition = |e: &&NessaExpr| matches!(e, NessaExpr::CompiledVariableDefinition(l, i, n, _, _) if *i == idx && n == name && (l.line, l.column) < position);
+
+    // Innermost scope first
+    for scope in path.iter().rev().skip(1) {
+        if let Some(e) = subexpressions(scope).into_iter().rev().find(is_definition) {
+            return Some(e.get_location().clone());
+        }
+
+        let binds_name = match scope {
+            NessaExpr::FunctionDefinition(_, _, _, _, a, _, _, _, _) => a.iter().any(|(n, _)| n == name),
+            NessaExpr::NaryOperationDefinition(_, _, _, _, (f, _), a, _, _) => f == name || a.iter().any(|(n, _)| n == name),
+            NessaExpr::CompiledLambda(_, _, c, a, _, _) => c.iter().any(|(n, _)| n == name) || a.iter().any(|(n, _)| n == name),
+            NessaExpr::PrefixOperationDefinition(_, _, _, _, n, _, _, _) |
+            NessaExpr::PostfixOperationDefinition(_, _, _, _, n, _, _, _) |
+            NessaExpr::CompiledFor(_, _, _, n, _, _) |
+            NessaExpr::CompiledTry(_, _, _, n, _, _) => n == name,
+            NessaExpr::BinaryOperationDefinition(_, _, _, _, (a, _), (b, _), _, _) => a == name || b == name,
+            NessaExpr::CompiledMatch(_, _, _, arms) => arms.iter().any(|(p, _, _)| p.bindings().iter().any(|(_, n, _)| *n == name)),
+            _ => false
+        };
+
+        if binds_name {
+            return Some(scope.get_location().clone());
+        }
+    }
+
+    lines.iter().rev().find(is_definition).map(|e| e.get_location().clone())
+}
+
+fn function_call_overload(ctx: &NessaContext, l: &Location, id: usize, templates: &[Type], args: &[NessaExpr]) -> Option<(usize, Type)> {
+    let arg_types = args.iter().map(|i| ctx.infer_type(i)).collect::<Result<Vec<_>, _>>().ok()?;
+    let (ov, ret, _, _) = ctx.get_first_function_overload(id, arg_types, Some(templates.to_vec()), true, l).ok()?;
+
+    Some((ov, ret))
+}
+
+fn overload_signature(ctx: &NessaContext, name: &String, ov: &FunctionOverload) -> String {
+    let args = match &ov.args {
+        Type::And(a) => a.iter().map(|i| i.get_name_plain(ctx)).collect::<Vec<_>>().join(", "),
+        t => t.get_name_plain(ctx)
+    };
+
+    format!("{}({}) -> {}", name, args, ov.ret.get_name_plain(ctx))
+}
+
+/*
+                                                  ╒═════════╕
+    ============================================= │  TESTS  │ =============================================
+                                                  ╘═════════╛
+*/
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::lsp::NessaLanguageServer;
+
+    const URI: &str = "file:///test.nessa";
+
+    const CODE: &str = "class Point {
+    x: Int;
+    y: Int;
+}
+
+fn add(a: Int, b: Int) -> Int {
+    let c = a + b;
+    return *c;
+}
+
+let p = Point(1, 2);
+let n = add(*p.x, 4);
+";
+
+    fn open(server: &mut NessaLanguageServer, code: &str) -> Value {
+        server.handle(&json!({
+            "jsonrpc": "2.0", 
+            "method": "textDocument/didOpen", 
+            "params": { "textDocument": { "uri": URI, "text": code } }
+        })).pop().unwrap()
+    }
+
+    fn request(server: &mut NessaLanguageServer, method: &str, line: usize, character: usize) -> Value {
+        server.handle(&json!({
+            "jsonrpc": "2.0", 
+            "id": 1,
+            "method": method, 
+            "params": { "textDocument": { "uri": URI }, "position": { "line": line, "character": character } }
+        })).pop().unwrap()["result"].clone()
+    }
+
+    #[test]
+    fn diagnostics() {
+        let mut server = NessaLanguageServer::new();
+
+        let res = open(&mut server, CODE);
+        assert_eq!(res["method"], "textDocument/publishDiagnostics");
+        assert_eq!(res["params"]["diagnostics"], json!([]));
+
+        let res = open(&mut server, "let a = 5;\nlet b: String = *a;\n");
+        let diagnostics = res["params"]["diagnostics"].as_array().unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["range"]["start"], json!({ "line": 1, "character": 0 }));
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert!(diagnostics[0]["message"].as_str().unwrap().contains("to variable b"));
+
+        let res = open(&mut server, "fn Test() -> Int { return 5; }\n");
+        let diagnostics = res["params"]["diagnostics"].as_array().unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 2);
+    }
+
+    #[test]
+    fn hover() {
+        let mut server = NessaLanguageServer::new();
+        open(&mut server, CODE);
+
+        assert_eq!(request(&mut server, "textDocument/hover", 11, 9)["contents"]["value"], "```nessa\nadd(Int, Int) -> Int\n```");
+        assert_eq!(request(&mut server, "textDocument/hover", 6, 12)["contents"]["value"], "```nessa\na: Int\n```");
+        assert_eq!(request(&mut server, "textDocument/hover", 6, 14)["contents"]["value"], "```nessa\nInt\n```");
+        assert_eq!(request(&mut server, "textDocument/hover", 10, 4)["contents"]["value"], "```nessa\np: Point\n```");
+        assert_eq!(request(&mut server, "textDocument/hover", 4, 0), Value::Null);
+    }
+
+    #[test]
+    fn definition() {
+        let mut server = NessaLanguageServer::new();
+        open(&mut server, CODE);
+
+        let start = |res: Value| res["range"]["start"].clone();
+
+        // Function calls, variables, arguments, attributes and class constructors
+        assert_eq!(start(request(&mut server, "textDocument/definition", 11, 9)), json!({ "line": 5, "character": 0 }));
+        assert_eq!(start(request(&mut server, "textDocument/definition", 11, 13)), json!({ "line": 10, "character": 0 }));
+        assert_eq!(start(request(&mut server, "textDocument/definition", 7, 12)), json!({ "line": 6, "character": 4 }));
+        assert_eq!(start(request(&mut server, "textDocument/definition", 6, 12)), json!({ "line": 5, "character": 0 }));
+        assert_eq!(start(request(&mut server, "textDocument/definition", 11, 15)), json!({ "line": 0, "character": 0 }));
+        assert_eq!(start(request(&mut server, "textDocument/definition", 10, 9)), json!({ "line": 0, "character": 0 }));
+        assert_eq!(request(&mut server, "textDocument/definition", 11, 9)["uri"], URI);
+
+        // Native types have no definition
+        assert_eq!(request(&mut server, "textDocument/definition", 5, 26), Value::Null);
+    }
+
+    #[test]
+    fn completion() {
+        let mut server = NessaLanguageServer::new();
+        open(&mut server, CODE);
+
+        let res = request(&mut server, "textDocument/completion", 0, 0);
+        let items = res.as_array().unwrap();
+
+        let find = |label: &str| items.iter().find(|i| i["label"] == label).cloned().unwrap();
+
+        assert_eq!(find("add")["detail"], "add(Int, Int) -> Int");
+        assert_eq!(find("add")["kind"], 3);
+        assert_eq!(find("Point")["kind"], 7);
+        assert_eq!(find("Printable")["kind"], 8);
+    }
+
+    #[test]
+    fn scripted_session() {
+        let messages = [
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "textDocument/didOpen", "params": { "textDocument": { "uri": URI, "text": CODE } } }),
+            json!({ "jsonrpc": "2.0", "method": "textDocument/didChange", "params": { "textDocument": { "uri": URI }, "contentChanges": [{ "text": "let a = b;" }] } }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol", "params": {} }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "shutdown" })
+        ];
+
+        let input = messages.iter().map(|i| {
+            let body = i.to_string();
+            format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+
+        }).collect::<String>();
+
+        let mut output = vec!();
+        NessaLanguageServer::new().serve(&mut input.as_bytes(), &mut output);
+
+        let output = String::from_utf8(output).unwrap();
+        let responses = output.split("Content-Length: ").skip(1).map(|i| {
+            let (length, body) = i.split_once("\r\n\r\n").unwrap();
+            assert_eq!(length.parse::<usize>().unwrap(), body.len());
+
+            serde_json::from_str::<Value>(body).unwrap()
+
+        }).collect::<Vec<_>>();
+
+        assert_eq!(responses.len(), 5);
+        assert_eq!(responses[0]["result"]["capabilities"]["hoverProvider"], true);
+        assert_eq!(responses[1]["params"]["diagnostics"], json!([]));
+        assert_eq!(responses[2]["params"]["diagnostics"].as_array().unwrap().len(), 1);
+        assert_eq!(responses[3]["error"]["code"], -32601);
+        assert_eq!(responses[4], json!({ "jsonrpc": "2.0", "id": 3, "result": null }));
+    }
+}
@@ -6,7 +6,7 @@ use inquire::{Text, required, validator::StringValidator, Autocomplete, Confirm}
 use regex::Regex;
 use glob::glob;
 
-use nessa::{config::{generate_docs, ModuleInfo, NessaConfig, CONFIG}, context::*, git::{install_prelude, install_repo, uninstall_repo}, nessa_error, nessa_warning};
+use nessa::{config::{generate_docs, ModuleInfo, NessaConfig, CONFIG}, context::*, git::{install_prelude, install_repo, uninstall_repo}, repl::NessaRepl, lsp::NessaLanguageServer, debugger::NessaDebugger, nessa_error, nessa_warning};
 use serde_yaml::{ from_str, to_string };
 
 #[derive(Clone)]
@@ -139,6 +139,46 @@ fn main() {
                     .default_value("false")
                 )
         )
+        .subcommand(
+            Command::new("debug")
+                .about("Run Nessa project step by step")
+                .arg(
+                    Arg::new("INPUT")
+                    .help("Specifies the file you want to debug")
+                    .required(false)
+                    .default_value(".")
+                    .index(1)
+                )
+                .arg(
+                    Arg::new("PROGRAM_INPUT")
+                    .help("Program input")
+                    .required(false)
+                    .index(2)
+                    .num_args(0..)
+                )
+        )
+        .subcommand(
+            Command::new("repl")
+            .about("Start an interactive Nessa session")
+            .arg(
+                Arg::new("INPUT")
+                .help("Specifies the project whose modules can be imported")
+                .required(false)
+                .default_value(".")
+                .index(1)
+            )
+        )
+        .subcommand(
+            Command::new("lsp")
+            .about("Start a language server that communicates through stdin and stdout")
+            .arg(
+                Arg::new("INPUT")
+                .help("Specifies the project whose modules can be imported")
+                .required(false)
+                .default_value(".")
+                .index(1)
+            )
+        )
         .subcommand(
             Command::new("new")
             .about("Create Nessa project with config files")
@@ -298,6 +338,56 @@ fn main() {
             }
         }
 
+        Some(("debug", run_args)) => {
+            let path = run_args.get_one::<String>("INPUT").expect("No input folder was provided");
+
+            let program_input = match run_args.get_many::<String>("PROGRAM_INPUT") {
+                Some(i) => i.cloned().collect::<Vec<_>>(),
+                None => vec!(),
+            };
+
+            let mut debugger = NessaDebugger::stdio();
+
+            if let Err(err) = NessaContext::debug_nessa_project(path.into(), &program_input, &mut debugger) {
+                err.emit();
+            }
+        }
+
+        Some(("repl", run_args)) => {
+            let path = run_args.get_one::<String>("INPUT").expect("No input folder was provided");
+            let config_path = Path::new(path).join(Path::new("nessa_config.yml"));
+
+            let mut repl = if config_path.exists() {
+                match NessaRepl::with_project(path) {
+                    Ok(r) => r,
+                    Err(err) => err.emit(),
+                }
+
+            } else {
+                NessaRepl::new()
+            };
+
+            repl.run();
+        }
+
+        Some(("lsp", run_args)) => {
+            let path = run_args.get_one::<String>("INPUT").expect("No input folder was provided");
+
+            let config_path = Path::new(path).join(Path::new("nessa_config.yml"));
+
+            let mut server = if config_path.exists() {
+                match NessaLanguageServer::with_project(path) {
+                    Ok(s) => s,
+                    Err(err) => err.emit(),
+                }
+
+            } else {
+                NessaLanguageServer::new()
+            };
+
+            server.run();
+        }
+
         Some(("new", run_args)) => {
             let name;
             let version;
@@ -1,8 +1,10 @@
-use std::{cell::RefCell, fs::File, path::PathBuf};
+use std::{cell::RefCell, fs::File, hash::{Hash, Hasher}, path::PathBuf};
 
-use crate::{compilation::message_and_exit, context::NessaContext, mut_cell::MutCell, types::{Type, ARR_ID, ARR_IT_ID, BOOL, BOOL_ID, FILE, FILE_ID, FLOAT, FLOAT_ID, INT, INT_ID, STR, STR_ID}, ARR_IT_OF, ARR_OF};
+use crate::{compilation::message_and_exit, context::NessaContext, mut_cell::MutCell, types::{Type, ARR_ID, ARR_IT_ID, BOOL, BOOL_ID, FILE, FILE_ID, FLOAT, FLOAT_ID, HASH_IT_ID, INT, INT_ID, MAP_ID, RANGE, RANGE_ID, REGEX, REGEX_ID, SET_ID, STR, STR_ID}, ARR_IT_OF, ARR_OF, MAP_OF, SET_OF};
 use malachite::Integer;
 use rclite::Rc;
+use regex::Regex;
+use rustc_hash::{FxHashMap, FxHasher};
 use serde::{Deserialize, Serialize};
 
 type DataBlock = Rc<MutCell<ObjectBlock>>;
@@ -32,6 +34,128 @@ pub struct NessaArrayIt {
     pub it_type: Box<Type>
 }
 
+// Insertion ordered hash table shared by maps and sets. Sets do not store values
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct NessaHashTable {
+    pub keys: Vec<Object>,
+    pub values: Vec<Object>,
+    buckets: FxHashMap<u64, Vec<usize>>
+}
+
+impl NessaHashTable {
+    pub fn hash_key(key: &Object, ctx: &NessaContext) -> Result<u64, String> {
+        let mut hasher = FxHasher::default();
+        key.inner.borrow().hash_contents(&mut hasher, ctx)?;
+
+        Ok(hasher.finish())
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn find(&self, key: &Object, ctx: &NessaContext) -> Result<Option<usize>, String> {
+        let hash = NessaHashTable::hash_key(key, ctx)?;
+
+        Ok(self.buckets.get(&hash).and_then(|b| b.iter().find(|i| self.keys[**i].inner.borrow().equals(key.inner.borrow())).copied()))
+    }
+
+    pub fn insert(&mut self, key: Object, value: Option<Object>, ctx: &NessaContext) -> Result<(), String> {
+        let hash = NessaHashTable::hash_key(&key, ctx)?;
+        let NessaHashTable { keys, values, buckets } = self;
+        let bucket = buckets.entry(hash).or_default();
+
+        match bucket.iter().find(|i| keys[**i].inner.borrow().equals(key.inner.borrow())) {
+            Some(idx) => {
+                if let Some(v) = value {
+                    values[*idx] = v;
+                }
+            },
+
+            None => {
+                bucket.push(keys.len());
+                keys.push(key);
+                values.extend(value);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, key: &Object, ctx: &NessaContext) -> Result<bool, String> {
+        let hash = NessaHashTable::hash_key(key, ctx)?;
+
+        let keys = &self.keys;
+
+        let idx = match self.buckets.get_mut(&hash) {
+            Some(bucket) => match bucket.iter().position(|i| keys[*i].inner.borrow().equals(key.inner.borrow())) {
+                Some(pos) => bucket.swap_remove(pos),
+                None => return Ok(false)
+            },
+
+            None => return Ok(false)
+        };
+
+        // Entries after the removed one are shifted to keep insertion order
+        for i in self.buckets.values_mut().flatten() {
+            if *i > idx {
+                *i -= 1;
+            }
+        }
+
+        self.keys.remove(idx);
+
+        if !self.values.is_empty() {
+            self.values.remove(idx);
+        }
+
+        Ok(true)
+    }
+
+    pub fn deep_clone(&self) -> Self {
+        NessaHashTable { 
+            keys: self.keys.iter().map(Object::deep_clone).collect(), 
+            values: self.values.iter().map(Object::deep_clone).collect(), 
+            buckets: self.buckets.clone() 
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct NessaMap {
+    pub table: NessaHashTable,
+    pub key_type: Box<Type>,
+    pub value_type: Box<Type>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct NessaSet {
+    pub table: NessaHashTable,
+    pub elem_type: Box<Type>
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct NessaHashIt {
+    pub pos: usize,
+    pub block: DataBlock,
+    pub it_type: Box<Type>
+}
+
+impl NessaHashIt {
+    pub fn table(&self) -> &NessaHashTable {
+        match self.block.borrow() {
+            ObjectBlock::Map(m) => &m.table,
+            ObjectBlock::Set(s) => &s.table,
+
+            _ => unreachable!()
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct NessaLambda {
     pub loc: usize,
@@ -113,6 +237,38 @@ impl NessaFile {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct NessaRegex {
+    pub regex: Regex
+}
+
+impl PartialEq for NessaRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex.as_str() == other.regex.as_str()
+    }
+}
+
+// Ranges keep their iteration state, so iterating one starts from a copy
+#[derive(Clone, PartialEq, Debug)]
+pub struct NessaRange {
+    pub start: Integer,
+    pub end: Integer,
+    pub step: Integer,
+    pub inclusive: bool,
+    pub current: Integer
+}
+
+impl NessaRange {
+    pub fn is_consumed(&self) -> bool {
+        match (self.step > 0, self.inclusive) {
+            (true, false) => self.current >= self.end,
+            (true, true) => self.current > self.end,
+            (false, false) => self.current <= self.end,
+            (false, true) => self.current < self.end
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct TypeInstance {
     pub id: usize,
@@ -137,11 +293,26 @@ pub enum ObjectBlock {
     #[serde(skip)]
     ArrayIter(NessaArrayIt),
 
+    #[serde(skip)]
+    Map(NessaMap),
+
+    #[serde(skip)]
+    Set(NessaSet),
+
+    #[serde(skip)]
+    HashIter(NessaHashIt),
+
     Lambda(NessaLambda),
 
     #[serde(skip)]
     File(NessaFile),
 
+    #[serde(skip)]
+    Regex(NessaRegex),
+
+    #[serde(skip)]
+    Range(NessaRange),
+
     Instance(TypeInstance),
 
     #[serde(skip)]
@@ -169,8 +340,13 @@ impl ObjectBlock {
             ObjectBlock::Tuple(_) => 0,
             ObjectBlock::Array(_) => ARR_ID,
             ObjectBlock::ArrayIter(_) => ARR_IT_ID,
+            ObjectBlock::Map(_) => MAP_ID,
+            ObjectBlock::Set(_) => SET_ID,
+            ObjectBlock::HashIter(_) => HASH_IT_ID,
             ObjectBlock::Lambda(_) => 0,
             ObjectBlock::File(_) => FILE_ID,
+            ObjectBlock::Regex(_) => REGEX_ID,
+            ObjectBlock::Range(_) => RANGE_ID,
             ObjectBlock::Instance(i) => i.id,
             ObjectBlock::Ref(_) => 0,
             ObjectBlock::Mut(_) => 0,
@@ -188,8 +364,13 @@ impl ObjectBlock {
             ObjectBlock::Tuple(t) => Type::And(t.elem_types.clone()),
             ObjectBlock::Array(a) => ARR_OF!(*a.elem_type.clone()),
             ObjectBlock::ArrayIter(i) => ARR_IT_OF!(*i.it_type.clone()),
+            ObjectBlock::Map(m) => MAP_OF!(*m.key_type.clone(), *m.value_type.clone()),
+            ObjectBlock::Set(s) => SET_OF!(*s.elem_type.clone()),
+            ObjectBlock::HashIter(i) => Type::Template(HASH_IT_ID, vec!(*i.it_type.clone())),
             ObjectBlock::Lambda(l) => Type::Function(l.args_type.clone(), l.ret_type.clone()),
             ObjectBlock::File(_) => FILE,
+            ObjectBlock::Regex(_) => REGEX,
+            ObjectBlock::Range(_) => RANGE,
             ObjectBlock::Instance(i) => if i.params.is_empty() { Type::Basic(i.id) } else { Type::Template(i.id, i.params.clone()) },
             ObjectBlock::Ref(r) => Type::Ref(Box::new(r.borrow().get_type())),
             ObjectBlock::Mut(r) => Type::MutRef(Box::new(r.borrow().get_type())),
@@ -200,6 +381,89 @@ impl ObjectBlock {
         matches!(self, ObjectBlock::NoValue)
     }
 
+    // Structural representation used by derived printing and the debugger
+    pub fn to_display_string(&self, ctx: &NessaContext) -> String {
+        let join = |elems: &[Object]| elems.iter().map(|i| i.to_display_string(ctx)).collect::<Vec<_>>().join(", ");
+
+        match self {
+            ObjectBlock::NoValue => "<moved>".into(),
+            ObjectBlock::Empty => "()".into(),
+            ObjectBlock::Int(i) => i.to_string(),
+            ObjectBlock::Float(f) => f.to_string(),
+            ObjectBlock::Str(s) => format!("{:?}", s),
+            ObjectBlock::Bool(b) => b.to_string(),
+            ObjectBlock::Tuple(t) => format!("({})", join(&t.elements)),
+            ObjectBlock::Array(a) => format!("[{}]", join(&a.elements)),
+            ObjectBlock::ArrayIter(i) => format!("<iterator at {}>", i.pos),
+            ObjectBlock::Map(m) => format!(
+                "{{{}}}", 
+                m.table.keys.iter().zip(&m.table.values).map(|(k, v)| format!("{}: {}", k.to_display_string(ctx), v.to_display_string(ctx))).collect::<Vec<_>>().join(", ")
+            ),
+            ObjectBlock::Set(s) => format!("{{{}}}", join(&s.table.keys)),
+            ObjectBlock::HashIter(i) => format!("<iterator at {}>", i.pos),
+            ObjectBlock::Lambda(_) => "<lambda>".into(),
+            ObjectBlock::File(f) => format!("<file {}>", f.path.display()),
+            ObjectBlock::Regex(r) => format!("Regex({:?})", r.regex.as_str()),
+            ObjectBlock::Range(r) => {
+                let range = format!("{}..{}{}", r.start, if r.inclusive { "=" } else { "" }, r.end);
+
+                if r.step == 1 { range } else { format!("({}).step({})", range, r.step) }
+            },
+            ObjectBlock::Instance(i) if i.attributes.is_empty() => ctx.type_templates[i.id].name.clone(),
+            ObjectBlock::Instance(i) => format!("{}({})", ctx.type_templates[i.id].name, join(&i.attributes)),
+            ObjectBlock::Ref(r) | ObjectBlock::Mut(r) => r.borrow().to_display_string(ctx)
+        }
+    }
+
+    // Structural hash used by maps and sets, consistent with equals
+    pub fn hash_contents<H: Hasher>(&self, state: &mut H, ctx: &NessaContext) -> Result<(), String> {
+        let hash_all = |elems: &[Object], state: &mut H| elems.iter().try_for_each(|i| i.inner.borrow().hash_contents(state, ctx));
+
+        match self {
+            ObjectBlock::Ref(r) | ObjectBlock::Mut(r) => return r.borrow().hash_contents(state, ctx),
+            _ => std::mem::discriminant(self).hash(state)
+        }
+
+        match self {
+            ObjectBlock::Empty => {},
+            ObjectBlock::Int(i) => i.hash(state),
+            ObjectBlock::Float(f) => (if *f == 0.0 { 0.0 } else { *f }).to_bits().hash(state),
+            ObjectBlock::Str(s) => s.hash(state),
+            ObjectBlock::Bool(b) => b.hash(state),
+            ObjectBlock::Tuple(t) => hash_all(&t.elements, state)?,
+            ObjectBlock::Array(a) => hash_all(&a.elements, state)?,
+            ObjectBlock::Instance(i) => {
+                i.id.hash(state);
+                hash_all(&i.attributes, state)?
+            },
+
+            _ => return Err(format!("Unable to hash value of type {}", self.get_type().get_name_plain(ctx)))
+        }
+
+        Ok(())
+    }
+
+    // Structural equality used by maps and sets
+    pub fn equals(&self, other: &ObjectBlock) -> bool {
+        let equals_all = |a: &[Object], b: &[Object]| a.len() == b.len() && a.iter().zip(b).all(|(i, j)| i.inner.borrow().equals(j.inner.borrow()));
+
+        match (self, other) {
+            (ObjectBlock::Ref(a) | ObjectBlock::Mut(a), b) => a.borrow().equals(b),
+            (a, ObjectBlock::Ref(b) | ObjectBlock::Mut(b)) => a.equals(b.borrow()),
+
+            (ObjectBlock::Empty, ObjectBlock::Empty) => true,
+            (ObjectBlock::Int(a), ObjectBlock::Int(b)) => a == b,
+            (ObjectBlock::Float(a), ObjectBlock::Float(b)) => a == b,
+            (ObjectBlock::Str(a), ObjectBlock::Str(b)) => a == b,
+            (ObjectBlock::Bool(a), ObjectBlock::Bool(b)) => a == b,
+            (ObjectBlock::Tuple(a), ObjectBlock::Tuple(b)) => equals_all(&a.elements, &b.elements),
+            (ObjectBlock::Array(a), ObjectBlock::Array(b)) => equals_all(&a.elements, &b.elements),
+            (ObjectBlock::Instance(a), ObjectBlock::Instance(b)) => a.id == b.id && a.params == b.params && equals_all(&a.attributes, &b.attributes),
+
+            _ => false
+        }
+    }
+
     pub fn get_inner<T>(&self) -> &T where ObjectBlock: Get<T> {
         return Get::<T>::get(self);
     }
@@ -230,6 +494,11 @@ impl ObjectBlock {
             (Bool(a), Bool(b)) => *a = b,
             (Array(a), Array(b)) if a.elem_type == b.elem_type => *a = b,
             (ArrayIter(a), ArrayIter(b)) if a.it_type == b.it_type => *a = b,
+            (Map(a), Map(b)) if a.key_type == b.key_type && a.value_type == b.value_type => *a = b,
+            (Set(a), Set(b)) if a.elem_type == b.elem_type => *a = b,
+            (HashIter(a), HashIter(b)) if a.it_type == b.it_type => *a = b,
+            (Regex(a), Regex(b)) => *a = b,
+            (Range(a), Range(b)) => *a = b,
             (Lambda(a), Lambda(b)) if a.args_type == b.args_type && a.ret_type == b.ret_type => *a = b,
             (Instance(a), Instance(b)) if a.id == b.id && a.params == b.params => *a = b,
             (Tuple(a), Tuple(b)) if a.elem_types == b.elem_types => *a = b,
@@ -266,6 +535,20 @@ impl ObjectBlock {
                 block: i.block.clone(), 
                 it_type: i.it_type.clone() 
             }),
+            ObjectBlock::Map(m) => ObjectBlock::Map(NessaMap { 
+                table: m.table.deep_clone(), 
+                key_type: m.key_type.clone(), 
+                value_type: m.value_type.clone() 
+            }),
+            ObjectBlock::Set(s) => ObjectBlock::Set(NessaSet { 
+                table: s.table.deep_clone(), 
+                elem_type: s.elem_type.clone() 
+            }),
+            ObjectBlock::HashIter(i) => ObjectBlock::HashIter(NessaHashIt { 
+                pos: i.pos, 
+                block: i.block.clone(), 
+                it_type: i.it_type.clone() 
+            }),
             ObjectBlock::Lambda(l) => ObjectBlock::Lambda(NessaLambda { 
                 loc: l.loc, 
                 captures: l.captures.iter().map(Object::deep_clone).collect(),
@@ -273,6 +556,8 @@ impl ObjectBlock {
                 ret_type: l.ret_type.clone() 
             }),
             ObjectBlock::File(f) => ObjectBlock::File(f.clone()),
+            ObjectBlock::Regex(r) => ObjectBlock::Regex(r.clone()),
+            ObjectBlock::Range(r) => ObjectBlock::Range(r.clone()),
             ObjectBlock::Instance(i) => ObjectBlock::Instance(TypeInstance {
                 id: i.id, 
                 params: i.params.clone(), 
@@ -323,6 +608,22 @@ impl Object {
         ObjectBlock::ArrayIter(NessaArrayIt { pos, block, it_type: Box::new(it_type) }).to_obj()
     }
 
+    pub fn map(key_type: Type, value_type: Type) -> Self {
+        ObjectBlock::Map(NessaMap { table: NessaHashTable::default(), key_type: Box::new(key_type), value_type: Box::new(value_type) }).to_obj()
+    }
+
+    pub fn set(elem_type: Type) -> Self {
+        ObjectBlock::Set(NessaSet { table: NessaHashTable::default(), elem_type: Box::new(elem_type) }).to_obj()
+    }
+
+    pub fn hash_it(it_type: Type, block: DataBlock, pos: usize) -> Self {
+        ObjectBlock::HashIter(NessaHashIt { pos, block, it_type: Box::new(it_type) }).to_obj()
+    }
+
+    pub fn range(start: Integer, end: Integer, inclusive: bool) -> Self {
+        ObjectBlock::Range(NessaRange { current: start.clone(), start, end, step: Integer::from(1), inclusive }).to_obj()
+    }
+
     pub fn lambda(loc: usize, captures: Vec<Object>, args_type: Type, ret_type: Type) -> Self {
         ObjectBlock::Lambda(NessaLambda { loc, captures, args_type: Box::new(args_type), ret_type: Box::new(ret_type) }).to_obj()
     }
@@ -451,6 +752,10 @@ impl Object {
         format!("{:?}", self.inner.borrow())
     }
 
+    pub fn to_display_string(&self, ctx: &NessaContext) -> String {
+        self.inner.borrow().to_display_string(ctx)
+    }
+
     pub fn deref_if_ref(&self) -> Object {
         return match self.inner.borrow() {
             ObjectBlock::Ref(i) |
@@ -487,6 +792,14 @@ impl Object {
             _ => unreachable!()
         };
     }
+
+    // Checks if this reference points to the data of another object
+    pub fn points_to(&self, other: &Object) -> bool {
+        match self.inner.borrow() {
+            ObjectBlock::Ref(r) | ObjectBlock::Mut(r) => Rc::ptr_eq(r, &other.inner),
+            _ => false
+        }
+    }
 }
 
 pub trait NessaData {
@@ -556,6 +869,11 @@ impl_nessa_data!(NessaTuple, Tuple);
 impl_nessa_data!(NessaLambda, Lambda);
 impl_nessa_data!(NessaArrayIt, ArrayIter);
 impl_nessa_data!(NessaFile, File);
+impl_nessa_data!(NessaMap, Map);
+impl_nessa_data!(NessaSet, Set);
+impl_nessa_data!(NessaHashIt, HashIter);
+impl_nessa_data!(NessaRegex, Regex);
+impl_nessa_data!(NessaRange, Range);
 
 /*
                                                   ╒═════════╕
@@ -645,4 +963,34 @@ mod tests {
         assert_eq!(number_2.ref_count(), 1);
         assert_eq!(*number_2.get::<Integer>(), Integer::from(10));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn hash_tables() {
+        let ctx = standard_ctx();
+        let mut table = NessaHashTable::default();
+
+        for i in 0..10 {
+            table.insert(Object::new(Integer::from(i)), Some(Object::new(format!("{}", i))), &ctx).unwrap();
+        }
+
+        table.insert(Object::new(Integer::from(3)), Some(Object::new(String::from("three"))), &ctx).unwrap();
+
+        assert_eq!(table.len(), 10);
+
+        assert!(table.remove(&Object::new(Integer::from(2)), &ctx).unwrap());
+        assert!(!table.remove(&Object::new(Integer::from(2)), &ctx).unwrap());
+
+        // Later entries are shifted to keep insertion order
+        assert_eq!(table.len(), 9);
+        assert_eq!(table.find(&Object::new(Integer::from(1)), &ctx).unwrap(), Some(1));
+        assert_eq!(table.find(&Object::new(Integer::from(3)), &ctx).unwrap(), Some(2));
+        assert_eq!(table.find(&Object::new(Integer::from(9)), &ctx).unwrap(), Some(8));
+        assert_eq!(table.find(&Object::new(Integer::from(2)), &ctx).unwrap(), None);
+
+        let idx = table.find(&Object::new(Integer::from(3)).get_ref(), &ctx).unwrap().unwrap();
+        assert_eq!(*table.values[idx].get::<String>(), "three".to_string());
+
+        let lambda = Object::lambda(0, vec!(), Type::Empty, Type::Empty);
+        assert!(table.insert(lambda, None, &ctx).is_err());
+    }
+}
@@ -1,9 +1,10 @@
+use malachite::Integer;
 use serde::{Deserialize, Serialize};
 
 use crate::annotations::Annotation;
 use crate::compilation::CompiledNessaExpr;
 use crate::parser::Location;
-use crate::types::{Type, INT, BOOL, STR, T_0, FLOAT};
+use crate::types::{Type, INT, BOOL, STR, T_0, FLOAT, RANGE};
 use crate::{object::*, ARR_OF};
 use crate::context::NessaContext;
 
@@ -129,6 +130,8 @@ pub const NEG_UNOP_ID: usize = 0;
 pub const NOT_UNOP_ID: usize = 1;
 pub const DEREF_UNOP_ID: usize = 2;
 
+pub const PROPAGATE_PREC: usize = 145; // Built-in postfix ? operator
+
 pub fn standard_unary_operations(ctx: &mut NessaContext) {
     ctx.define_unary_operator("-".into(), true, 300).unwrap();
 
@@ -177,6 +180,15 @@ macro_rules! define_binary_native_op_combinations {
     };
 }
 
+// In-place operations on mutable references, implemented by their opcodes
+macro_rules! define_compound_assignment_native_op_combinations {
+    ($ctx: ident, $id: expr, $base_type: expr) => {
+        define_binary_native_op!($ctx, $id, $base_type.to_mut(), $base_type, Type::Empty);
+        define_binary_native_op!($ctx, $id, $base_type.to_mut(), $base_type.to_ref(), Type::Empty);
+        define_binary_native_op!($ctx, $id, $base_type.to_mut(), $base_type.to_mut(), Type::Empty);
+    };
+}
+
 // Constant identifiers
 pub const ADD_BINOP_ID: usize = 0;
 pub const SUB_BINOP_ID: usize = 1;
@@ -208,6 +220,27 @@ pub const LT_BINOP_PREC: usize = 900;
 
 pub const DEFINE_BINOP_ID: usize = 20;
 
+pub const ADD_ASSIGN_BINOP_ID: usize = 21;
+pub const SUB_ASSIGN_BINOP_ID: usize = 22;
+pub const MUL_ASSIGN_BINOP_ID: usize = 23;
+pub const DIV_ASSIGN_BINOP_ID: usize = 24;
+pub const MOD_ASSIGN_BINOP_ID: usize = 25;
+
+pub const RANGE_BINOP_ID: usize = 26;
+pub const RANGE_INCL_BINOP_ID: usize = 27;
+
+// Operation that a compound assignment falls back to when it has no overload (a = a op b)
+pub fn compound_assignment_base(id: usize) -> Option<usize> {
+    match id {
+        ADD_ASSIGN_BINOP_ID => Some(ADD_BINOP_ID),
+        SUB_ASSIGN_BINOP_ID => Some(SUB_BINOP_ID),
+        MUL_ASSIGN_BINOP_ID => Some(MUL_BINOP_ID),
+        DIV_ASSIGN_BINOP_ID => Some(DIV_BINOP_ID),
+        MOD_ASSIGN_BINOP_ID => Some(MOD_BINOP_ID),
+        _ => None
+    }
+}
+
 pub fn standard_binary_operations(ctx: &mut NessaContext) {
     
     /*
@@ -356,9 +389,70 @@ pub fn standard_binary_operations(ctx: &mut NessaContext) {
     define_binary_native_op_combinations!(ctx, 19, INT, INT);
 
     ctx.define_binary_operator("=".into(), false, 200000).unwrap();
+
+    /*
+        ╒══════════════════════╕
+        │ Compound assignments │
+        ╘══════════════════════╛
+    */
+
+    ctx.define_binary_operator("+=".into(), false, 100100).unwrap();
+
+    define_compound_assignment_native_op_combinations!(ctx, 21, INT);
+    define_compound_assignment_native_op_combinations!(ctx, 21, FLOAT);
+    define_compound_assignment_native_op_combinations!(ctx, 21, STR);
+
+    ctx.define_binary_operator("-=".into(), false, 100200).unwrap();
+
+    define_compound_assignment_native_op_combinations!(ctx, 22, INT);
+    define_compound_assignment_native_op_combinations!(ctx, 22, FLOAT);
+
+    ctx.define_binary_operator("*=".into(), false, 100300).unwrap();
+
+    define_compound_assignment_native_op_combinations!(ctx, 23, INT);
+    define_compound_assignment_native_op_combinations!(ctx, 23, FLOAT);
+
+    ctx.define_binary_operator("/=".into(), false, 100400).unwrap();
+
+    define_compound_assignment_native_op_combinations!(ctx, 24, INT);
+    define_compound_assignment_native_op_combinations!(ctx, 24, FLOAT);
+
+    ctx.define_binary_operator("%=".into(), false, 100500).unwrap();
+
+    define_compound_assignment_native_op_combinations!(ctx, 25, INT);
+    define_compound_assignment_native_op_combinations!(ctx, 25, FLOAT);
+
+    /*
+        ╒════════╕
+        │ Ranges │
+        ╘════════╛
+    */
+
+    let int_args = [INT, INT.to_ref(), INT.to_mut()];
+
+    ctx.define_binary_operator("..".into(), false, 800).unwrap();
+
+    for a in &int_args {
+        for b in &int_args {
+            ctx.define_native_binary_operation(26, 0, a.clone(), b.clone(), RANGE, |_, _, a, b, _| {
+                Ok(Object::range(a.deref_if_ref().get::<Integer>().clone(), b.deref_if_ref().get::<Integer>().clone(), false))
+            }).unwrap();
+        }
+    }
+
+    ctx.define_binary_operator("..=".into(), false, 850).unwrap();
+
+    for a in &int_args {
+        for b in &int_args {
+            ctx.define_native_binary_operation(27, 0, a.clone(), b.clone(), RANGE, |_, _, a, b, _| {
+                Ok(Object::range(a.deref_if_ref().get::<Integer>().clone(), b.deref_if_ref().get::<Integer>().clone(), true))
+            }).unwrap();
+        }
+    }
 }
 
 pub const CALL_OP: usize = 0;
+pub const IDX_OP: usize = 1;
 
 pub fn standard_nary_operations(ctx: &mut NessaContext) {
     ctx.define_nary_operator("(".into(), ")".into(), 50).unwrap();
@@ -1,7 +1,7 @@
 use rustc_hash::{FxHashMap, FxHashSet};
 use malachite::Integer;
 
-use crate::{compilation::{CompiledNessaExpr, NessaInstruction}, context::NessaContext, integer_ext::{is_valid_index, to_usize, ONE}, object::Object, operations::{ADD_BINOP_ID, ASSIGN_BINOP_ID, DEREF_UNOP_ID, DIV_BINOP_ID, MOD_BINOP_ID, MUL_BINOP_ID, NEG_UNOP_ID, SHL_BINOP_ID, SUB_BINOP_ID}, parser::{Location, NessaExpr}, types::{Type, FLOAT, INT}};
+use crate::{compilation::{CompiledNessaExpr, NessaInstruction}, context::NessaContext, integer_ext::{is_valid_index, to_usize, ONE}, object::Object, operations::{ADD_ASSIGN_BINOP_ID, ADD_BINOP_ID, ASSIGN_BINOP_ID, DEREF_UNOP_ID, DIV_BINOP_ID, MOD_BINOP_ID, MUL_BINOP_ID, NEG_UNOP_ID, SHL_BINOP_ID, SUB_ASSIGN_BINOP_ID, SUB_BINOP_ID}, parser::{Location, NessaExpr}, types::{Type, FLOAT, INT}};
 
 /*
     ╒═══════════════════════════╕
@@ -28,6 +28,7 @@ impl NessaContext {
             NessaExpr::UnaryOperation(_, _, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) => NessaContext::count_usages_expr(e, var_usages, offset),
 
@@ -39,6 +40,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::FunctionCall(_, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     NessaContext::count_usages_expr(e, var_usages, offset);
@@ -68,6 +70,24 @@ impl NessaContext {
                 NessaContext::count_usages_expr(b, var_usages, offset);
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for e in b.iter().chain(h) {
+                    NessaContext::count_usages_expr(e, var_usages, offset);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                NessaContext::count_usages_expr(e, var_usages, offset);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        NessaContext::count_usages_expr(g_inner, var_usages, offset);
+                    }
+
+                    NessaContext::count_usages_expr(b, var_usages, offset);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 NessaContext::count_usages_expr(ic, var_usages, offset);
                 
@@ -94,13 +114,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -114,6 +134,7 @@ impl NessaContext {
     pub fn insert_moves_expr(&self, expr: &mut NessaExpr, var_usages: &mut FxHashMap<usize, usize>) {
         match expr {
             NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) => self.insert_moves_expr(e, var_usages),
@@ -131,6 +152,7 @@ impl NessaContext {
             }
 
             NessaExpr::DoBlock(_, exprs, _) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     self.insert_moves_expr(e, var_usages);
@@ -190,6 +212,24 @@ impl NessaContext {
                 self.insert_moves_expr(b, var_usages);
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for e in b.iter_mut().chain(h) {
+                    self.insert_moves_expr(e, var_usages);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.insert_moves_expr(e, var_usages);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.insert_moves_expr(g_inner, var_usages);
+                    }
+
+                    self.insert_moves_expr(b, var_usages);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 self.insert_moves_expr(ic, var_usages);
                 
@@ -217,13 +257,13 @@ impl NessaContext {
             NessaExpr::Variable(_, _, _, _) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -252,6 +292,7 @@ impl NessaContext {
         match expr {
             NessaExpr::UnaryOperation(_, _, _, e) |
             NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) => self.strength_reduction_expr(e),
@@ -263,6 +304,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::CompiledLambda(_, _, _, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     self.strength_reduction_expr(e);
@@ -369,6 +411,24 @@ impl NessaContext {
                 }
             }
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for e in b.iter_mut().chain(h) {
+                    self.strength_reduction_expr(e);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.strength_reduction_expr(e);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.strength_reduction_expr(g_inner);
+                    }
+
+                    self.strength_reduction_expr(b);
+                }
+            },
+
             NessaExpr::NaryOperation(_, _, _, c, exprs) |
             NessaExpr::CompiledFor(_, _, _, _, c, exprs) |
             NessaExpr::While(_, c, exprs) => {
@@ -429,6 +489,27 @@ impl NessaContext {
                     }
                 }
 
+                // Compound assignments by one are also increments and decrements
+                if (*id == ADD_ASSIGN_BINOP_ID || *id == SUB_ASSIGN_BINOP_ID) && t_a == INT.to_mut() && t_b == INT {
+                    if let NessaExpr::Literal(_, obj) = &**b {
+                        if obj.get_type() == INT && *obj.get::<Integer>() == *ONE {
+                            let func = if *id == ADD_ASSIGN_BINOP_ID { "inc" } else { "dec" };
+
+                            *expr = NessaExpr::FunctionCall(
+                                l.clone(), 
+                                self.get_function_id(func.into()).unwrap(), 
+                                vec!(), 
+                                vec!(*a.clone())
+                            );
+
+                            // Sanity check and overload registration
+                            self.static_check(expr).unwrap();
+
+                            return;
+                        }
+                    }
+                }
+
                 // Change multiplications for shifts when applicable
                 if *id == MUL_BINOP_ID && *t_a.deref_type() == INT && *t_b.deref_type() == INT {
                     if let NessaExpr::Literal(_, obj) = &**a {
@@ -504,13 +585,13 @@ impl NessaContext {
             NessaExpr::Variable(_, _, _, _) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -541,9 +622,15 @@ impl NessaContext {
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::Return(_, e) => NessaContext::max_variable(e, offset),
 
+            NessaExpr::CompiledPropagate(_, value_idx, e) => {
+                *offset = (*offset).max(*value_idx);
+                NessaContext::max_variable(e, offset)
+            },
+
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::CompiledLambda(_, _, _, _, _, exprs) |
             NessaExpr::FunctionCall(_, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     NessaContext::max_variable(e, offset);
@@ -583,6 +670,32 @@ impl NessaContext {
                 NessaContext::max_variable(b, offset);
             },
 
+            NessaExpr::CompiledTry(_, b, error_idx, _, _, h) => {
+                *offset = (*offset).max(*error_idx);
+
+                for e in b.iter().chain(h) {
+                    NessaContext::max_variable(e, offset);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, match_idx, e, arms) => {
+                *offset = (*offset).max(*match_idx);
+
+                NessaContext::max_variable(e, offset);
+
+                for (p, g, b) in arms {
+                    for (id, _, _) in p.bindings() {
+                        *offset = (*offset).max(id);
+                    }
+
+                    if let Some(g_inner) = g {
+                        NessaContext::max_variable(g_inner, offset);
+                    }
+
+                    NessaContext::max_variable(b, offset);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 NessaContext::max_variable(ic, offset);
                 
@@ -609,13 +722,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -642,6 +755,11 @@ impl NessaContext {
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::Return(_, e) => NessaContext::offset_variables(e, offset),
 
+            NessaExpr::CompiledPropagate(_, value_idx, e) => {
+                *value_idx += offset;
+                NessaContext::offset_variables(e, offset)
+            },
+
             NessaExpr::CompiledLambda(_, _, c, _, _, exprs) => {
                 for (_, e) in c {
                     NessaContext::offset_variables(e, offset);
@@ -654,6 +772,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::FunctionCall(_, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     NessaContext::offset_variables(e, offset);
@@ -693,6 +812,30 @@ impl NessaContext {
                 NessaContext::offset_variables(b, offset);
             },
 
+            NessaExpr::CompiledTry(_, b, error_idx, _, _, h) => {
+                *error_idx += offset;
+
+                for e in b.iter_mut().chain(h) {
+                    NessaContext::offset_variables(e, offset);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, match_idx, e, arms) => {
+                *match_idx += offset;
+
+                NessaContext::offset_variables(e, offset);
+
+                for (p, g, b) in arms {
+                    p.offset_bindings(offset);
+
+                    if let Some(g_inner) = g {
+                        NessaContext::offset_variables(g_inner, offset);
+                    }
+
+                    NessaContext::offset_variables(b, offset);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 NessaContext::offset_variables(ic, offset);
                 
@@ -719,13 +862,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -774,6 +917,7 @@ impl NessaContext {
     pub fn inline_functions_expr(&self, expr: &mut NessaExpr, offset: &mut usize) {
         match expr {
             NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) => self.inline_functions_expr(e, offset),
@@ -785,6 +929,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::CompiledLambda(_, _, _, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 self.inline_functions(exprs, offset);
             },
@@ -806,7 +951,8 @@ impl NessaContext {
                 if let Some(inner) = body {
                     let weight = self.inlining_weight(inner);
 
-                    if weight < INLINE_THRESHOLD {
+                    // Arguments that propagate values have to return from the caller, not from the inlined body
+                    if weight < INLINE_THRESHOLD && e.propagations().is_empty() {
                         let mut inlined_body = self.inline_body(inner.clone(), vec!(*e.clone()), offset, l);
                         let return_type = self.infer_type(expr).unwrap();
 
@@ -844,7 +990,8 @@ impl NessaContext {
                 if let Some(inner) = body {
                     let weight = self.inlining_weight(inner);
 
-                    if weight < INLINE_THRESHOLD {
+                    // Arguments that propagate values have to return from the caller, not from the inlined body
+                    if weight < INLINE_THRESHOLD && a.propagations().is_empty() && b.propagations().is_empty() {
                         let mut inlined_body = self.inline_body(inner.clone(), vec!(*a.clone(), *b.clone()), offset, l);
                         let return_type = self.infer_type(expr).unwrap();
 
@@ -884,7 +1031,8 @@ impl NessaContext {
                 if let Some(inner) = body {
                     let weight = self.inlining_weight(inner);
 
-                    if weight < INLINE_THRESHOLD {
+                    // Arguments that propagate values have to return from the caller, not from the inlined body
+                    if weight < INLINE_THRESHOLD && c.propagations().is_empty() && exprs.iter().all(|i| i.propagations().is_empty()) {
                         let mut args_vec = vec!(*c.clone());
                         args_vec.extend(exprs.iter().cloned());
         
@@ -924,7 +1072,8 @@ impl NessaContext {
                 if let Some(inner) = body {
                     let weight = self.inlining_weight(inner);
 
-                    if weight < INLINE_THRESHOLD {
+                    // Arguments that propagate values have to return from the caller, not from the inlined body
+                    if weight < INLINE_THRESHOLD && args.iter().all(|i| i.propagations().is_empty()) {
                         let mut inlined_body = self.inline_body(inner.clone(), args.clone(), offset, l);
                         let return_type = self.infer_type(expr).unwrap();
 
@@ -949,6 +1098,23 @@ impl NessaContext {
                 self.inline_functions(exprs, offset);
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                self.inline_functions(b, offset);
+                self.inline_functions(h, offset);
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.inline_functions_expr(e, offset);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.inline_functions_expr(g_inner, offset);
+                    }
+
+                    self.inline_functions_expr(b, offset);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 self.inline_functions_expr(ic, offset);
                 self.inline_functions(ib, offset);
@@ -968,13 +1134,13 @@ impl NessaContext {
             NessaExpr::Variable(_, _, _, _) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -1123,10 +1289,12 @@ impl NessaContext {
         match expr {
             NessaExpr::UnaryOperation(_, _, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
-            NessaExpr::Return(_, e)  => self.get_constants(e, consts, const_exprs),
+            NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) => self.get_constants(e, consts, const_exprs),
             
-            NessaExpr::CompiledVariableDefinition(_, id, _, _, e) => { 
-                if self.is_constant_expr(e, consts) {
+            NessaExpr::CompiledVariableDefinition(_, id, _, t, e) => { 
+                // Variables with a wider type than their value (such as unions) are kept
+                if self.is_constant_expr(e, consts) && self.infer_type(e).is_ok_and(|i| i == *t) {
                     consts.insert(*id, true);
                     const_exprs.insert(*id, NessaExpr::Literal(Location::none(), NessaContext::compute_constant_expr(e, const_exprs)));    
                 }
@@ -1135,6 +1303,7 @@ impl NessaContext {
 
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::FunctionCall(_, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     self.get_constants(e, consts, const_exprs);
@@ -1164,6 +1333,24 @@ impl NessaContext {
                 self.get_constants(b, consts, const_exprs);
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for e in b.iter().chain(h) {
+                    self.get_constants(e, consts, const_exprs);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.get_constants(e, consts, const_exprs);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.get_constants(g_inner, consts, const_exprs);
+                    }
+
+                    self.get_constants(b, consts, const_exprs);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 self.get_constants(ic, consts, const_exprs);
                 
@@ -1192,13 +1379,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -1227,7 +1414,8 @@ impl NessaContext {
             NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
-            NessaExpr::Return(_, e)  => self.sub_variables(e, assigned_exprs),
+            NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) => self.sub_variables(e, assigned_exprs),
 
             NessaExpr::UnaryOperation(_, _, _, e) => {
                 self.sub_variables(e, assigned_exprs);
@@ -1237,13 +1425,26 @@ impl NessaContext {
             }
 
             NessaExpr::DoBlock(_, exprs, _) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => {
                 for e in exprs {
                     self.sub_variables(e, assigned_exprs);
                 }
             },
 
-            NessaExpr::FunctionCall(_, _, _, exprs) => {
+            NessaExpr::FunctionCall(_, id, _, exprs) => {
+                let move_id = self.get_function_id("move".into()).unwrap();
+
+                // Moving a constant is the same as using its value
+                if *id == move_id && exprs.len() == 1 {
+                    if let NessaExpr::Variable(_, var_id, _, _) = &exprs[0] {
+                        if let Some(const_expr) = assigned_exprs.get(var_id) {
+                            *expr = const_expr.clone();
+                            return;
+                        }
+                    }
+                }
+
                 for e in exprs {
                     self.sub_variables(e, assigned_exprs);
                 }
@@ -1285,6 +1486,24 @@ impl NessaContext {
                 self.static_check(&expr).unwrap();
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                for e in b.iter_mut().chain(h) {
+                    self.sub_variables(e, assigned_exprs);
+                }
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.sub_variables(e, assigned_exprs);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.sub_variables(g_inner, assigned_exprs);
+                    }
+
+                    self.sub_variables(b, assigned_exprs);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 self.sub_variables(ic, assigned_exprs);
                 
@@ -1312,13 +1531,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -1347,10 +1566,12 @@ impl NessaContext {
             NessaExpr::CompiledVariableAssignment(_, _, _, _, e) |
             NessaExpr::UnaryOperation(_, _, _, e) |
             NessaExpr::AttributeAccess(_, e, _) |
-            NessaExpr::Return(_, e)  => self.remove_assignments_expr(e, assigned_exprs),
+            NessaExpr::Return(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) => self.remove_assignments_expr(e, assigned_exprs),
             
             NessaExpr::DoBlock(_, exprs, _) |
             NessaExpr::FunctionCall(_, _, _, exprs) |
+            NessaExpr::VariadicArgs(_, _, exprs) |
             NessaExpr::Tuple(_, exprs) => self.remove_assignments(exprs, assigned_exprs),
 
             NessaExpr::CompiledFor(_, _, _, _, c, exprs) |
@@ -1370,6 +1591,23 @@ impl NessaContext {
                 self.remove_assignments_expr(b, assigned_exprs);
             },
 
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => {
+                self.remove_assignments(b, assigned_exprs);
+                self.remove_assignments(h, assigned_exprs);
+            },
+
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                self.remove_assignments_expr(e, assigned_exprs);
+
+                for (_, g, b) in arms {
+                    if let Some(g_inner) = g {
+                        self.remove_assignments_expr(g_inner, assigned_exprs);
+                    }
+
+                    self.remove_assignments_expr(b, assigned_exprs);
+                }
+            },
+
             NessaExpr::If(_, ic, ib, ei, eb) => {
                 self.remove_assignments_expr(ic, assigned_exprs);
                 self.remove_assignments(ib, assigned_exprs);
@@ -1390,13 +1628,13 @@ impl NessaContext {
             NessaExpr::Continue(_) |
             NessaExpr::Literal(_, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -1467,9 +1705,26 @@ fn compute_labels(program: &mut [NessaInstruction]) {
                     *to = labels[to];
                 }
             },
+
+            CompiledNessaExpr::DynamicCall(_, table) => {
+                for (_, i) in table {
+                    if let CompiledNessaExpr::Call(to) = i {
+                        if !labels.contains_key(to) {
+                            labels.entry(*to).or_insert(curr_idx);
+                            *to = curr_idx;
+                            curr_idx += 1;
+        
+                        } else {
+                            *to = labels[to];
+                        }
+                    }
+                }
+            },
                     
             CompiledNessaExpr::RelativeJumpIfFalse(to, _) |
-            CompiledNessaExpr::RelativeJumpIfTrue(to, _) => {
+            CompiledNessaExpr::RelativeJumpIfTrue(to, _) |
+            CompiledNessaExpr::Try(to) |
+            CompiledNessaExpr::StackBase(to) => {
                 let p = idx + *to;
 
                 if !labels.contains_key(&p) {
@@ -1525,9 +1780,19 @@ fn reassign_labels(program: &mut [NessaInstruction]) {
             CompiledNessaExpr::Jump(to) => {
                 *to = positions[to];
             },
+
+            CompiledNessaExpr::DynamicCall(_, table) => {
+                for (_, i) in table {
+                    if let CompiledNessaExpr::Call(to) = i {
+                        *to = positions[to];
+                    }
+                }
+            },
                     
             CompiledNessaExpr::RelativeJumpIfFalse(to, _) |
-            CompiledNessaExpr::RelativeJumpIfTrue(to, _) => {
+            CompiledNessaExpr::RelativeJumpIfTrue(to, _) |
+            CompiledNessaExpr::Try(to) |
+            CompiledNessaExpr::StackBase(to) => {
                 *to = positions[to] - idx;
             },
     
@@ -1797,6 +2062,16 @@ impl NessaContext {
                 };
             }
 
+            // Calls inside try blocks have to keep their frame so errors can be caught
+            let mut protected = vec!(false; program.len());
+
+            for (idx, i) in program.iter().enumerate() {
+                if let Try(tag) = i.instruction {
+                    let handler = program.iter().position(|j| j.debug_info.labels.contains(&tag)).unwrap();
+                    protected[idx..handler].fill(true);
+                }
+            }
+
             for i in 0..(program.len() - 1) {
                 macro_rules! change_first {
                     ($new_expr: expr) => {
@@ -1808,6 +2083,10 @@ impl NessaContext {
                     };
                 }
                 
+                if protected[i] {
+                    continue;
+                }
+
                 if let [Call(loc), Return] = [&program[i].instruction, &program[i + 1].instruction] {
                     change_first!(Jump(*loc));
                 }
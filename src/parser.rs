@@ -6,8 +6,8 @@ use std::sync::Arc;
 use malachite::num::conversion::string::options::FromSciStringOptions;
 use malachite::num::conversion::traits::FromSciString;
 use nom::AsChar;
-use nom::bytes::complete::{tag, take_till, take_until};
-use nom::combinator::{cut, map_opt};
+use nom::bytes::complete::{is_not, tag, take_till, take_until};
+use nom::combinator::{cut, map_opt, verify};
 use nom::error::{VerboseError, VerboseErrorKind, context};
 use nom::sequence::preceded;
 use nom::{
@@ -17,7 +17,7 @@ use nom::{
     sequence::{tuple, delimited, terminated},
     branch::alt,
     character::complete::{multispace1, satisfy},
-    multi::{separated_list1, separated_list0}
+    multi::{separated_list1, separated_list0, many0, fold_many1}
 };
 
 use nom_locate::LocatedSpan;
@@ -35,6 +35,7 @@ use crate::object::{Object, TypeInstance};
 use crate::precedence_cache::PrecedenceCache;
 use crate::types::*;
 use crate::operations::*;
+use crate::ARR_OF;
 use crate::context::NessaContext;
 use crate::patterns::*;
 
@@ -46,18 +47,20 @@ type UnaryOpHeader = (usize, Vec<String>, String, Type, Type);
 type BinaryOpHeader = (usize, Vec<String>, (String, Type), (String, Type), Type);
 type NaryOpHeader = (usize, Vec<String>, (String, Type), Vec<(String, Type)>, Type);
 type FunctionHeader = (String, Option<Vec<String>>, Vec<(String, Type)>, Type);
+type FunctionParams = (Vec<Option<NessaExpr>>, Vec<(String, MatchPattern)>, bool);
+type EnumVariant = (Location, String, Vec<(String, Type)>);
 
 type AnnotUnaryOpHeader = (Vec<Annotation>, usize, Vec<String>, String, Type, Type);
 type AnnotBinaryOpHeader = (Vec<Annotation>, usize, Vec<String>, (String, Type), (String, Type), Type);
 type AnnotNaryOpHeader = (Vec<Annotation>, usize, Vec<String>, (String, Type), Vec<(String, Type)>, Type);
-type AnnotFunctionHeader = (Vec<Annotation>, String, Option<Vec<String>>, Vec<(String, Type)>, Type);
+type AnnotFunctionHeader = (Vec<Annotation>, String, Option<Vec<String>>, Vec<(String, Type)>, Type, Option<Vec<NessaExpr>>);
 
 #[derive(Debug, PartialEq, Clone, Eq)]
 pub enum InterfaceHeader {
     UnaryOpHeader(Vec<Annotation>, usize, Vec<String>, String, Type, Type),
     BinaryOpHeader(Vec<Annotation>, usize, Vec<String>, (String, Type), (String, Type), Type),
     NaryOpHeader(Vec<Annotation>, usize, Vec<String>, (String, Type), Vec<(String, Type)>, Type),
-    FunctionHeader(Vec<Annotation>, String, Option<Vec<String>>, Vec<(String, Type)>, Type)
+    FunctionHeader(Vec<Annotation>, String, Option<Vec<String>>, Vec<(String, Type)>, Type, Option<Vec<NessaExpr>>)
 }
 
 pub fn verbose_error<'a>(input: Span<'a>, msg: &'static str) -> nom::Err<VerboseError<Span<'a>>> {
@@ -134,6 +137,28 @@ fn skip_token(input: Span<'_>) -> PResult<'_, ()> {
     ))(input);
 }
 
+// Skips a parenthesized group without parsing its contents
+fn skip_parenthesized(input: Span<'_>) -> PResult<'_, ()> {
+    let (mut input, _) = tag("(")(input)?;
+    let mut depth = 1;
+
+    while depth > 0 {
+        if let Ok((i, _)) = tag::<_, _, VerboseError<Span<'_>>>(")")(input) {
+            input = i;
+            depth -= 1;
+
+        } else if let Ok((i, _)) = tag::<_, _, VerboseError<Span<'_>>>("(")(input) {
+            input = i;
+            depth += 1;
+
+        } else {
+            input = skip_token(input)?.0;
+        }
+    }
+
+    Ok((input, ()))
+}
+
 pub fn empty0(mut input: Span<'_>) -> PResult<'_, ()> {
     while let Ok((i, _)) = separator(input) {
         input = i;
@@ -191,6 +216,19 @@ pub fn many_separated0<
                                                   ╘══════════════════╛
 */
 
+#[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
+pub enum MatchPattern {
+    Wildcard,
+    Literal(Object),
+    Type(Type),
+    Tuple(Vec<MatchPattern>),
+    Class(Type, Vec<MatchPattern>),
+    Binding(String, Box<MatchPattern>),
+    CompiledBinding(usize, String, Type, Box<MatchPattern>) // Register and type of the bound value
+}
+
+pub type MatchArm = (MatchPattern, Option<NessaExpr>, NessaExpr);
+
 #[derive(Debug, PartialEq, Clone, Eq, Serialize, Deserialize)]
 pub enum NessaExpr {
     // Compiled
@@ -200,6 +238,10 @@ pub enum NessaExpr {
     CompiledVariableAssignment(Location, usize, String, Type, Box<NessaExpr>),
     FunctionCall(Location, usize, Vec<Type>, Vec<NessaExpr>),
     CompiledFor(Location, usize, usize, String, Box<NessaExpr>, Vec<NessaExpr>),
+    CompiledMatch(Location, usize, Box<NessaExpr>, Vec<MatchArm>),
+    CompiledTry(Location, Vec<NessaExpr>, usize, String, Type, Vec<NessaExpr>),
+    CompiledPropagate(Location, usize, Box<NessaExpr>),
+    VariadicArgs(Location, Type, Vec<NessaExpr>),
     DoBlock(Location, Vec<NessaExpr>, Type),
     AttributeAccess(Location, Box<NessaExpr>, usize),
     AttributeAssignment(Location, Box<NessaExpr>, Box<NessaExpr>, usize),
@@ -216,20 +258,26 @@ pub enum NessaExpr {
     Tuple(Location, Vec<NessaExpr>),
     Lambda(Location, Vec<String>, Vec<(String, Type)>, Type, Vec<NessaExpr>),
     NameReference(Location, String),
+    Match(Location, Box<NessaExpr>, Vec<MatchArm>),
+    Propagate(Location, Box<NessaExpr>),
+    Interpolation(Location, Box<NessaExpr>),
+    NamedArgument(Location, String, Box<NessaExpr>),
 
     UnaryOperation(Location, usize, Vec<Type>, Box<NessaExpr>),
     BinaryOperation(Location, usize, Vec<Type>, Box<NessaExpr>, Box<NessaExpr>),
     NaryOperation(Location, usize, Vec<Type>, Box<NessaExpr>, Vec<NessaExpr>),
+    CompoundAssignment(Location, usize, Box<NessaExpr>, Box<NessaExpr>),
 
     VariableDefinition(Location, String, Type, Box<NessaExpr>),
+    DestructuringDefinition(Location, MatchPattern, Box<NessaExpr>),
     VariableAssignment(Location, String, Box<NessaExpr>),
-    FunctionDefinition(Location, Vec<Annotation>, usize, Vec<String>, Vec<(String, Type)>, Type, Vec<NessaExpr>),
+    FunctionDefinition(Location, Vec<Annotation>, usize, Vec<String>, Vec<(String, Type)>, Vec<Option<NessaExpr>>, bool, Type, Vec<NessaExpr>),
     PrefixOperatorDefinition(Location, String, usize),
     PostfixOperatorDefinition(Location, String, usize),
     BinaryOperatorDefinition(Location, String, bool, usize),
     NaryOperatorDefinition(Location, String, String, usize),
-    ClassDefinition(Location, Vec<Annotation>, String, Vec<String>, Vec<(String, Type)>, Option<Type>, Vec<Pattern>),
-    InterfaceDefinition(Location, Vec<Annotation>, String, Vec<String>, Vec<AnnotFunctionHeader>, Vec<AnnotUnaryOpHeader>, Vec<AnnotBinaryOpHeader>, Vec<AnnotNaryOpHeader>),
+    ClassDefinition(Location, Vec<Annotation>, String, Vec<String>, Vec<(String, Type)>, Option<Type>, Vec<Pattern>, bool),
+    InterfaceDefinition(Location, Vec<Annotation>, String, Vec<String>, Vec<InterfaceConstraint>, Vec<AnnotFunctionHeader>, Vec<AnnotUnaryOpHeader>, Vec<AnnotBinaryOpHeader>, Vec<AnnotNaryOpHeader>),
     InterfaceImplementation(Location, Vec<String>, Type, String, Vec<Type>),
 
     PrefixOperationDefinition(Location, Vec<Annotation>, usize, Vec<String>, String, Type, Type, Vec<NessaExpr>),
@@ -240,6 +288,7 @@ pub enum NessaExpr {
     If(Location, Box<NessaExpr>, Vec<NessaExpr>, Vec<(NessaExpr, Vec<NessaExpr>)>, Option<Vec<NessaExpr>>),
     While(Location, Box<NessaExpr>, Vec<NessaExpr>),
     For(Location, String, Box<NessaExpr>, Vec<NessaExpr>),
+    Try(Location, Vec<NessaExpr>, String, Type, Vec<NessaExpr>),
     Return(Location, Box<NessaExpr>)
 }
 
@@ -251,16 +300,17 @@ impl NessaExpr {
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::BinaryOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::NaryOperationDefinition(_, _, _, _, _, _, _, _) => true,
 
             NessaExpr::VariableDefinition(_, _, _, _) |
+            NessaExpr::DestructuringDefinition(_, _, _) |
             NessaExpr::VariableAssignment(_, _, _) |
             NessaExpr::QualifiedName(_, _, _) |
             NessaExpr::CompiledVariableDefinition(_, _, _, _, _) |
@@ -271,19 +321,29 @@ impl NessaExpr {
             NessaExpr::Variable(_, _, _, _) |
             NessaExpr::FunctionCall(_, _, _, _) |
             NessaExpr::CompiledFor(_, _, _, _, _, _) |
+            NessaExpr::CompiledMatch(_, _, _, _) |
+            NessaExpr::CompiledPropagate(_, _, _) |
             NessaExpr::CompiledLambda(_, _, _, _, _, _) |
             NessaExpr::Literal(_, _) |
+            NessaExpr::VariadicArgs(_, _, _) |
             NessaExpr::Tuple(_, _) |
             NessaExpr::Lambda(_, _, _, _, _) |
             NessaExpr::NameReference(_, _) |
+            NessaExpr::Match(_, _, _) |
+            NessaExpr::Propagate(_, _) |
+            NessaExpr::Interpolation(_, _) |
+            NessaExpr::NamedArgument(_, _, _) |
             NessaExpr::UnaryOperation(_, _, _, _) |
             NessaExpr::BinaryOperation(_, _, _, _, _) |
             NessaExpr::NaryOperation(_, _, _, _, _) |
+            NessaExpr::CompoundAssignment(_, _, _, _) |
             NessaExpr::If(_, _, _, _, _) |
             NessaExpr::While(_, _, _) |
             NessaExpr::Break(_) |
             NessaExpr::Continue(_) |
             NessaExpr::For(_, _, _, _) |
+            NessaExpr::Try(_, _, _, _, _) |
+            NessaExpr::CompiledTry(_, _, _, _, _, _) |
             NessaExpr::Return(_, _) => false,
         }
     }
@@ -296,13 +356,14 @@ impl NessaExpr {
             NessaExpr::CompiledVariableAssignment(_, _, _, _, _) |
             NessaExpr::Macro(_, _, _, _, _, _) |
             NessaExpr::VariableDefinition(_, _, _, _) |
+            NessaExpr::DestructuringDefinition(_, _, _) |
             NessaExpr::VariableAssignment(_, _, _) |
             NessaExpr::PrefixOperatorDefinition(_, _, _) |
             NessaExpr::PostfixOperatorDefinition(_, _, _) |
             NessaExpr::BinaryOperatorDefinition(_, _, _, _) |
             NessaExpr::NaryOperatorDefinition(_, _, _, _) |
-            NessaExpr::ClassDefinition(_, _, _, _, _, _, _) |
-            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::ClassDefinition(_, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(_, _, _, _, _, _, _, _, _) |
             NessaExpr::InterfaceImplementation(_, _, _, _, _) |
             NessaExpr::PrefixOperationDefinition(_, _, _, _, _, _, _, _) |
             NessaExpr::PostfixOperationDefinition(_, _, _, _, _, _, _, _) |
@@ -313,6 +374,8 @@ impl NessaExpr {
             NessaExpr::Break(_) |
             NessaExpr::Continue(_) |
             NessaExpr::For(_, _, _, _) |
+            NessaExpr::Try(_, _, _, _, _) |
+            NessaExpr::CompiledTry(_, _, _, _, _, _) |
             NessaExpr::Return(_, _) => false,
 
             NessaExpr::DoBlock(_, _, _) |
@@ -320,15 +383,178 @@ impl NessaExpr {
             NessaExpr::Variable(_, _, _, _) |
             NessaExpr::FunctionCall(_, _, _, _) |
             NessaExpr::CompiledFor(_, _, _, _, _, _) |
+            NessaExpr::CompiledMatch(_, _, _, _) |
+            NessaExpr::CompiledPropagate(_, _, _) |
             NessaExpr::CompiledLambda(_, _, _, _, _, _) |
             NessaExpr::Literal(_, _) |
+            NessaExpr::VariadicArgs(_, _, _) |
             NessaExpr::Tuple(_, _) |
             NessaExpr::Lambda(_, _, _, _, _) |
             NessaExpr::NameReference(_, _) |
+            NessaExpr::Match(_, _, _) |
+            NessaExpr::Propagate(_, _) |
+            NessaExpr::Interpolation(_, _) |
+            NessaExpr::NamedArgument(_, _, _) |
             NessaExpr::UnaryOperation(_, _, _, _) |
             NessaExpr::BinaryOperation(_, _, _, _, _) |
             NessaExpr::NaryOperation(_, _, _, _, _) |
-            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _) => true,
+            NessaExpr::CompoundAssignment(_, _, _, _) |
+            NessaExpr::FunctionDefinition(_, _, _, _, _, _, _, _, _) => true,
+        }
+    }
+
+    pub fn get_location(&self) -> &Location {
+        match self {
+            NessaExpr::QualifiedName(l, _, _) |
+            NessaExpr::Variable(l, _, _, _) |
+            NessaExpr::CompiledVariableDefinition(l, _, _, _, _) |
+            NessaExpr::CompiledVariableAssignment(l, _, _, _, _) |
+            NessaExpr::FunctionCall(l, _, _, _) |
+            NessaExpr::CompiledFor(l, _, _, _, _, _) |
+            NessaExpr::CompiledMatch(l, _, _, _) |
+            NessaExpr::CompiledPropagate(l, _, _) |
+            NessaExpr::VariadicArgs(l, _, _) |
+            NessaExpr::DoBlock(l, _, _) |
+            NessaExpr::AttributeAccess(l, _, _) |
+            NessaExpr::AttributeAssignment(l, _, _, _) |
+            NessaExpr::Break(l) |
+            NessaExpr::Continue(l) |
+            NessaExpr::CompiledLambda(l, _, _, _, _, _) |
+            NessaExpr::Macro(l, _, _, _, _, _) |
+            NessaExpr::Literal(l, _) |
+            NessaExpr::Tuple(l, _) |
+            NessaExpr::Lambda(l, _, _, _, _) |
+            NessaExpr::NameReference(l, _) |
+            NessaExpr::Match(l, _, _) |
+            NessaExpr::Propagate(l, _) |
+            NessaExpr::Interpolation(l, _) |
+            NessaExpr::NamedArgument(l, _, _) |
+            NessaExpr::UnaryOperation(l, _, _, _) |
+            NessaExpr::BinaryOperation(l, _, _, _, _) |
+            NessaExpr::CompoundAssignment(l, _, _, _) |
+            NessaExpr::NaryOperation(l, _, _, _, _) |
+            NessaExpr::VariableDefinition(l, _, _, _) |
+            NessaExpr::DestructuringDefinition(l, _, _) |
+            NessaExpr::VariableAssignment(l, _, _) |
+            NessaExpr::FunctionDefinition(l, _, _, _, _, _, _, _, _) |
+            NessaExpr::PrefixOperatorDefinition(l, _, _) |
+            NessaExpr::PostfixOperatorDefinition(l, _, _) |
+            NessaExpr::BinaryOperatorDefinition(l, _, _, _) |
+            NessaExpr::NaryOperatorDefinition(l, _, _, _) |
+            NessaExpr::ClassDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceDefinition(l, _, _, _, _, _, _, _, _) |
+            NessaExpr::InterfaceImplementation(l, _, _, _, _) |
+            NessaExpr::PrefixOperationDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::PostfixOperationDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::BinaryOperationDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::NaryOperationDefinition(l, _, _, _, _, _, _, _) |
+            NessaExpr::If(l, _, _, _, _) |
+            NessaExpr::While(l, _, _) |
+            NessaExpr::For(l, _, _, _) |
+            NessaExpr::Try(l, _, _, _, _) |
+            NessaExpr::CompiledTry(l, _, _, _, _, _) |
+            NessaExpr::Return(l, _) => l
+        }
+    }
+
+    // Outermost uses of the ? operator that are evaluated as part of this line (nested bodies are not included)
+    pub fn propagations(&self) -> Vec<&NessaExpr> {
+        match self {
+            NessaExpr::Propagate(..) |
+            NessaExpr::CompiledPropagate(..) => vec!(self),
+
+            NessaExpr::VariableDefinition(_, _, _, e) |
+            NessaExpr::DestructuringDefinition(_, _, e) |
+            NessaExpr::VariableAssignment(_, _, e) |
+            NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
+            NessaExpr::CompiledVariableAssignment(_, _, _, _, e) |
+            NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::UnaryOperation(_, _, _, e) |
+            NessaExpr::NamedArgument(_, _, e) |
+            NessaExpr::Interpolation(_, e) |
+            NessaExpr::While(_, e, _) |
+            NessaExpr::For(_, _, e, _) |
+            NessaExpr::CompiledFor(_, _, _, _, e, _) |
+            NessaExpr::Return(_, e) => e.propagations(),
+
+            NessaExpr::AttributeAssignment(_, a, b, _) |
+            NessaExpr::CompoundAssignment(_, _, a, b) |
+            NessaExpr::BinaryOperation(_, _, _, a, b) => a.propagations().into_iter().chain(b.propagations()).collect(),
+
+            NessaExpr::FunctionCall(_, _, _, b) |
+            NessaExpr::VariadicArgs(_, _, b) |
+            NessaExpr::Tuple(_, b) => b.iter().flat_map(NessaExpr::propagations).collect(),
+
+            NessaExpr::NaryOperation(_, _, _, a, b) => a.propagations().into_iter().chain(b.iter().flat_map(NessaExpr::propagations)).collect(),
+
+            NessaExpr::If(_, ih, _, ei, _) => ih.propagations().into_iter().chain(ei.iter().flat_map(|(h, _)| h.propagations())).collect(),
+
+            NessaExpr::Match(_, e, arms) |
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                let mut res = e.propagations();
+
+                for (_, g, b) in arms {
+                    res.extend(g.iter().flat_map(NessaExpr::propagations));
+                    res.extend(b.propagations());
+                }
+
+                res
+            }
+
+            _ => vec!()
+        }
+    }
+
+    // Checks if a variable with this name is assigned anywhere inside this expression (lambdas only assign their own copies)
+    pub fn assigns_variable(&self, name: &String) -> bool {
+        let any = |v: &Vec<NessaExpr>| v.iter().any(|i| i.assigns_variable(name));
+
+        match self {
+            NessaExpr::VariableAssignment(_, n, e) |
+            NessaExpr::CompiledVariableAssignment(_, _, n, _, e) => n == name || e.assigns_variable(name),
+
+            NessaExpr::VariableDefinition(_, _, _, e) |
+            NessaExpr::DestructuringDefinition(_, _, e) |
+            NessaExpr::CompiledVariableDefinition(_, _, _, _, e) |
+            NessaExpr::AttributeAccess(_, e, _) |
+            NessaExpr::UnaryOperation(_, _, _, e) |
+            NessaExpr::Propagate(_, e) |
+            NessaExpr::CompiledPropagate(_, _, e) |
+            NessaExpr::NamedArgument(_, _, e) |
+            NessaExpr::Interpolation(_, e) |
+            NessaExpr::Return(_, e) => e.assigns_variable(name),
+
+            NessaExpr::AttributeAssignment(_, a, b, _) |
+            NessaExpr::CompoundAssignment(_, _, a, b) |
+            NessaExpr::BinaryOperation(_, _, _, a, b) => a.assigns_variable(name) || b.assigns_variable(name),
+
+            NessaExpr::NaryOperation(_, _, _, a, b) => a.assigns_variable(name) || any(b),
+
+            NessaExpr::FunctionCall(_, _, _, b) |
+            NessaExpr::VariadicArgs(_, _, b) |
+            NessaExpr::Tuple(_, b) |
+            NessaExpr::DoBlock(_, b, _) => any(b),
+
+            NessaExpr::While(_, c, b) |
+            NessaExpr::For(_, _, c, b) |
+            NessaExpr::CompiledFor(_, _, _, _, c, b) => c.assigns_variable(name) || any(b),
+
+            NessaExpr::Try(_, b, _, _, h) |
+            NessaExpr::CompiledTry(_, b, _, _, _, h) => any(b) || any(h),
+
+            NessaExpr::If(_, ih, ib, ei, eb) => {
+                ih.assigns_variable(name) || any(ib) ||
+                ei.iter().any(|(h, b)| h.assigns_variable(name) || any(b)) ||
+                eb.as_ref().is_some_and(any)
+            },
+
+            NessaExpr::Match(_, e, arms) |
+            NessaExpr::CompiledMatch(_, _, e, arms) => {
+                e.assigns_variable(name) ||
+                arms.iter().any(|(_, g, b)| g.as_ref().is_some_and(|i| i.assigns_variable(name)) || b.assigns_variable(name))
+            }
+
+            _ => false
         }
     }
 }
@@ -524,69 +750,210 @@ pub fn nessa_module_imports_parser(mut input: Span<'_>, module: Arc<String>) ->
 
 impl NessaExpr {
     pub fn compile_types(&mut self, templates: &Vec<String>) {
+        self.map_types(&mut |t| t.compile_templates(templates));
+    }
+
+    // Applies a function to every type written in an uncompiled expression
+    pub fn map_types(&mut self, f: &mut impl FnMut(&mut Type)) {
         match self {
             NessaExpr::VariableDefinition(_, _, t, e) => {
-                t.compile_templates(templates);
-                e.compile_types(templates);
+                f(t);
+                e.map_types(f);
+            }
+
+            NessaExpr::DestructuringDefinition(_, p, e) => {
+                p.types_mut().into_iter().for_each(&mut *f);
+                e.map_types(f);
             }
 
+            NessaExpr::VariableAssignment(_, _, e) => e.map_types(f),
+
+            NessaExpr::VariadicArgs(_, _, e) |
             NessaExpr::Tuple(_, e) => {
-                e.iter_mut().for_each(|i| i.compile_types(templates));
+                e.iter_mut().for_each(|i| i.map_types(f));
+            }
+
+            NessaExpr::FunctionCall(_, _, t, e) => {
+                t.iter_mut().for_each(&mut *f);
+                e.iter_mut().for_each(|i| i.map_types(f));
             }
 
             NessaExpr::UnaryOperation(_, _, t, e) => {
-                t.iter_mut().for_each(|i| i.compile_templates(templates));
-                e.compile_types(templates);
+                t.iter_mut().for_each(&mut *f);
+                e.map_types(f);
             },
 
             NessaExpr::BinaryOperation(_, _, t, a, b) => {
-                t.iter_mut().for_each(|i| i.compile_templates(templates));
-                a.compile_types(templates);
-                b.compile_types(templates);
+                t.iter_mut().for_each(&mut *f);
+                a.map_types(f);
+                b.map_types(f);
+            },
+            NessaExpr::CompoundAssignment(_, _, a, b) => {
+                a.map_types(f);
+                b.map_types(f);
             },
             NessaExpr::NaryOperation(_, _, t, a, b) => {
-                t.iter_mut().for_each(|i| i.compile_templates(templates));
-                a.compile_types(templates);
-                b.iter_mut().for_each(|i| i.compile_types(templates));
+                t.iter_mut().for_each(&mut *f);
+                a.map_types(f);
+                b.iter_mut().for_each(|i| i.map_types(f));
             },
 
             NessaExpr::If(_, h, ib, ei, eb) => {
-                h.compile_types(templates);
-                ib.iter_mut().for_each(|i| i.compile_types(templates));
+                h.map_types(f);
+                ib.iter_mut().for_each(|i| i.map_types(f));
 
                 ei.iter_mut().for_each(|(ei_h, ei_b)| {
-                    ei_h.compile_types(templates); 
-                    ei_b.iter_mut().for_each(|i| i.compile_types(templates));                   
+                    ei_h.map_types(f); 
+                    ei_b.iter_mut().for_each(|i| i.map_types(f));                   
                 });
 
                 if let Some(eb_inner) = eb {
-                    eb_inner.iter_mut().for_each(|i| i.compile_types(templates));
+                    eb_inner.iter_mut().for_each(|i| i.map_types(f));
                 }
             }
 
             NessaExpr::Lambda(_, _, a, r, b) => {
-                a.iter_mut().for_each(|(_, t)| {
-                    t.compile_templates(templates);
-                });
+                a.iter_mut().for_each(|(_, t)| f(t));
 
-                r.compile_templates(templates);
+                f(r);
 
-                b.iter_mut().for_each(|i| i.compile_types(templates));
+                b.iter_mut().for_each(|i| i.map_types(f));
             }
             
             NessaExpr::While(_, c, b) |
             NessaExpr::For(_, _, c, b) => {
-                c.compile_types(templates);
-                b.iter_mut().for_each(|i| i.compile_types(templates));
+                c.map_types(f);
+                b.iter_mut().for_each(|i| i.map_types(f));
             },
 
-            NessaExpr::Return(_, e) => e.compile_types(templates),
+            NessaExpr::Return(_, e) |
+            NessaExpr::Propagate(_, e) |
+            NessaExpr::Interpolation(_, e) |
+            NessaExpr::NamedArgument(_, _, e) => e.map_types(f),
+
+            NessaExpr::Try(_, b, _, t, h) => {
+                b.iter_mut().for_each(|i| i.map_types(f));
+                f(t);
+                h.iter_mut().for_each(|i| i.map_types(f));
+            }
+
+            NessaExpr::Match(_, e, arms) => {
+                e.map_types(f);
+
+                for (p, g, b) in arms {
+                    p.types_mut().into_iter().for_each(&mut *f);
+
+                    if let Some(g_inner) = g {
+                        g_inner.map_types(f);
+                    }
+
+                    b.map_types(f);
+                }
+            }
 
             _ => {}
         }
     }
 }
 
+impl MatchPattern {
+    pub fn compile_types(&mut self, templates: &Vec<String>) {
+        self.types_mut().into_iter().for_each(|t| t.compile_templates(templates));
+    }
+
+    pub fn sub_templates(&mut self, templates: &HashMap<usize, Type>) {
+        self.types_mut().into_iter().for_each(|t| *t = t.sub_templates(templates));
+    }
+
+    // Types that appear in the pattern
+    pub fn types(&self) -> Vec<&Type> {
+        match self {
+            MatchPattern::Wildcard |
+            MatchPattern::Literal(_) => vec!(),
+
+            MatchPattern::Type(t) => vec!(t),
+
+            MatchPattern::Class(t, p) => {
+                let mut res = vec!(t);
+                res.extend(p.iter().flat_map(MatchPattern::types));
+                res
+            }
+
+            MatchPattern::Tuple(p) => p.iter().flat_map(MatchPattern::types).collect(),
+
+            MatchPattern::Binding(_, p) => p.types(),
+
+            MatchPattern::CompiledBinding(_, _, t, p) => {
+                let mut res = vec!(t);
+                res.extend(p.types());
+                res
+            }
+        }
+    }
+
+    pub fn types_mut(&mut self) -> Vec<&mut Type> {
+        match self {
+            MatchPattern::Wildcard |
+            MatchPattern::Literal(_) => vec!(),
+
+            MatchPattern::Type(t) => vec!(t),
+
+            MatchPattern::Class(t, p) => {
+                let mut res = vec!(t);
+                res.extend(p.iter_mut().flat_map(MatchPattern::types_mut));
+                res
+            }
+
+            MatchPattern::Tuple(p) => p.iter_mut().flat_map(MatchPattern::types_mut).collect(),
+
+            MatchPattern::Binding(_, p) => p.types_mut(),
+
+            MatchPattern::CompiledBinding(_, _, t, p) => {
+                let mut res = vec!(t);
+                res.extend(p.types_mut());
+                res
+            }
+        }
+    }
+
+    pub fn offset_bindings(&mut self, offset: usize) {
+        match self {
+            MatchPattern::Wildcard |
+            MatchPattern::Literal(_) |
+            MatchPattern::Type(_) => {},
+
+            MatchPattern::Class(_, p) |
+            MatchPattern::Tuple(p) => p.iter_mut().for_each(|i| i.offset_bindings(offset)),
+
+            MatchPattern::Binding(_, p) => p.offset_bindings(offset),
+
+            MatchPattern::CompiledBinding(id, _, _, p) => {
+                *id += offset;
+                p.offset_bindings(offset);
+            }
+        }
+    }
+
+    // Names and registers of the variables bound by the pattern, in order
+    pub fn bindings(&self) -> Vec<(usize, &String, &Type)> {
+        match self {
+            MatchPattern::Wildcard |
+            MatchPattern::Literal(_) |
+            MatchPattern::Type(_) |
+            MatchPattern::Binding(_, _) => vec!(),
+
+            MatchPattern::Class(_, p) |
+            MatchPattern::Tuple(p) => p.iter().flat_map(MatchPattern::bindings).collect(),
+
+            MatchPattern::CompiledBinding(id, n, t, p) => {
+                let mut res = vec!((*id, n, t));
+                res.extend(p.bindings());
+                res
+            }
+        }
+    }
+}
+
 impl NessaContext {
     // Parser combinator that allows to store the precise location of an element
     pub fn located<'a, O, P1: FnMut(Span<'a>) -> PResult<'a, O>>(&'a self, mut parser: P1) -> impl FnMut(Span<'a>) -> PResult<'a, (Location, O)> {
@@ -609,7 +976,7 @@ impl NessaContext {
     
     pub fn get_type_id(&self, name: String) -> Result<usize, String> {
         return self.cache.class_id.get(name, |name| {
-            self.type_templates.iter().find(|t| t.name == name).map(|i| i.id).ok_or(format!("No type with name {}", name))
+            self.type_templates.iter().rfind(|t| t.name == name).map(|i| i.id).ok_or(format!("No type with name {}", name))
         });
     }
     
@@ -672,36 +1039,62 @@ impl NessaContext {
         map_res(identifier_parser, |n| self.get_interface_id(n))(input)
     }
 
+    fn interface_constraints_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, Vec<InterfaceConstraint>> {
+        return map(
+            tuple((
+                tag("["),
+                empty0,
+                separated_list1(
+                    tuple((empty0, tag(","), empty0)), 
+                    tuple((
+                        context("Invalid interface name", cut(|input| self.interface_parser(input))),
+                        opt(delimited(
+                            tuple((tag("<"), empty0)),
+                            separated_list1(
+                                tuple((empty0, tag(","), empty0)), 
+                                |input| self.type_parser(input)
+                            ),
+                            tuple((empty0, tag(">"))),
+                        ))
+                    ))
+                ),
+                empty0,
+                tag("]"),
+            )),
+            |(_, _, v, _, _)| v.into_iter().map(|(id, t)| InterfaceConstraint::new(id, t.unwrap_or_default())).collect()
+        )(input);
+    }
+
     fn template_type_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, Type> {
         return map(
             tuple((
                 tag("'"),
                 context("Invalid template identifier", cut(identifier_parser)),
-                opt(tuple((
-                    empty0,
-                    tag("["),
+                opt(preceded(
                     empty0,
+                    |input| self.interface_constraints_parser(input)
+                ))
+            )), 
+            |(_, n, w)| Type::TemplateParamStr(n, w.unwrap_or_default())
+        )(input);
+    }
+
+    fn dyn_type_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, Type> {
+        return map(
+            tuple((
+                tag("dyn"),
+                empty1,
+                context("Invalid interface name", cut(|input| self.interface_parser(input))),
+                opt(delimited(
+                    tuple((empty0, tag("<"), empty0)),
                     separated_list1(
                         tuple((empty0, tag(","), empty0)), 
-                        tuple((
-                            context("Invalid interface name", cut(|input| self.interface_parser(input))),
-                            opt(delimited(
-                                tuple((tag("<"), empty0)),
-                                separated_list1(
-                                    tuple((empty0, tag(","), empty0)), 
-                                    |input| self.type_parser(input)
-                                ),
-                                tuple((empty0, tag(">"))),
-                            ))
-                        ))
+                        |input| self.type_parser(input)
                     ),
-                    empty0,
-                    tag("]"),
-                )))
+                    tuple((empty0, context("Expected '>' at the end of dyn type", cut(tag(">")))))
+                ))
             )), 
-            |(_, n, w)| Type::TemplateParamStr(n, w.map(|(_, _, _, v, _, _)| {
-                v.into_iter().map(|(id, t)| InterfaceConstraint::new(id, t.unwrap_or_default())).collect()
-            }).unwrap_or_default())
+            |(_, _, id, t)| Type::Dyn(InterfaceConstraint::new(id, t.unwrap_or_default()))
         )(input);
     }
 
@@ -791,6 +1184,7 @@ impl NessaContext {
                 |input| self.wildcard_type_parser(input),
                 |input| self.mutable_reference_type_parser(input),
                 |input| self.constant_reference_type_parser(input),
+                |input| self.dyn_type_parser(input),
                 |input| self.parametric_type_parser(input),
                 |input| self.template_type_parser(input),
                 |input| self.basic_type_parser(input)
@@ -804,6 +1198,7 @@ impl NessaContext {
                 |input| self.wildcard_type_parser(input),
                 |input| self.mutable_reference_type_parser(input),
                 |input| self.constant_reference_type_parser(input),
+                |input| self.dyn_type_parser(input),
                 |input| self.parametric_type_parser(input),
                 |input| self.template_type_parser(input),
                 |input| self.basic_type_parser(input)
@@ -817,6 +1212,7 @@ impl NessaContext {
                 |input| self.wildcard_type_parser(input),
                 |input| self.mutable_reference_type_parser(input),
                 |input| self.constant_reference_type_parser(input),
+                |input| self.dyn_type_parser(input),
                 |input| self.parametric_type_parser(input),
                 |input| self.template_type_parser(input),
                 |input| self.basic_type_parser(input)
@@ -829,6 +1225,7 @@ impl NessaContext {
                 |input| self.wildcard_type_parser(input),
                 |input| self.mutable_reference_type_parser(input),
                 |input| self.constant_reference_type_parser(input),
+                |input| self.dyn_type_parser(input),
                 |input| self.parametric_type_parser(input),
                 |input| self.template_type_parser(input),
                 |input| self.basic_type_parser(input)
@@ -974,22 +1371,25 @@ impl NessaContext {
                             return Err(format!("NDL extraction results map does not contain the attribute {}", n));
                         }
 
+                        let parse_basic = |t_id: usize, arg: &String| match self.type_templates[t_id].parser {
+                            Some(parser) => parser(self, &self.type_templates[t_id], arg),
+                            None => Err(format!("Unable to parse attribute {} of type {}", n, t.get_name_plain(self)))
+                        };
+
                         if let Type::Basic(t_id) = t {  
-                            return self.type_templates[*t_id].parser.unwrap()(self, &self.type_templates[*t_id], &args[n][0]);
+                            return parse_basic(*t_id, &args[n][0]);
                         }
 
                         if let Type::Template(ARR_ID, t) = t {  
                             if let &[Type::Basic(t_id)] = &t[..] {
-                                return args[n].iter().cloned()
-                                    .map(|arg| self.type_templates[t_id].parser.unwrap()(self, &self.type_templates[t_id], &arg))
+                                return args[n].iter()
+                                    .map(|arg| parse_basic(t_id, arg))
                                     .collect::<Result<Vec<_>, _>>()
                                     .map(|r| Object::arr(r, Type::Basic(t_id)));
                             }
-
-                            unimplemented!();
                         }
 
-                        unimplemented!();
+                        Err(format!("Unable to parse attribute {} of type {}", n, t.get_name_plain(self)))
 
                     }).collect::<Result<Vec<Object>, String>>()?
                 }))
@@ -1016,23 +1416,78 @@ impl NessaContext {
     }
     
     fn literal_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        return alt((
+            |input| self.interpolated_string_parser(input, cache),
+            map(
+                self.located(
+                    alt((
+                        |input| self.custom_literal_parser(input, cache),
+                        map(|input| self.bool_parser(input), Object::new),
+                        map(|input| self.float_parser(input), Object::new),
+                        map(|input| self.binary_integer_parser(input), Object::new),
+                        map(|input| self.hex_integer_parser(input), Object::new),
+                        map(|input| self.integer_parser(input), Object::new),
+                        map(|input| self.char_parser(input), Object::new),
+                        map(string_parser, Object::new),
+                    ))
+                ),
+                |(l, o)| NessaExpr::Literal(l, o)
+            )
+        ))(input);
+    }
+    
+    // Interpolated strings are desugared into a concatenation of their parts, where every embedded expression is converted using to_string when compiled
+    fn interpolated_string_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
         return map(
             self.located(
-                alt((
-                    |input| self.custom_literal_parser(input, cache),
-                    map(|input| self.bool_parser(input), Object::new),
-                    map(|input| self.float_parser(input), Object::new),
-                    map(|input| self.binary_integer_parser(input), Object::new),
-                    map(|input| self.hex_integer_parser(input), Object::new),
-                    map(|input| self.integer_parser(input), Object::new),
-                    map(|input| self.char_parser(input), Object::new),
-                    map(string_parser, Object::new),
-                ))
+                delimited(
+                    tag("$\""),
+                    many0(
+                        alt((
+                            map(
+                                self.located(
+                                    fold_many1(
+                                        alt((
+                                            value('{', tag("{{")),
+                                            value('}', tag("}}")),
+                                            preceded(
+                                                tag("\\"),
+                                                cut(alt((
+                                                    value('\n', tag("n")),
+                                                    value('\t', tag("t")),
+                                                    value('"', tag("\"")),
+                                                    value('\\', tag("\\"))
+                                                )))
+                                            ),
+                                            satisfy(|i| i != '"' && i != '\\' && i != '{' && i != '}')
+                                        )),
+                                        String::new,
+                                        |mut s, c| { s.push(c); s }
+                                    )
+                                ),
+                                |(l, s)| NessaExpr::Literal(l, Object::new(s))
+                            ),
+                            map(
+                                delimited(
+                                    tuple((tag("{"), empty0)),
+                                    cut(self.located(|input| self.nessa_expr_parser(input, cache))),
+                                    cut(tuple((empty0, tag("}"))))
+                                ),
+                                |(l, e)| NessaExpr::Interpolation(l, Box::new(e))
+                            )
+                        ))
+                    ),
+                    cut(tag("\""))
+                )
             ),
-            |(l, o)| NessaExpr::Literal(l, o)
+            |(l, parts)| {
+                parts.into_iter()
+                     .reduce(|a, b| NessaExpr::BinaryOperation(l.clone(), ADD_BINOP_ID, vec!(), Box::new(a), Box::new(b)))
+                     .unwrap_or_else(|| NessaExpr::Literal(l, Object::new(String::new())))
+            }
         )(input);
     }
-    
+
     fn custom_syntax_parser<'a>(&'a self, mut input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
         for m in self.macros.iter().filter(|i| i.m_type != NessaMacroType::Block) {
             if let Ok((new_input, args)) = m.pattern.extract(input, self, cache) {
@@ -1321,7 +1776,7 @@ impl NessaContext {
                     empty0,
                     separated_list0(
                         tuple((empty0, tag(","), empty0)),
-                        |input| self.nessa_expr_parser_wrapper(input, &mut FxHashSet::default(), cache)
+                        |input| self.nary_argument_parser(input, id, cache)
                     ),
                     empty0,
                     opt(tuple((tag(","), empty0))),
@@ -1332,13 +1787,85 @@ impl NessaContext {
         )(input)
     }
 
+    // Function calls can also receive arguments by the name of their parameters
+    fn nary_argument_parser<'a>(&'a self, input: Span<'a>, id: usize, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        if id == CALL_OP {
+            let named = map(
+                self.located(
+                    tuple((
+                        identifier_parser,
+                        empty0,
+                        tag(":"),
+                        empty0,
+                        |input| self.nessa_expr_parser_wrapper(input, &mut FxHashSet::default(), cache)
+                    ))
+                ),
+                |(l, (n, _, _, _, e))| NessaExpr::NamedArgument(l, n, Box::new(e))
+            )(input);
+
+            if named.is_ok() {
+                return named;
+            }
+        }
+
+        self.nessa_expr_parser_wrapper(input, &mut FxHashSet::default(), cache)
+    }
+
+    fn propagation_operation_parser<'a>(&'a self, input: Span<'a>, checked_precs: &mut FxHashSet<usize>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        if let Some(res) = cache.borrow().get(input.len(), PROPAGATE_PREC) {
+            return res.clone();
+        }
+
+        let mut checked_cpy = checked_precs.clone();
+        checked_cpy.insert(PROPAGATE_PREC);
+
+        // Parsed without combinators to keep the stack small on deeply nested expressions
+        let res = self.nessa_expr_parser_wrapper(input, &mut checked_cpy, cache).and_then(|(rest, e)| {
+            // No spaces are allowed before the ? so it does not clash with syntaxes such as ternary macros
+            let (rest, _) = tag("?")(rest)?;
+
+            let line = input.location_line() as usize;
+            let column = input.get_column();
+            let span = &input[..(input.len() - rest.len())];
+
+            Ok((rest, NessaExpr::Propagate(Location::new(line, column, span.to_string(), self.module_name.clone()), Box::new(e))))
+        });
+
+        if res.is_ok() {
+            cache.borrow_mut().set(input.len(), PROPAGATE_PREC, res.clone());
+        }
+
+        res
+    }
+
     fn operation_parser<'a>(&'a self, input: Span<'a>, checked_precs: &mut FxHashSet<usize>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        // The ? operator is only tried if it can appear in the rest of the input and it is not user-defined
+        let mut propagation_checked = checked_precs.contains(&PROPAGATE_PREC) || 
+                                      !input.fragment().contains('?') ||
+                                      self.unary_ops.iter().any(|o| matches!(o, Operator::Unary { representation, prefix: false, .. } if representation == "?"));
+
         for o in self.sorted_ops.iter().rev() {
+            // The ? operator is tried in precedence order along with the defined ones
+            if !propagation_checked && o.get_precedence() < PROPAGATE_PREC {
+                propagation_checked = true;
+
+                let res = self.propagation_operation_parser(input, checked_precs, cache);
+
+                if res.is_ok() {
+                    return res;
+                }
+            }
+
             // Skip already checked precedences
             if checked_precs.contains(&o.get_precedence()) {
                 continue;
             }
 
+            // Compound assignments are parsed as statements
+            if matches!(o, Operator::Binary { id, .. } if compound_assignment_base(*id).is_some()) {
+                continue;
+            }
+
             if let Some(res) = cache.borrow().get(input.len(), o.get_precedence()) {
                 return res.clone();
             } 
@@ -1370,6 +1897,14 @@ impl NessaContext {
             }
         }
 
+        if !propagation_checked {
+            let res = self.propagation_operation_parser(input, checked_precs, cache);
+
+            if res.is_ok() {
+                return res;
+            }
+        }
+
         cache.borrow_mut().set(input.len(), 0, Err(verbose_error(input, "Unable to parse")));
 
         return Err(verbose_error(input, "Unable to parse"));
@@ -1402,6 +1937,25 @@ impl NessaContext {
         )(input);
     }
     
+    fn destructuring_definition_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        return map(
+            self.located(
+                tuple((
+                    tag("let"),
+                    empty0,
+                    |input| self.destructuring_pattern_parser(input),
+                    empty0,
+                    tag("="),
+                    empty0,
+                    context("Invalid right handside on destructuring definition", cut(|input| self.nessa_expr_parser(input, cache))),
+                    empty0,
+                    context("Expected ';' at the end of destructuring definition", cut(tag(";")))
+                ))
+            ),
+            |(l, (_, _, p, _, _, _, e, _, _))| NessaExpr::DestructuringDefinition(l, p, Box::new(e))
+        )(input);
+    }
+    
     fn variable_assignment_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
         return map(
             self.located(
@@ -1419,6 +1973,37 @@ impl NessaContext {
         )(input);
     }
     
+    fn compound_assignment_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        let compound_ops = self.binary_ops.iter().filter(|o| compound_assignment_base(o.get_id()).is_some()).collect::<Vec<_>>();
+
+        let res = map(
+            self.located(
+                tuple((
+                    |input| self.nessa_expr_parser(input, cache),
+                    empty0,
+                    |input| {
+                        for o in &compound_ops {
+                            let res = map(tag(o.get_repr().as_str()), |_| o.get_id())(input);
+
+                            if res.is_ok() {
+                                return res;
+                            }
+                        }
+
+                        Err(verbose_error(input, "Unable to parse"))
+                    },
+                    empty0,
+                    context("Invalid right handside on compound assignment", cut(|input| self.nessa_expr_parser(input, cache))),
+                    empty0,
+                    context("Expected ';' at the end of compound assignment", cut(tag(";")))
+                ))
+            ),
+            |(l, (a, _, id, _, b, _, _))| NessaExpr::CompoundAssignment(l, id, Box::new(a), Box::new(b))
+        )(input);
+
+        res
+    }
+    
     fn return_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
         return map(
             self.located(
@@ -1579,13 +2164,16 @@ impl NessaContext {
         )(input);
     }
     
-    fn for_header_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, (String, NessaExpr)> {
+    fn for_header_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, (MatchPattern, NessaExpr)> {
         return map(
             tuple((
                 tag("for"),
                 empty1,
-                context("Invalid for iterator identifier", cut(identifier_parser)),
-                empty1,
+                context("Invalid for iterator identifier", cut(alt((
+                    |input| self.destructuring_pattern_parser(input),
+                    map(identifier_parser, |n| MatchPattern::Binding(n, Box::new(MatchPattern::Wildcard)))
+                )))),
+                empty0,
                 context("Expected 'in' after for iterator identifier", cut(tag("in"))),
                 empty1,
                 cut(|input| self.nessa_expr_parser(input, cache))
@@ -1616,11 +2204,53 @@ impl NessaContext {
                     cut(|input| self.code_block_parser(input, cache)),
                 ))
             ),
-            |(l, ((n, c), _, b))| NessaExpr::For(l, n, Box::new(c), b)
+            |(l, ((p, c), _, mut b))| match p {
+                MatchPattern::Binding(n, _) => NessaExpr::For(l, n, Box::new(c), b),
+
+                // Destructured elements are bound at the start of each iteration
+                _ => {
+                    let element = "__element__".to_string();
+                    b.insert(0, NessaExpr::DestructuringDefinition(l.clone(), p, Box::new(NessaExpr::NameReference(l.clone(), element.clone()))));
+
+                    NessaExpr::For(l, element, Box::new(c), b)
+                }
+            }
         )(input);
     }
 
-    fn function_header_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, FunctionHeader> {
+    fn try_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        return map(
+            self.located(
+                tuple((
+                    tag("try"),
+                    empty0,
+                    |input| self.code_block_parser(input, cache),
+                    empty0,
+                    context("Expected 'catch' after try block", cut(tag("catch"))),
+                    empty0,
+                    context("Expected '(' after 'catch'", cut(tag("("))),
+                    empty0,
+                    context("Invalid catch variable identifier", cut(identifier_parser)),
+                    empty0,
+                    context("Expected ':' after catch variable identifier", cut(tag(":"))),
+                    empty0,
+                    context("Invalid catch variable type", cut(|input| self.type_parser(input))),
+                    empty0,
+                    context("Expected ')' after catch variable type", cut(tag(")"))),
+                    empty0,
+                    cut(|input| self.code_block_parser(input, cache))
+                ))
+            ),
+            |(l, (_, _, b, _, _, _, _, _, n, _, _, _, t, _, _, _, h))| NessaExpr::Try(l, b, n, t, h)
+        )(input);
+    }
+
+    fn function_header_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, FunctionHeader> {
+        return map(|input| self.function_header_params_parser(input, cache), |(h, _)| h)(input);
+    }
+
+    // Function header along with the default values of its parameters, the hidden names and patterns of the destructured ones and whether the last one is variadic
+    fn function_header_params_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, (FunctionHeader, FunctionParams)> {
         return map(
             tuple((
                 tag("fn"),
@@ -1645,27 +2275,58 @@ impl NessaContext {
                 empty0,
                 context("Expected '(' after function name in function definition", cut(tag("("))),
                 empty0,
-                separated_list0(
+                context("Only the last parameter can be variadic and it cannot have a default value", cut(verify(separated_list0(
                     tuple((empty0, tag(","), empty0)), 
-                    tuple((
-                        identifier_parser,
+                    tuple((alt((
                         map(
-                            opt(
+                            tuple((
+                                |input| self.destructuring_pattern_parser(input),
+                                empty0,
+                                tag(":"),
+                                empty0,
+                                context("Expected type for destructured parameter", cut(|input| self.type_parser(input))),
+                                empty0
+                            )),
+                            |(p, _, _, _, t, _)| (String::new(), t, Some(p), false)
+                        ),
+                        map(
+                            tuple((
+                                identifier_parser,
                                 map(
-                                    tuple((
-                                        empty0,
-                                        tag(":"),
-                                        empty0,
-                                        cut(|input| self.type_parser(input)),
-                                        empty0
-                                    )),
-                                    |(_, _, _, t, _)| t
+                                    opt(
+                                        map(
+                                            tuple((
+                                                empty0,
+                                                tag(":"),
+                                                empty0,
+                                                opt(terminated(tag("..."), empty0)),
+                                                cut(|input| self.type_parser(input)),
+                                                empty0
+                                            )),
+                                            |(_, _, _, v, t, _)| (t, v.is_some())
+                                        )
+                                    ),
+                                    |t| t.unwrap_or((Type::Wildcard, false))
                                 )
-                            ),
-                            |t| t.unwrap_or(Type::Wildcard)
+                            )),
+                            // Variadic parameters collect the rest of the arguments in an array
+                            |(n, (t, v))| (n, if v { ARR_OF!(t) } else { t }, None, v)
                         )
-                    ))
-                ),
+                    )),
+                    opt(
+                        preceded(
+                            tuple((empty0, tag("="), empty0)),
+                            context(
+                                "Invalid default value for parameter", 
+                                cut(map(
+                                    self.located(|input| self.nessa_expr_parser(input, cache)),
+                                    // Kept as a parenthesized expression so its location covers all of the written value
+                                    |(l, e)| NessaExpr::Tuple(l, vec!(e))
+                                ))
+                            )
+                        )
+                    ))),
+                ), |a: &Vec<_>| a.iter().enumerate().all(|(i, ((_, _, _, v), d))| !v || (i == a.len() - 1 && d.is_none()))))),
                 empty0,
                 opt(tuple((tag(","), empty0))),
                 context("Expected ')' after parameters in function definition", cut(tag(")"))),
@@ -1676,7 +2337,29 @@ impl NessaContext {
                     )
                 )
             )),
-            |(_, t, _, n, _, _, _, a, _, _, _, r)| (n, t, a, r.unwrap_or(Type::Empty))
+            |(_, t, _, n, _, _, _, a, _, _, _, r)| {
+                let mut args = vec!();
+                let mut defaults = vec!();
+                let mut patterns = vec!();
+                let variadic = a.last().map(|((_, _, _, v), _)| *v).unwrap_or(false);
+
+                for (i, ((an, at, p, _), d)) in a.into_iter().enumerate() {
+                    defaults.push(d);
+
+                    match p {
+                        Some(p) => {
+                            let hidden = format!("__arg_{}__", i);
+
+                            patterns.push((hidden.clone(), p));
+                            args.push((hidden, at));
+                        }
+
+                        None => args.push((an, at))
+                    }
+                }
+
+                ((n, t, args, r.unwrap_or(Type::Empty)), (defaults, patterns, variadic))
+            }
         )(input);
     }
 
@@ -1691,19 +2374,25 @@ impl NessaContext {
                         ),
                         empty0
                     ),
-                    |input| self.function_header_parser(input),
+                    |input| self.function_header_params_parser(input, cache),
                     empty0,
                     cut(|input| self.code_block_parser(input, cache)),
                 ))
             ),
-            |(l, (an, (n, t, mut a, mut r), _, mut b))| {
+            |(l, (an, ((n, t, mut a, mut r), (mut d, p, v)), _, mut b))| {
                 let u_t = t.unwrap_or_default();
 
+                // Destructured parameters are bound at the start of the body
+                for (hidden, pattern) in p.into_iter().rev() {
+                    b.insert(0, NessaExpr::DestructuringDefinition(l.clone(), pattern, Box::new(NessaExpr::NameReference(l.clone(), hidden))));
+                }
+
                 a.iter_mut().for_each(|(_, i)| i.compile_templates(&u_t));
+                d.iter_mut().flatten().for_each(|e| e.compile_types(&u_t));
                 r.compile_templates(&u_t);
                 b.iter_mut().for_each(|e| e.compile_types(&u_t));
 
-                NessaExpr::FunctionDefinition(l, an, self.get_function_id(n).unwrap(), u_t, a, r, b)
+                NessaExpr::FunctionDefinition(l, an, self.get_function_id(n).unwrap(), u_t, a, d, v, r, b)
             }
         )(input);
     }
@@ -2417,7 +3106,7 @@ impl NessaContext {
 
                 t.compile_templates(&u_t);
 
-                NessaExpr::ClassDefinition(l, vec!(), n, u_t, vec!(), Some(t), vec!())
+                NessaExpr::ClassDefinition(l, vec!(), n, u_t, vec!(), Some(t), vec!(), false)
             }
         )(input);
     }
@@ -2524,7 +3213,162 @@ impl NessaContext {
 
                 f.iter_mut().for_each(|(_, tp)| tp.compile_templates(&u_t));
 
-                NessaExpr::ClassDefinition(l, an, n, u_t, f, None, p)
+                NessaExpr::ClassDefinition(l, an, n, u_t, f, None, p, false)
+            }
+        )(input);
+    }
+
+    fn enum_name_definition_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, Vec<String>> {
+        map(
+            tuple((
+                tag("enum"),
+                empty1,
+                context("Invalid enum identifier", cut(identifier_parser)),
+                empty0,
+                opt(
+                    tuple((
+                        tag("<"),
+                        empty0,
+                        separated_list1(
+                            tuple((empty0, tag(","), empty0)), 
+                            identifier_parser
+                        ),
+                        empty0,
+                        tag(">"),
+                        empty0,
+                    ))
+                ),
+                tag("{"),
+                empty0,
+                separated_list1(
+                    tuple((empty0, tag(","), empty0)),
+                    terminated(identifier_parser, tuple((empty0, opt(skip_parenthesized))))
+                )
+            )),
+            |(_, _, n, _, _, _, _, mut v)| {
+                v.push(n);
+                v
+            }
+        )(input)
+    }
+
+    fn enum_variant_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, EnumVariant> {
+        map(
+            self.located(
+                tuple((
+                    identifier_parser,
+                    empty0,
+                    opt(
+                        delimited(
+                            tuple((tag("("), empty0)),
+                            separated_list0(
+                                tuple((empty0, tag(","), empty0)),
+                                map(
+                                    tuple((
+                                        identifier_parser,
+                                        empty0,
+                                        context("Expected ':' after enum variant field name", cut(tag(":"))),
+                                        empty0,
+                                        cut(|input| self.type_parser(input))
+                                    )),
+                                    |(n, _, _, _, t)| (n, t)
+                                )
+                            ),
+                            tuple((empty0, opt(tuple((tag(","), empty0))), context("Expected ')' at the end of enum variant fields", cut(tag(")")))))
+                        )
+                    )
+                ))
+            ),
+            |(l, (n, _, f))| (l, n, f.unwrap_or_default())
+        )(input)
+    }
+
+    fn enum_definition_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, Vec<NessaExpr>> {
+        return map_res(
+            self.located(
+                tuple((
+                    terminated(
+                        separated_list0(
+                            empty0, 
+                            parse_annotation
+                        ),
+                        empty0
+                    ),
+                    tag("enum"),
+                    empty1,
+                    context("Invalid enum identifier", cut(identifier_parser)),
+                    empty0,
+                    opt(
+                        map(
+                            tuple((
+                                tag("<"),
+                                empty0,
+                                separated_list1(
+                                    tuple((empty0, tag(","), empty0)), 
+                                    identifier_parser
+                                ),
+                                empty0,
+                                tag(">"),
+                                empty0,
+                            )),
+                            |(_, _, t, _, _, _)| t
+                        )
+                    ),
+                    context("Expected '{' after enum name", cut(tag("{"))),
+                    empty0,
+                    context(
+                        "Expected at least one enum variant",
+                        cut(separated_list1(
+                            tuple((empty0, tag(","), empty0)),
+                            |input| self.enum_variant_parser(input)
+                        ))
+                    ),
+                    empty0,
+                    opt(tuple((tag(","), empty0))),
+                    context("Expected '}' at the end of enum definition", cut(tag("}")))
+                ))
+            ),
+            |(l, (an, _, _, n, _, t, _, _, v, _, _, _))| {
+                let u_t = t.unwrap_or_default();
+
+                let mut res = vec!();
+                let mut variants = vec!();
+
+                // Each variant is a class that only takes the template parameters its fields use
+                for (vl, vn, mut f) in v {
+                    let mut used = HashSet::new();
+
+                    for (_, tp) in &f {
+                        let mut compiled = tp.clone();
+                        compiled.compile_templates(&u_t);
+                        compiled.template_dependencies(&mut used);
+                    }
+
+                    let mut used = used.into_iter().collect::<Vec<_>>();
+                    used.sort();
+
+                    let v_t = used.iter().map(|i| u_t[*i].clone()).collect::<Vec<_>>();
+
+                    f.iter_mut().for_each(|(_, tp)| tp.compile_templates(&v_t));
+
+                    let id = self.get_type_id(vn.clone())?;
+
+                    variants.push(if used.is_empty() { 
+                        Type::Basic(id) 
+                    
+                    } else { 
+                        Type::Template(id, used.iter().map(|i| Type::TemplateParam(*i, vec!())).collect()) 
+                    });
+
+                    res.push(NessaExpr::ClassDefinition(vl, vec!(), vn, v_t, f, None, vec!(), false));
+                }
+
+                let alias = if variants.len() == 1 { variants.pop().unwrap() } else { Type::Or(variants) };
+
+                // The enum itself is an alias whose derived operations are generated when it is defined
+                res.push(NessaExpr::ClassDefinition(l, an, n, u_t, vec!(), Some(alias), vec!(), true));
+
+                Result::<_, String>::Ok(res)
             }
         )(input);
     }
@@ -2552,13 +3396,14 @@ impl NessaContext {
                         |(_, _, t, _, _, _)| t
                     )
                 ),
+                opt(tuple((tag("["), is_not("{"), empty0))),
                 tag("{")
             )),
-            |(_, _, n, _, _, _)| n
+            |(_, _, n, _, _, _, _)| n
         )(input)
     }
 
-    fn interface_definition_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, NessaExpr> {
+    fn interface_definition_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
         return map(
             self.located(
                 tuple((
@@ -2589,11 +3434,15 @@ impl NessaContext {
                             |(_, _, t, _, _, _)| t
                         )
                     ),
+                    opt(terminated(
+                        |input| self.interface_constraints_parser(input),
+                        empty0
+                    )),
                     tag("{"),
                     empty0,
                     separated_list0(
                         empty0,
-                        delimited(
+                        preceded(
                             empty0, 
                             alt((
                                 map(
@@ -2605,72 +3454,89 @@ impl NessaContext {
                                             ),
                                             empty0
                                         ),
-                                        |input| self.function_header_parser(input),
-                                    )),
-                                    |(an, (a, b, c, d))| InterfaceHeader::FunctionHeader(an, a, b, c, d)
-                                ),
-                                map(
-                                    tuple((
-                                        terminated(
-                                            separated_list0(
-                                                empty0, 
-                                                parse_annotation
-                                            ),
-                                            empty0
-                                        ),
-                                        |input| self.prefix_operation_header_definition_parser(input),
+                                        |input| self.function_header_parser(input, cache),
+                                        empty0,
+                                        context(
+                                            "Expected ';' or a default body at the end of interface function signature", 
+                                            cut(alt((
+                                                map(tag(";"), |_| None),
+                                                map(|input| self.code_block_parser(input, cache), Some)
+                                            )))
+                                        )
                                     )),
-                                    |(an, (a, b, c, d, e))| InterfaceHeader::UnaryOpHeader(an, a, b, c, d, e)
+                                    |(an, (a, b, c, d), _, body)| InterfaceHeader::FunctionHeader(an, a, b, c, d, body)
                                 ),
-                                map(
-                                    tuple((
-                                        terminated(
-                                            separated_list0(
-                                                empty0, 
-                                                parse_annotation
-                                            ),
-                                            empty0
+                                terminated(
+                                    alt((
+                                        map(
+                                            tuple((
+                                                terminated(
+                                                    separated_list0(
+                                                        empty0, 
+                                                        parse_annotation
+                                                    ),
+                                                    empty0
+                                                ),
+                                                |input| self.prefix_operation_header_definition_parser(input),
+                                            )),
+                                            |(an, (a, b, c, d, e))| InterfaceHeader::UnaryOpHeader(an, a, b, c, d, e)
                                         ),
-                                        |input| self.postfix_operation_header_definition_parser(input),
-                                    )),
-                                    |(an, (a, b, c, d, e))| InterfaceHeader::UnaryOpHeader(an, a, b, c, d, e)
-                                ),
-                                map(
-                                    tuple((
-                                        terminated(
-                                            separated_list0(
-                                                empty0, 
-                                                parse_annotation
-                                            ),
-                                            empty0
+                                        map(
+                                            tuple((
+                                                terminated(
+                                                    separated_list0(
+                                                        empty0, 
+                                                        parse_annotation
+                                                    ),
+                                                    empty0
+                                                ),
+                                                |input| self.postfix_operation_header_definition_parser(input),
+                                            )),
+                                            |(an, (a, b, c, d, e))| InterfaceHeader::UnaryOpHeader(an, a, b, c, d, e)
                                         ),
-                                        |input| self.binary_operation_header_definition_parser(input),
-                                    )),
-                                    |(an, (a, b, c, d, e))| InterfaceHeader::BinaryOpHeader(an, a, b, c, d, e)
-                                ),
-                                map(
-                                    tuple((
-                                        terminated(
-                                            separated_list0(
-                                                empty0, 
-                                                parse_annotation
-                                            ),
-                                            empty0
+                                        map(
+                                            tuple((
+                                                terminated(
+                                                    separated_list0(
+                                                        empty0, 
+                                                        parse_annotation
+                                                    ),
+                                                    empty0
+                                                ),
+                                                |input| self.binary_operation_header_definition_parser(input),
+                                            )),
+                                            |(an, (a, b, c, d, e))| InterfaceHeader::BinaryOpHeader(an, a, b, c, d, e)
                                         ),
-                                        |input| self.nary_operation_header_definition_parser(input),
+                                        map(
+                                            tuple((
+                                                terminated(
+                                                    separated_list0(
+                                                        empty0, 
+                                                        parse_annotation
+                                                    ),
+                                                    empty0
+                                                ),
+                                                |input| self.nary_operation_header_definition_parser(input),
+                                            )),
+                                            |(an, (a, b, c, d, e))| InterfaceHeader::NaryOpHeader(an, a, b, c, d, e)
+                                        )
                                     )),
-                                    |(an, (a, b, c, d, e))| InterfaceHeader::NaryOpHeader(an, a, b, c, d, e)
+                                    context("Expected ';' at the end of interface operation signature", cut(tag(";")))
                                 )
-                            )),
-                            context("Expected ';' at the end of interface function signature", cut(tag(";")))
+                            ))
                         )
                     ),
                     empty0,
                     tag("}")
                 ))
             ),
-            |(l, (an, _, _, n, _, t, _, _, p, _, _))| {
+            |(l, (an, _, _, n, _, t, prt, _, _, p, _, _))| {
                 let u_t = t.unwrap_or_default();
+                let mut parents = prt.unwrap_or_default();
+
+                parents.iter_mut().for_each(|c| {
+                    c.args.iter_mut().for_each(|tp| tp.compile_templates(&u_t));
+                });
 
                 let mut fns: Vec<AnnotFunctionHeader> = vec!();
                 let mut unary: Vec<AnnotUnaryOpHeader> = vec!();
@@ -2679,7 +3545,7 @@ impl NessaContext {
 
                 p.into_iter().for_each(|h| {
                     match h {
-                        InterfaceHeader::FunctionHeader(an, n, tm, mut args, mut ret) => {
+                        InterfaceHeader::FunctionHeader(an, n, tm, mut args, mut ret, mut body) => {
                             let u_tm = tm.clone().unwrap_or_default();
                             let all_tm = u_t.iter().cloned().chain(u_tm).collect::<Vec<_>>();
         
@@ -2689,7 +3555,11 @@ impl NessaContext {
         
                             ret.compile_templates(&all_tm);
 
-                            fns.push((an, n, tm, args, ret));
+                            if let Some(b) = &mut body {
+                                b.iter_mut().for_each(|e| e.compile_types(&all_tm));
+                            }
+
+                            fns.push((an, n, tm, args, ret, body));
                         },
 
                         InterfaceHeader::UnaryOpHeader(an, id, tm, a, mut at, mut ret) => {
@@ -2730,7 +3600,7 @@ impl NessaContext {
                     }
                 });
 
-                NessaExpr::InterfaceDefinition(l, an, n, u_t, fns, unary, binary, nary)
+                NessaExpr::InterfaceDefinition(l, an, n, u_t, parents, fns, unary, binary, nary)
             }
         )(input);
     }
@@ -2829,6 +3699,148 @@ impl NessaContext {
         )(input)
     }
 
+    fn match_pattern_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, MatchPattern> {
+        let patterns = |input| separated_list1(
+            tuple((empty0, tag(","), empty0)),
+            |input| self.match_pattern_parser(input, cache)
+        )(input);
+
+        return alt((
+            map_opt(identifier_parser, |n| (n == "_").then_some(MatchPattern::Wildcard)),
+            map(
+                tuple((
+                    identifier_parser,
+                    empty0,
+                    tag(":"),
+                    empty0,
+                    context("Invalid pattern type", cut(|input| self.type_parser_wrapper(input, false, true)))
+                )),
+                |(n, _, _, _, t)| MatchPattern::Binding(n, Box::new(MatchPattern::Type(t)))
+            ),
+            map(
+                tuple((
+                    |input| self.type_parser_wrapper(input, false, true),
+                    empty0,
+                    tag("("),
+                    empty0,
+                    patterns,
+                    empty0,
+                    opt(tuple((tag(","), empty0))),
+                    tag(")")
+                )),
+                |(t, _, _, _, p, _, _, _)| MatchPattern::Class(t, p)
+            ),
+            map(
+                tuple((
+                    tag("("),
+                    empty0,
+                    patterns,
+                    empty0,
+                    opt(tuple((tag(","), empty0))),
+                    tag(")")
+                )),
+                |(_, _, mut p, _, _, _)| if p.len() == 1 { p.pop().unwrap() } else { MatchPattern::Tuple(p) }
+            ),
+            map_opt(
+                |input| self.literal_parser(input, cache),
+                |e| if let NessaExpr::Literal(_, o) = e { Some(MatchPattern::Literal(o)) } else { None }
+            ),
+            map(|input| self.type_parser_wrapper(input, false, true), MatchPattern::Type),
+            map(identifier_parser, |n| MatchPattern::Binding(n, Box::new(MatchPattern::Wildcard)))
+        ))(input);
+    }
+
+    // Irrefutable subset of the match patterns, where at least one tuple or class is destructured
+    fn destructuring_pattern_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, MatchPattern> {
+        return verify(
+            |input| self.destructuring_subpattern_parser(input),
+            |p| matches!(p, MatchPattern::Tuple(_) | MatchPattern::Class(..))
+        )(input);
+    }
+
+    fn destructuring_subpattern_parser<'a>(&'a self, input: Span<'a>) -> PResult<'a, MatchPattern> {
+        let patterns = |input| separated_list1(
+            tuple((empty0, tag(","), empty0)),
+            |input| self.destructuring_subpattern_parser(input)
+        )(input);
+
+        return alt((
+            map_opt(identifier_parser, |n| (n == "_").then_some(MatchPattern::Wildcard)),
+            map(
+                tuple((
+                    |input| self.type_parser_wrapper(input, false, true),
+                    empty0,
+                    tag("("),
+                    empty0,
+                    patterns,
+                    empty0,
+                    opt(tuple((tag(","), empty0))),
+                    tag(")")
+                )),
+                |(t, _, _, _, p, _, _, _)| MatchPattern::Class(t, p)
+            ),
+            map(
+                tuple((
+                    tag("("),
+                    empty0,
+                    patterns,
+                    empty0,
+                    opt(tuple((tag(","), empty0))),
+                    tag(")")
+                )),
+                |(_, _, mut p, _, _, _)| if p.len() == 1 { p.pop().unwrap() } else { MatchPattern::Tuple(p) }
+            ),
+            map(identifier_parser, |n| MatchPattern::Binding(n, Box::new(MatchPattern::Wildcard)))
+        ))(input);
+    }
+
+    fn match_arm_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, MatchArm> {
+        return map(
+            tuple((
+                |input| self.match_pattern_parser(input, cache),
+                empty0,
+                opt(
+                    map(
+                        tuple((
+                            tag("if"),
+                            empty1,
+                            context("Invalid match guard", cut(|input| self.nessa_expr_parser(input, cache))),
+                            empty0
+                        )),
+                        |(_, _, g, _)| g
+                    )
+                ),
+                tag("=>"),
+                empty0,
+                context("Invalid match arm", cut(|input| self.nessa_expr_parser(input, cache)))
+            )),
+            |(p, _, g, _, _, e)| (p, g, e)
+        )(input);
+    }
+
+    fn match_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        return map(
+            self.located(
+                tuple((
+                    tag("match"),
+                    empty1,
+                    |input| self.nessa_expr_parser(input, cache),
+                    empty0,
+                    tag("{"),
+                    empty0,
+                    many_separated0(
+                        tuple((empty0, opt(tag(",")), empty0)),
+                        |input| self.match_arm_parser(input, cache)
+                    ),
+                    empty0,
+                    opt(tuple((tag(","), empty0))),
+                    context("Expected '}' at the end of match expression", cut(tag("}")))
+                ))
+            ),
+            |(l, (_, _, e, _, _, _, arms, _, _, _))| NessaExpr::Match(l, Box::new(e), arms)
+        )(input);
+    }
+
     fn lambda_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
         return map(
             self.located(
@@ -2893,8 +3905,14 @@ impl NessaContext {
     }
 
     fn nessa_expr_parser_wrapper<'a>(&'a self, input: Span<'a>, checked_precs: &mut FxHashSet<usize>, cache: &PCache<'a>) -> PResult<'a, NessaExpr> {
+        // Operations are tried outside of alt because they recurse once per precedence level and alt's frame is large
+        match self.operation_parser(input, checked_precs, cache) {
+            Err(nom::Err::Error(_)) => {},
+            res => return res
+        }
+
         return alt((
-            |input| self.operation_parser(input, checked_precs, cache),
+            |input| self.match_parser(input, cache),
             |input| self.custom_syntax_parser(input, cache),
             |input| self.do_block_parser(input, cache),
             |input| self.lambda_parser(input, cache),
@@ -2914,11 +3932,14 @@ impl NessaContext {
             map(
                 alt((
                     |input| self.variable_definition_parser(input, cache),
+                    |input| self.destructuring_definition_parser(input, cache),
                     |input| self.variable_assignment_parser(input, cache),
+                    |input| self.compound_assignment_parser(input, cache),
                     |input| self.return_parser(input, cache),
                     |input| self.while_parser(input, cache),
                     |input| self.for_parser(input, cache),
                     |input| self.if_parser(input, cache),
+                    |input| self.try_parser(input, cache),
                     |input| self.break_parser(input),
                     |input| self.continue_parser(input),
                     |input| terminated(|input| self.nessa_expr_parser(input, cache), cut(tuple((empty0, tag(";")))))(input)
@@ -2931,20 +3952,24 @@ impl NessaContext {
     fn nessa_global_parser<'a>(&'a self, input: Span<'a>, cache: &PCache<'a>) -> PResult<'a, Vec<NessaExpr>> {
         return alt((
             |input| self.custom_syntax_block_parser(input, cache),
+            |input| self.enum_definition_parser(input),
             map(
                 alt((
                     |input| self.variable_definition_parser(input, cache),
+                    |input| self.destructuring_definition_parser(input, cache),
                     |input| self.variable_assignment_parser(input, cache),
+                    |input| self.compound_assignment_parser(input, cache),
                     |input| self.return_parser(input, cache),
                     |input| self.while_parser(input, cache),
                     |input| self.for_parser(input, cache),
                     |input| self.if_parser(input, cache),
+                    |input| self.try_parser(input, cache),
                     |input| self.function_definition_parser(input, cache),
                     |input| self.operator_definition_parser(input),
                     |input| self.operation_definition_parser(input, cache),
                     |input| self.class_definition_parser(input),
                     |input| self.alias_definition_parser(input),
-                    |input| self.interface_definition_parser(input),
+                    |input| self.interface_definition_parser(input, cache),
                     |input| self.interface_implementation_parser(input),
                     |input| self.macro_parser(input), 
                     |input| terminated(|input| self.nessa_expr_parser(input, cache), cut(tuple((empty0, tag(";")))))(input)
@@ -2972,9 +3997,10 @@ impl NessaContext {
 
     pub fn nessa_function_headers_parser<'a>(&'a self, mut input: Span<'a>) -> PResult<'a, Vec<FunctionHeader>> {
         let mut ops = vec!();
+        let cache = RefCell::default();
 
         while input.len() > 0 {
-            if let Ok((i, o)) = self.function_header_parser(input) {
+            if let Ok((i, o)) = self.function_header_parser(input, &cache) {
                 input = i;
                 ops.push(o);
             
@@ -3038,7 +4064,7 @@ impl NessaContext {
         let mut ops = vec!();
 
         while input.len() > 0 {
-            if let Ok((i, o)) = self.interface_definition_parser(input) {
+            if let Ok((i, o)) = self.interface_definition_parser(input, &RefCell::default()) {
                 input = i;
                 ops.push(o);
             
@@ -3078,6 +4104,10 @@ impl NessaContext {
                 input = i;
                 ops.push(o);
             
+            } else if let Ok((i, o)) = self.enum_definition_parser(input) {
+                input = i;
+                ops.extend(o);
+            
             } else {
                 input = skip_token(input)?.0;
             }
@@ -3098,6 +4128,10 @@ impl NessaContext {
                 input = i;
                 ops.insert(o);
             
+            } else if let Ok((i, o)) = self.enum_name_definition_parser(input) {
+                input = i;
+                ops.extend(o);
+            
             } else {
                 input = skip_token(input)?.0;
             }
@@ -3141,9 +4175,8 @@ mod tests {
 
     #[test]
     fn type_parsing() {
-        let mut ctx = standard_ctx();
+        let ctx = standard_ctx();
 
-        ctx.define_type(Location::none(), vec!(), "Map".into(), vec!("Key".into(), "Value".into()), vec!(), None, vec!(), None).unwrap();
         let map_id = ctx.get_type_id("Map".into()).unwrap();
 
         let wildcard_str = "*";
@@ -3168,6 +4201,9 @@ mod tests {
         let template_str = "'T";
         let template_bounded_str = "'T [Printable, Iterable<Int>]";
 
+        let dyn_str = "dyn Printable";
+        let dyn_args_str = "&dyn Iterable<Int, @String>";
+
         let (_, wildcard) = ctx.type_parser(Span::new(wildcard_str)).unwrap();
         let (_, empty) = ctx.type_parser(Span::new(empty_str)).unwrap();
 
@@ -3223,6 +4259,12 @@ mod tests {
             InterfaceConstraint::new(PRINTABLE_ID, vec!()),
             InterfaceConstraint::new(ITERABLE_ID, vec!(INT))
         )));
+
+        let (_, dyn_type) = ctx.type_parser(Span::new(dyn_str)).unwrap();
+        let (_, dyn_args) = ctx.type_parser(Span::new(dyn_args_str)).unwrap();
+
+        assert_eq!(dyn_type, Type::Dyn(InterfaceConstraint::new(PRINTABLE_ID, vec!())));
+        assert_eq!(dyn_args, Type::Ref(Box::new(Type::Dyn(InterfaceConstraint::new(ITERABLE_ID, vec!(INT, STR.to_mut()))))));
     }
 
     #[test]
@@ -3708,9 +4750,8 @@ mod tests {
 
     #[test]
     fn function_header_parsing() {
-        let mut ctx = standard_ctx();
+        let ctx = standard_ctx();
 
-        ctx.define_type(Location::none(), vec!(), "Map".into(), vec!("Key".into(), "Value".into()), vec!(), None, vec!(), None).unwrap();
         let map_id = ctx.get_type_id("Map".into()).unwrap();
 
         let number_header_str = "fn test(a: Int) -> Int";
@@ -3718,10 +4759,10 @@ mod tests {
         let two_args_header_str = "fn test_3(arg_1: &Int, arg: String | Int) -> Int | String";
         let complex_args_header_str = "fn test_4(a: String | &Int, b: &Array<(Bool, Int)>, c: @*) -> Map<Int, String>";
 
-        let (_, number_header) = ctx.function_header_parser(Span::new(number_header_str)).unwrap();
-        let (_, ref_header) = ctx.function_header_parser(Span::new(ref_header_str)).unwrap();
-        let (_, two_args_header) = ctx.function_header_parser(Span::new(two_args_header_str)).unwrap();
-        let (_, complex_args_header) = ctx.function_header_parser(Span::new(complex_args_header_str)).unwrap();
+        let (_, number_header) = ctx.function_header_parser(Span::new(number_header_str), &RefCell::default()).unwrap();
+        let (_, ref_header) = ctx.function_header_parser(Span::new(ref_header_str), &RefCell::default()).unwrap();
+        let (_, two_args_header) = ctx.function_header_parser(Span::new(two_args_header_str), &RefCell::default()).unwrap();
+        let (_, complex_args_header) = ctx.function_header_parser(Span::new(complex_args_header_str), &RefCell::default()).unwrap();
 
         assert_eq!(number_header, ("test".into(), None, vec!(("a".into(), INT)), INT));
         assert_eq!(ref_header, ("test".into(), None, vec!(("arg".into(), Type::Ref(Box::new(INT)))), Type::MutRef(Box::new(INT))));
@@ -3771,9 +4812,8 @@ mod tests {
 
     #[test]
     fn function_definition_and_flow_control_parsing() {
-        let mut ctx = standard_ctx();
+        let ctx = standard_ctx();
 
-        ctx.define_type(Location::none(), vec!(), "Map".into(), vec!("Key".into(), "Value".into()), vec!(), None, vec!(), None).unwrap();
         let map_id = ctx.get_type_id("Map".into()).unwrap();
 
         let test_1_str = "fn inc() -> Int {
@@ -3818,6 +4858,8 @@ mod tests {
                 0,
                 vec!(),
                 vec!(),
+                vec!(),
+                false,
                 INT,
                 vec!(
                     NessaExpr::VariableDefinition(Location::none(), "res".into(), Type::InferenceMarker, Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(5))))),
@@ -3845,6 +4887,8 @@ mod tests {
                         Type::Ref(Box::new(INT))
                     )
                 ),
+                vec!(None),
+                false,
                 Type::Or(vec!(
                     INT,
                     STR
@@ -3909,6 +4953,8 @@ mod tests {
                     ("key".into(), T_0),
                     ("value".into(), T_1)
                 ),
+                vec!(None, None),
+                false,
                 Type::Template(map_id, vec!(T_0, T_1)),
                 vec!(
                     NessaExpr::VariableDefinition(Location::none(), 
@@ -4330,7 +5376,8 @@ mod tests {
                 ("rolls".into(), INT)
             ),
             None,
-            vec!()
+            vec!(),
+            false
         ));
 
         assert_eq!(sync_lists, NessaExpr::ClassDefinition(Location::none(), 
@@ -4379,7 +5426,8 @@ mod tests {
                         ))
                     ))
                 ))
-            )
+            ),
+            false
         ));
     }
 
@@ -4399,7 +5447,314 @@ mod tests {
             Some(Type::Or(vec!(
                 INT, FLOAT
             ))),
-            vec!()
+            vec!(),
+            false
+        ));
+    }
+
+    #[test]
+    fn enum_definition_parsing() {
+        let mut ctx = standard_ctx();
+
+        for n in ["Tree", "Leaf", "Node", "Empty"] {
+            ctx.define_type(Location::none(), vec!(), n.into(), vec!(), vec!(), None, vec!(), None).unwrap();
+        }
+
+        let leaf_id = ctx.get_type_id("Leaf".into()).unwrap();
+        let node_id = ctx.get_type_id("Node".into()).unwrap();
+        let empty_id = ctx.get_type_id("Empty".into()).unwrap();
+
+        let tree_str = "enum Tree<K, V> {
+            Leaf(key: 'K, value: 'V),
+            Node(left: Tree<'K, 'V>, right: Tree<'K, 'V>),
+            Empty
+        }";
+
+        let (_, tree) = ctx.enum_definition_parser(Span::new(tree_str)).unwrap();
+
+        let tree_t = Type::Template(ctx.get_type_id("Tree".into()).unwrap(), vec!(T_0, T_1));
+
+        assert_eq!(tree, vec!(
+            NessaExpr::ClassDefinition(Location::none(), vec!(), "Leaf".into(), vec!("K".into(), "V".into()), vec!(("key".into(), T_0), ("value".into(), T_1)), None, vec!(), false),
+            NessaExpr::ClassDefinition(Location::none(), vec!(), "Node".into(), vec!("K".into(), "V".into()), vec!(("left".into(), tree_t.clone()), ("right".into(), tree_t)), None, vec!(), false),
+            NessaExpr::ClassDefinition(Location::none(), vec!(), "Empty".into(), vec!(), vec!(), None, vec!(), false),
+            NessaExpr::ClassDefinition(Location::none(), 
+                vec!(),
+                "Tree".into(),
+                vec!("K".into(), "V".into()),
+                vec!(),
+                Some(Type::Or(vec!(
+                    Type::Template(leaf_id, vec!(T_0, T_1)),
+                    Type::Template(node_id, vec!(T_0, T_1)),
+                    Type::Basic(empty_id)
+                ))),
+                vec!(),
+                true
+            )
+        ));
+
+        let (_, names) = ctx.enum_name_definition_parser(Span::new(tree_str)).unwrap();
+
+        assert_eq!(names, vec!("Leaf".to_string(), "Node".into(), "Empty".into(), "Tree".into()));
+    }
+
+    #[test]
+    fn match_parsing() {
+        let ctx = standard_ctx();
+
+        let match_str = "match *x {
+            0 => 1,
+            n: Int if true => n,
+            (a, _) => a,
+            Array<Int> => 2,
+            _ => 3,
+        }";
+
+        let (_, match_expr) = ctx.match_parser(Span::new(match_str), &RefCell::default()).unwrap();
+
+        assert_eq!(match_expr, NessaExpr::Match(
+            Location::none(),
+            Box::new(NessaExpr::UnaryOperation(Location::none(), DEREF_UNOP_ID, vec!(), Box::new(NessaExpr::NameReference(Location::none(), "x".into())))),
+            vec!(
+                (MatchPattern::Literal(Object::new(Integer::from(0))), None, NessaExpr::Literal(Location::none(), Object::new(Integer::from(1)))),
+                (
+                    MatchPattern::Binding("n".into(), Box::new(MatchPattern::Type(INT))), 
+                    Some(NessaExpr::Literal(Location::none(), Object::new(true))), 
+                    NessaExpr::NameReference(Location::none(), "n".into())
+                ),
+                (
+                    MatchPattern::Tuple(vec!(MatchPattern::Binding("a".into(), Box::new(MatchPattern::Wildcard)), MatchPattern::Wildcard)), 
+                    None, 
+                    NessaExpr::NameReference(Location::none(), "a".into())
+                ),
+                (MatchPattern::Type(ARR_OF!(INT)), None, NessaExpr::Literal(Location::none(), Object::new(Integer::from(2)))),
+                (MatchPattern::Wildcard, None, NessaExpr::Literal(Location::none(), Object::new(Integer::from(3))))
+            )
+        ));
+    }
+
+    #[test]
+    fn destructuring_parsing() {
+        let ctx = standard_ctx();
+
+        let def_str = "let (a, (_, b)) = x;";
+        let for_str = "for (k, v) in x { k; }";
+
+        let (_, def) = ctx.destructuring_definition_parser(Span::new(def_str), &RefCell::default()).unwrap();
+        let (_, for_expr) = ctx.for_parser(Span::new(for_str), &RefCell::default()).unwrap();
+
+        let binding = |n: &str| MatchPattern::Binding(n.into(), Box::new(MatchPattern::Wildcard));
+
+        assert_eq!(def, NessaExpr::DestructuringDefinition(
+            Location::none(),
+            MatchPattern::Tuple(vec!(binding("a"), MatchPattern::Tuple(vec!(MatchPattern::Wildcard, binding("b"))))),
+            Box::new(NessaExpr::NameReference(Location::none(), "x".into()))
+        ));
+
+        assert_eq!(for_expr, NessaExpr::For(
+            Location::none(),
+            "__element__".into(),
+            Box::new(NessaExpr::NameReference(Location::none(), "x".into())),
+            vec!(
+                NessaExpr::DestructuringDefinition(
+                    Location::none(),
+                    MatchPattern::Tuple(vec!(binding("k"), binding("v"))),
+                    Box::new(NessaExpr::NameReference(Location::none(), "__element__".into()))
+                ),
+                NessaExpr::NameReference(Location::none(), "k".into())
+            )
+        ));
+
+        // A single name is not a destructuring
+        assert!(ctx.destructuring_definition_parser(Span::new("let a = x;"), &RefCell::default()).is_err());
+    }
+
+    #[test]
+    fn default_and_named_args_parsing() {
+        let ctx = standard_ctx();
+
+        let def_str = "fn open(file: String, write: Bool = true) { }";
+        let call_str = "open(\"f\", write: true)";
+
+        let (_, def) = ctx.function_definition_parser(Span::new(def_str), &RefCell::default()).unwrap();
+        let (_, call) = ctx.nessa_expr_parser(Span::new(call_str), &RefCell::default()).unwrap();
+
+        let true_lit = || NessaExpr::Literal(Location::none(), Object::new(true));
+
+        if let NessaExpr::FunctionDefinition(_, _, _, _, a, d, _, _, _) = def {
+            assert_eq!(a, vec!(("file".into(), STR), ("write".into(), BOOL)));
+            assert_eq!(d, vec!(None, Some(NessaExpr::Tuple(Location::none(), vec!(true_lit())))));
+
+        } else {
+            panic!("Invalid function definition");
+        }
+
+        assert_eq!(call, NessaExpr::NaryOperation(Location::none(), 
+            CALL_OP, 
+            vec!(),
+            Box::new(NessaExpr::NameReference(Location::none(), "open".into())),
+            vec!(
+                NessaExpr::Literal(Location::none(), Object::new("f".to_string())),
+                NessaExpr::NamedArgument(Location::none(), "write".into(), Box::new(true_lit()))
+            )
+        ));
+
+        // Named arguments are only allowed in calls
+        assert!(ctx.nessa_expr_parser(Span::new("(write: true)"), &RefCell::default()).is_err());
+    }
+
+    #[test]
+    fn variadic_args_parsing() {
+        let ctx = standard_ctx();
+
+        let def_str = "fn max(first: Int, rest: ...Int) -> Int { }";
+        let (_, def) = ctx.function_definition_parser(Span::new(def_str), &RefCell::default()).unwrap();
+
+        if let NessaExpr::FunctionDefinition(_, _, _, _, a, _, v, _, _) = def {
+            assert_eq!(a, vec!(("first".into(), INT), ("rest".into(), ARR_OF!(INT))));
+            assert!(v);
+
+        } else {
+            panic!("Invalid function definition");
+        }
+
+        // Only the last parameter can be variadic
+        assert!(ctx.function_definition_parser(Span::new("fn max(rest: ...Int, last: Int) { }"), &RefCell::default()).is_err());
+        assert!(ctx.function_definition_parser(Span::new("fn max(rest: ...Int = 5) { }"), &RefCell::default()).is_err());
+    }
+
+    #[test]
+    fn compound_assignment_parsing() {
+        let ctx = standard_ctx();
+
+        let (_, add) = ctx.compound_assignment_parser(Span::new("a += 5;"), &RefCell::default()).unwrap();
+        let (_, mul) = ctx.compound_assignment_parser(Span::new("a.b *= 2 + 3;"), &RefCell::default()).unwrap();
+
+        assert_eq!(add, NessaExpr::CompoundAssignment(Location::none(), 
+            ADD_ASSIGN_BINOP_ID,
+            Box::new(NessaExpr::NameReference(Location::none(), "a".into())),
+            Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(5))))
+        ));
+
+        assert_eq!(mul, NessaExpr::CompoundAssignment(Location::none(), 
+            MUL_ASSIGN_BINOP_ID,
+            Box::new(NessaExpr::BinaryOperation(Location::none(), 
+                DOT_BINOP_ID,
+                vec!(),
+                Box::new(NessaExpr::NameReference(Location::none(), "a".into())),
+                Box::new(NessaExpr::NameReference(Location::none(), "b".into()))
+            )),
+            Box::new(NessaExpr::BinaryOperation(Location::none(), 
+                ADD_BINOP_ID,
+                vec!(),
+                Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(2)))),
+                Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(3))))
+            ))
+        ));
+
+        // Compound assignments are statements
+        assert!(ctx.compound_assignment_parser(Span::new("a += 5"), &RefCell::default()).is_err());
+    }
+
+    #[test]
+    fn range_parsing() {
+        let ctx = standard_ctx();
+
+        let (_, excl) = ctx.nessa_expr_parser(Span::new("0..10"), &RefCell::default()).unwrap();
+        let (_, incl) = ctx.nessa_expr_parser(Span::new("a..=b + 1"), &RefCell::default()).unwrap();
+
+        assert_eq!(excl, NessaExpr::BinaryOperation(Location::none(),
+            RANGE_BINOP_ID,
+            vec!(),
+            Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(0)))),
+            Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(10))))
+        ));
+
+        assert_eq!(incl, NessaExpr::BinaryOperation(Location::none(),
+            RANGE_INCL_BINOP_ID,
+            vec!(),
+            Box::new(NessaExpr::NameReference(Location::none(), "a".into())),
+            Box::new(NessaExpr::BinaryOperation(Location::none(),
+                ADD_BINOP_ID,
+                vec!(),
+                Box::new(NessaExpr::NameReference(Location::none(), "b".into())),
+                Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(1))))
+            ))
+        ));
+    }
+
+    #[test]
+    fn try_parsing() {
+        let ctx = standard_ctx();
+
+        let try_str = "try {
+            5;
+
+        } catch (e: Error) {
+            e;
+        }";
+
+        let (_, try_expr) = ctx.try_parser(Span::new(try_str), &RefCell::default()).unwrap();
+
+        assert_eq!(try_expr, NessaExpr::Try(
+            Location::none(),
+            vec!(NessaExpr::Literal(Location::none(), Object::new(Integer::from(5)))),
+            "e".into(),
+            ERROR,
+            vec!(NessaExpr::NameReference(Location::none(), "e".into()))
+        ));
+
+        assert!(ctx.try_parser(Span::new("try { 5; } catch e { }"), &RefCell::default()).is_err());
+    }
+
+    #[test]
+    fn propagation_parsing() {
+        let ctx = standard_ctx();
+
+        let (_, sum) = ctx.nessa_expr_parser(Span::new("5 + a?"), &RefCell::default()).unwrap();
+        let (_, deref) = ctx.nessa_expr_parser(Span::new("*a?"), &RefCell::default()).unwrap();
+        let (rest, spaced) = ctx.nessa_expr_parser(Span::new("a ?"), &RefCell::default()).unwrap();
+
+        assert_eq!(sum, NessaExpr::BinaryOperation(
+            Location::none(), 0, vec!(),
+            Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(5)))),
+            Box::new(NessaExpr::Propagate(Location::none(), Box::new(NessaExpr::NameReference(Location::none(), "a".into()))))
+        ));
+
+        assert_eq!(deref, NessaExpr::UnaryOperation(
+            Location::none(), DEREF_UNOP_ID, vec!(),
+            Box::new(NessaExpr::Propagate(Location::none(), Box::new(NessaExpr::NameReference(Location::none(), "a".into()))))
+        ));
+
+        assert_eq!(spaced, NessaExpr::NameReference(Location::none(), "a".into()));
+        assert_eq!(*rest.fragment(), " ?");
+    }
+    #[test]
+    fn interpolation_parsing() {
+        let ctx = standard_ctx();
+
+        let (_, interp) = ctx.nessa_expr_parser(Span::new("$\"a = {a}, {{b}}\""), &RefCell::default()).unwrap();
+        let (_, single) = ctx.nessa_expr_parser(Span::new("$\"{ 5 }\""), &RefCell::default()).unwrap();
+        let (_, empty) = ctx.nessa_expr_parser(Span::new("$\"\""), &RefCell::default()).unwrap();
+
+        assert_eq!(interp, NessaExpr::BinaryOperation(
+            Location::none(), ADD_BINOP_ID, vec!(),
+            Box::new(NessaExpr::BinaryOperation(
+                Location::none(), ADD_BINOP_ID, vec!(),
+                Box::new(NessaExpr::Literal(Location::none(), Object::new("a = ".to_string()))),
+                Box::new(NessaExpr::Interpolation(Location::none(), Box::new(NessaExpr::NameReference(Location::none(), "a".into()))))
+            )),
+            Box::new(NessaExpr::Literal(Location::none(), Object::new(", {b}".to_string())))
+        ));
+
+        assert_eq!(single, NessaExpr::Interpolation(
+            Location::none(), 
+            Box::new(NessaExpr::Literal(Location::none(), Object::new(Integer::from(5))))
         ));
+
+        assert_eq!(empty, NessaExpr::Literal(Location::none(), Object::new(String::new())));
+
+        assert!(ctx.nessa_expr_parser(Span::new("$\"{a\""), &RefCell::default()).is_err());
+        assert!(ctx.nessa_expr_parser(Span::new("$\"a}\""), &RefCell::default()).is_err());
     }
 }
\ No newline at end of file
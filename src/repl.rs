@@ -0,0 +1,385 @@
+use std::io::{self, BufRead, Write};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use colored::Colorize;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::compilation::{CompiledNessaExpr, NessaError, NessaInstruction};
+use crate::config::NessaProject;
+use crate::context::{standard_ctx, NessaContext};
+use crate::debug::DebugInfo;
+use crate::execution::ExecutionInfo;
+use crate::parser::{nessa_module_imports_parser, NessaExpr, Span};
+use crate::types::Type;
+use crate::variable_map::VariableMap;
+
+/*
+                                                  ╒══════════════════╕
+    ============================================= │  IMPLEMENTATION  │ =============================================
+                                                  ╘══════════════════╛
+*/
+
+type SessionVariables = FxHashMap<String, (usize, Type)>;
+type CompiledInput = (Vec<NessaInstruction>, Vec<NessaExpr>, SessionVariables, bool);
+
+pub struct NessaRepl {
+    pub ctx: NessaContext,
+
+    // Function and operation definitions are compiled again on every input so their code is always emitted
+    definitions: Vec<NessaExpr>,
+    variables: SessionVariables,
+
+    // Code is never discarded because lambdas created by previous inputs may point to it
+    program: Vec<CompiledNessaExpr>,
+    debug_info: Vec<DebugInfo>,
+
+    project: Option<NessaProject>
+}
+
+impl Default for NessaRepl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NessaRepl {
+    pub fn new() -> Self {
+        NessaRepl {
+            ctx: standard_ctx(),
+            definitions: vec!(),
+            variables: FxHashMap::default(),
+            program: vec!(),
+            debug_info: vec!(),
+            project: None
+        }
+    }
+
+    pub fn with_project(path: &String) -> Result<Self, NessaError> {
+        let mut res = NessaRepl::new();
+        let project = NessaProject::new(path)?;
+
+        res.ctx.module_name = project.module_name.clone().into();
+        res.ctx.module_path = path.clone();
+        res.project = Some(project);
+
+        Ok(res)
+    }
+
+    fn is_replayed(expr: &NessaExpr) -> bool {
+        matches!(
+            expr,
+            NessaExpr::FunctionDefinition(..) |
+            NessaExpr::PrefixOperationDefinition(..) |
+            NessaExpr::PostfixOperationDefinition(..) |
+            NessaExpr::BinaryOperationDefinition(..) |
+            NessaExpr::NaryOperationDefinition(..)
+        )
+    }
+
+    fn parse_input(&mut self, code: &String) -> Result<Vec<NessaExpr>, NessaError> {
+        let (_, imports) = nessa_module_imports_parser(Span::new(code), self.ctx.module_name.clone())?;
+
+        if imports.is_empty() {
+            return self.ctx.parse_without_precompiling(code);
+        }
+
+        match &mut self.project {
+            Some(project) => Ok(project.parse_with_imports(&mut self.ctx, code)?.0),
+            None => Err(NessaError::module_error("Imports are only available when the REPL is started inside a project".into()))
+        }
+    }
+
+    fn compile_input(&mut self, code: &String) -> Result<CompiledInput, NessaError> {
+        let lines = self.parse_input(code)?;
+        let new_definitions = lines.iter().filter(|i| Self::is_replayed(i)).cloned().collect::<Vec<_>>();
+
+        let mut program = self.definitions.clone();
+        let first_new = program.len();
+
+        program.extend(lines);
+
+        // Variables from previous inputs are visible and their registers are reserved
+        let mut var_map = VariableMap::new();
+
+        for (n, (idx, t)) in &self.variables {
+            var_map.define_var(n.clone(), *idx, t.clone());
+        }
+
+        let used_registers = self.variables.values().map(|(i, _)| *i).collect::<FxHashSet<_>>();
+        let mut registers = (0..self.ctx.variables.len()).rev().filter(|i| !used_registers.contains(i)).collect();
+
+        self.ctx.compile_vars_and_infer_ctx(&mut program, &mut registers, &mut var_map, &vec!())?;
+
+        for expr in program.iter_mut() {
+            self.ctx.transform_term(expr)?;
+        }
+
+        let mut new_variables = self.variables.clone();
+
+        for expr in &program[first_new..] {
+            if let NessaExpr::CompiledVariableDefinition(_, idx, n, t, _) = expr {
+                new_variables.insert(n.clone(), (*idx, t.clone()));
+            }
+        }
+
+        // Print the value of the last expression if possible
+        let mut prints_value = false;
+
+        if program.len() > first_new {
+            let last = program.last_mut().unwrap();
+
+            let location = match last {
+                NessaExpr::Literal(l, ..) |
+                NessaExpr::Variable(l, ..) |
+                NessaExpr::FunctionCall(l, ..) |
+                NessaExpr::UnaryOperation(l, ..) |
+                NessaExpr::BinaryOperation(l, ..) |
+                NessaExpr::NaryOperation(l, ..) |
+                NessaExpr::Tuple(l, ..) |
+                NessaExpr::VariadicArgs(l, ..) |
+                NessaExpr::AttributeAccess(l, ..) |
+                NessaExpr::DoBlock(l, ..) |
+                NessaExpr::CompiledMatch(l, ..) => Some(l.clone()),
+
+                _ => None
+            };
+
+            if let Some(l) = location {
+                let t = self.ctx.infer_type(last)?;
+
+                let print_id = self.ctx.get_function_id("print".into()).unwrap();
+
+                // Values are only echoed if some overload of print accepts them
+                if t != Type::Empty && self.ctx.get_first_function_overload(print_id, vec!(t), None, true, &l).is_ok() {
+                    *last = NessaExpr::FunctionCall(l, print_id, vec!(), vec!(last.clone()));
+                    prints_value = true;
+                }
+            }
+        }
+
+        for expr in &program {
+            self.ctx.static_check(expr)?;
+            NessaContext::main_propagation_check(expr)?;
+        }
+
+        self.ctx.get_template_calls_body(&program)?;
+
+        // Lambda positions are only valid for a single program
+        self.ctx.lambda_code_length = 0;
+        self.ctx.lambda_code.clear();
+        self.ctx.lambda_positions.clear();
+
+        let instructions = self.ctx.compiled_form(&program)?;
+
+        Ok((instructions, new_definitions, new_variables, prints_value))
+    }
+
+    // Failed inputs leave no definitions or variables in the session, although values assigned while running are kept
+    fn discard_input(&mut self, snapshot: NessaContext) {
+        let variables = std::mem::take(&mut self.ctx.variables);
+
+        self.ctx = snapshot;
+        self.ctx.variables = variables;
+    }
+
+    pub fn eval(&mut self, code: &String) -> Result<ExecutionInfo, NessaError> {
+        let snapshot = self.ctx.clone();
+
+        let compiled = catch_unwind(AssertUnwindSafe(|| self.compile_input(code))).unwrap_or_else(|_| {
+            Err(NessaError::execution_error("Internal compiler error".into()))
+        });
+
+        let (instructions, new_definitions, new_variables, prints_value) = match compiled {
+            Ok(res) => res,
+
+            Err(err) => {
+                self.discard_input(snapshot);
+                return Err(err);
+            }
+        };
+
+        let max_var = new_variables.values().map(|(i, _)| *i as i32).max().unwrap_or(-1);
+        let start = self.program.len();
+
+        // Relocate absolute addresses after the code of previous inputs
+        for i in instructions {
+            self.program.push(match i.instruction {
+                CompiledNessaExpr::Jump(to) => CompiledNessaExpr::Jump(to + start),
+                CompiledNessaExpr::Call(to) => CompiledNessaExpr::Call(to + start),
+                CompiledNessaExpr::Lambda(pos, cap, args, ret) => CompiledNessaExpr::Lambda(pos + start, cap, args, ret),
+                instr => instr
+            });
+
+            self.debug_info.push(i.debug_info);
+        }
+
+        let res = catch_unwind(AssertUnwindSafe(|| self.ctx.execute_compiled_code_from::<false>(&self.program, &self.debug_info, start, max_var))).unwrap_or_else(|_| {
+            Err(NessaError::execution_error("Internal execution error".into()))
+        });
+
+        if prints_value {
+            println!();
+        }
+
+        // Variables defined by an input might not be initialized if it fails at runtime
+        match res {
+            Ok(info) => {
+                self.definitions.extend(new_definitions);
+                self.variables = new_variables;
+
+                Ok(info)
+            }
+
+            Err(err) => {
+                self.discard_input(snapshot);
+                Err(err)
+            }
+        }
+    }
+
+    pub fn is_complete_input(code: &str) -> bool {
+        let mut depth = 0i32;
+        let mut in_string = false;
+        let mut escaped = false;
+
+        for c in code.chars() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+
+            } else {
+                match c {
+                    '"' => in_string = true,
+                    '{' | '(' | '[' => depth += 1,
+                    '}' | ')' | ']' => depth -= 1,
+                    _ => {}
+                }
+            }
+        }
+
+        depth <= 0 && !in_string
+    }
+
+    pub fn run(&mut self) {
+        let stdin = io::stdin();
+        let mut buffer = String::new();
+
+        loop {
+            print!("{} ", if buffer.is_empty() { ">>".green() } else { "..".green() });
+            io::stdout().flush().unwrap();
+
+            let mut line = String::new();
+
+            if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                println!();
+                break;
+            }
+
+            buffer.push_str(&line);
+
+            if !Self::is_complete_input(&buffer) {
+                continue;
+            }
+
+            let mut code = buffer.trim().to_string();
+            buffer.clear();
+
+            if code.is_empty() {
+                continue;
+            }
+
+            // Allow omitting the final semicolon
+            if !code.ends_with(';') && !code.ends_with('}') {
+                code.push(';');
+            }
+
+            if let Err(err) = self.eval(&code) {
+                err.print();
+            }
+        }
+    }
+}
+
+/*
+                                                  ╒═════════╕
+    ============================================= │  TESTS  │ =============================================
+                                                  ╘═════════╛
+*/
+
+#[cfg(test)]
+mod tests {
+    use malachite::Integer;
+
+    use crate::object::Object;
+    use crate::repl::NessaRepl;
+
+    #[test]
+    fn incremental_definitions() {
+        let mut repl = NessaRepl::new();
+
+        repl.eval(&"fn double(n: Int) -> Int { return n * 2; }".into()).unwrap();
+        repl.eval(&"let a = double(5);".into()).unwrap();
+        repl.eval(&"let b = double(*a) + a;".into()).unwrap();
+        repl.eval(&"b;".into()).unwrap();
+
+        assert_eq!(repl.ctx.variables[0], Object::new(Integer::from(10)));
+        assert_eq!(repl.ctx.variables[1], Object::new(Integer::from(30)));
+    }
+
+    #[test]
+    fn incremental_classes_and_lambdas() {
+        let mut repl = NessaRepl::new();
+
+        repl.eval(&"class Point { x: Int; y: Int; }".into()).unwrap();
+        repl.eval(&"let c = 10;".into()).unwrap();
+        repl.eval(&"let f = [c](p: Point) -> Int { return p.x + p.y + c; };".into()).unwrap();
+        repl.eval(&"let s = f(Point(2, 3));".into()).unwrap();
+
+        assert_eq!(repl.ctx.variables[2], Object::new(Integer::from(15)));
+    }
+
+    #[test]
+    fn failed_inputs_are_discarded() {
+        let mut repl = NessaRepl::new();
+
+        repl.eval(&"let a = 5;".into()).unwrap();
+        assert!(repl.eval(&"let b = a + \"test\";".into()).is_err());
+        assert!(repl.eval(&"fn broken() -> Int { return true; }".into()).is_err());
+
+        // The function name is still free after the failed definition
+        repl.eval(&"fn broken(n: Int) -> Int { return n + 1; }".into()).unwrap();
+        repl.eval(&"let b = broken(*a);".into()).unwrap();
+
+        assert_eq!(repl.ctx.variables[0], Object::new(Integer::from(5)));
+        assert_eq!(repl.ctx.variables[1], Object::new(Integer::from(6)));
+    }
+
+    #[test]
+    fn runtime_failures_are_discarded() {
+        let mut repl = NessaRepl::new();
+
+        repl.eval(&"let a = arr<Int>();".into()).unwrap();
+        assert!(repl.eval(&"let b = a[5];".into()).is_err());
+
+        // The variable from the failed input was never initialized, so it is not defined
+        let err = repl.eval(&"b;".into()).err().unwrap();
+        assert!(err.message.contains("Identifier with name b is not defined"));
+
+        repl.eval(&"a.push(3);".into()).unwrap();
+        repl.eval(&"let b = a[0];".into()).unwrap();
+        repl.eval(&"b;".into()).unwrap();
+    }
+
+    #[test]
+    fn input_completion() {
+        assert!(NessaRepl::is_complete_input("let a = 5;"));
+        assert!(!NessaRepl::is_complete_input("fn test() -> Int {"));
+        assert!(NessaRepl::is_complete_input("fn test() -> Int {\n return 5;\n}"));
+        assert!(NessaRepl::is_complete_input("let a = \"{\";"));
+        assert!(!NessaRepl::is_complete_input("let a = \"test"));
+    }
+}
@@ -1,6 +1,6 @@
 use std::{cell::RefCell, fs, path::Path};
 
-use crate::{cache::NessaCache, compilation::{CompiledNessaExpr, NessaError, NessaInstruction}, config::{ImportMap, InnerDepGraph, NessaModule}, context::{standard_ctx, NessaContext, NUM_STD_BINOPS, NUM_STD_FNS, NUM_STD_INTS, NUM_STD_INT_IMPL, NUM_STD_MACROS, NUM_STD_NARYOPS, NUM_STD_TYPES, NUM_STD_UNOPS}, execution::ExecutionInfo, functions::Function, interfaces::{Interface, InterfaceImpl}, macros::NessaMacro, operations::Operator, parser::{NessaExpr, Span}, types::TypeTemplate};
+use crate::{cache::NessaCache, debug::DebugInfo, compilation::{CompiledNessaExpr, NessaError, NessaInstruction}, config::{ImportMap, InnerDepGraph, NessaModule}, context::{standard_ctx, NessaContext, NUM_STD_BINOPS, NUM_STD_FNS, NUM_STD_INTS, NUM_STD_INT_IMPL, NUM_STD_MACROS, NUM_STD_NARYOPS, NUM_STD_TYPES, NUM_STD_UNOPS}, execution::ExecutionInfo, functions::Function, interfaces::{Interface, InterfaceImpl}, macros::NessaMacro, operations::Operator, parser::{NessaExpr, Span}, types::TypeTemplate};
 
 use serde::{Serialize, Deserialize};
 use bitcode;
@@ -228,7 +228,8 @@ pub struct CompiledNessaModule {
     pub hash: String,
     type_templates: Vec<TypeTemplate>,
     interface_impls: Vec<InterfaceImpl>,
-    instructions: Vec<CompiledNessaExpr>
+    instructions: Vec<CompiledNessaExpr>,
+    debug_info: Vec<DebugInfo>
 }
 
 impl NessaContext {
@@ -245,7 +246,14 @@ impl NessaContext {
             hash, 
             type_templates: reduced_types, 
             interface_impls: self.interface_impls[*NUM_STD_INT_IMPL.lock().unwrap().borrow()..].to_vec(), 
-            instructions: instructions.iter().map(|i| i.instruction.clone()).collect()
+            instructions: instructions.iter().map(|i| i.instruction.clone()).collect(),
+
+            // Only what is needed to locate execution errors is kept
+            debug_info: instructions.iter().map(|i| DebugInfo {
+                functions: i.debug_info.functions.clone(),
+                lines: i.debug_info.lines.clone(),
+                ..Default::default()
+            }).collect()
         };
     }
 }
@@ -264,6 +272,12 @@ impl CompiledNessaModule {
         CompiledNessaModule::deserialize(&data)
     }
 
+    // Caches written with an older format are treated as missing
+    pub fn from_file_checked(path: &Path) -> Option<Self> {
+        let data = fs::read(path).ok()?;
+        bitcode::deserialize(&data).ok()
+    }
+
     pub fn write_to_file(&self, path: &Path) {
         fs::write(path, self.serialize()).expect("Unable to write serialized code to file");
     }
@@ -276,6 +290,6 @@ impl CompiledNessaModule {
 
         ctx.program_input = program_input.to_vec();
 
-        ctx.execute_compiled_code::<DEBUG>(&self.instructions, &[])
+        ctx.execute_compiled_code::<DEBUG>(&self.instructions, &self.debug_info)
     }
 }
\ No newline at end of file
@@ -3,7 +3,8 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use crate::types::Type;
 
 pub struct VariableMap {
-    vars: Vec<FxHashMap<String, (usize, Type)>>
+    vars: Vec<FxHashMap<String, (usize, Type)>>,
+    narrowed: Vec<FxHashMap<usize, Type>> // Types of variables that have been checked in each context
 }
 
 impl Default for VariableMap {
@@ -14,15 +15,17 @@ impl Default for VariableMap {
 
 impl VariableMap {
     pub fn new() -> Self {
-        VariableMap { vars: vec!(FxHashMap::default()) }
+        VariableMap { vars: vec!(FxHashMap::default()), narrowed: vec!(FxHashMap::default()) }
     }
 
     pub fn add_context(&mut self) {
         self.vars.push(FxHashMap::default());
+        self.narrowed.push(FxHashMap::default());
     } 
 
     pub fn remove_context(&mut self) {
         self.vars.pop().unwrap();
+        self.narrowed.pop().unwrap();
     }
 
     pub fn define_var(&mut self, name: String, idx: usize, t: Type) {
@@ -53,6 +56,18 @@ impl VariableMap {
         None
     }
 
+    pub fn narrow_var(&mut self, idx: usize, t: Type) {
+        self.narrowed.last_mut().unwrap().insert(idx, t);
+    }
+
+    // Same as get_var, but taking into account the narrowed types in the current context
+    pub fn get_var_narrowed(&mut self, name: &String) -> Option<(usize, Type)> {
+        let (idx, t) = self.get_var(name).cloned()?;
+        let narrowed = self.narrowed.iter().rev().find_map(|i| i.get(&idx)).cloned();
+
+        Some((idx, narrowed.unwrap_or(t)))
+    }
+
     pub fn for_each_last_ctx<T: FnMut(usize)>(&self, f: T) {
         self.vars.last().unwrap().values().map(|(i, _)| *i).for_each(f);
     }
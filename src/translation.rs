@@ -1,4 +1,4 @@
-use crate::{compilation::CompiledNessaExpr, context::NessaContext, operations::{Operator, ADD_BINOP_ID, ANDB_BINOP_ID, AND_BINOP_ID, ASSIGN_BINOP_ID, DEREF_UNOP_ID, DIV_BINOP_ID, EQ_BINOP_ID, GTEQ_BINOP_ID, GT_BINOP_ID, LTEQ_BINOP_ID, LT_BINOP_ID, MOD_BINOP_ID, MUL_BINOP_ID, NEG_UNOP_ID, NEQ_BINOP_ID, NOT_UNOP_ID, ORB_BINOP_ID, OR_BINOP_ID, SHL_BINOP_ID, SHR_BINOP_ID, SUB_BINOP_ID, XOR_BINOP_ID}, types::{Type, BOOL_ID, FLOAT_ID, INT, INT_ID, STR_ID}};
+use crate::{compilation::CompiledNessaExpr, context::NessaContext, operations::{Operator, ADD_ASSIGN_BINOP_ID, ADD_BINOP_ID, ANDB_BINOP_ID, AND_BINOP_ID, ASSIGN_BINOP_ID, DEREF_UNOP_ID, DIV_ASSIGN_BINOP_ID, DIV_BINOP_ID, EQ_BINOP_ID, GTEQ_BINOP_ID, GT_BINOP_ID, LTEQ_BINOP_ID, LT_BINOP_ID, MOD_ASSIGN_BINOP_ID, MOD_BINOP_ID, MUL_ASSIGN_BINOP_ID, MUL_BINOP_ID, NEG_UNOP_ID, NEQ_BINOP_ID, NOT_UNOP_ID, ORB_BINOP_ID, OR_BINOP_ID, SHL_BINOP_ID, SHR_BINOP_ID, SUB_ASSIGN_BINOP_ID, SUB_BINOP_ID, XOR_BINOP_ID}, types::{Type, BOOL_ID, FLOAT_ID, INT, INT_ID, STR_ID}};
 
 fn load_unop_opcodes<F: Fn(&Type) -> Option<CompiledNessaExpr>>(ctx: &mut NessaContext, id: usize, f: F) {
     if let Operator::Unary { operations, .. } = &ctx.unary_ops[id] {
@@ -21,7 +21,7 @@ fn load_binop_opcodes<F: Fn(&Type, &Type) -> Option<CompiledNessaExpr>>(ctx: &mu
         for (ov_id, op_ov) in operations.iter().enumerate() {
             if let Type::And(types) = &op_ov.args {
                 if let Some(opcode) = f(&types[0], &types[1]) {
-                    let mut offset = (types[0].is_ref() as usize) + (types[1].is_ref() as usize);
+                    let mut offset = (types[1].is_ref() as usize) + (!opcode.is_in_place() && types[0].is_ref()) as usize;
 
                     if opcode.needs_float() {
                         offset += (*types[0].deref_type() == INT) as usize;
@@ -84,6 +84,27 @@ pub fn load_optimized_binop_opcodes(ctx: &mut NessaContext) {
         });
     }
 
+    // In-place opcodes
+    let ids = [
+        ADD_ASSIGN_BINOP_ID, SUB_ASSIGN_BINOP_ID, MUL_ASSIGN_BINOP_ID, DIV_ASSIGN_BINOP_ID, MOD_ASSIGN_BINOP_ID
+    ];
+
+    let opcodes = [
+        (AddAssigni, AddAssignf, Some(AddAssignStr)), (SubAssigni, SubAssignf, None), (MulAssigni, MulAssignf, None), 
+        (DivAssigni, DivAssignf, None), (ModAssigni, ModAssignf, None)
+    ];
+
+    for (id, (i_opcode, f_opcode, s_opcode)) in ids.iter().zip(opcodes) {
+        load_binop_opcodes(ctx, *id, |a, b| {
+            return match (a.deref_type(), b.deref_type()) {
+                (Type::Basic(INT_ID), Type::Basic(INT_ID)) => Some(i_opcode.clone()),
+                (Type::Basic(FLOAT_ID), Type::Basic(FLOAT_ID)) => Some(f_opcode.clone()),
+                (Type::Basic(STR_ID), Type::Basic(STR_ID)) => s_opcode.clone(),
+                _ => None
+            }
+        });
+    }
+
     // Bool opcodes
     let ids = [
         EQ_BINOP_ID, NEQ_BINOP_ID
@@ -80,7 +80,10 @@ pub enum Type {
     Template(usize, Vec<Type>),
 
     // Function type
-    Function(Box<Type>, Box<Type>)
+    Function(Box<Type>, Box<Type>),
+
+    // Values of any type implementing an interface
+    Dyn(InterfaceConstraint)
 }
 
 impl PartialEq for Type {
@@ -101,6 +104,7 @@ impl PartialEq for Type {
             (Type::TemplateParamStr(n_a, v_a), Type::TemplateParamStr(n_b, v_b)) => n_a == n_b && v_a == v_b,
             (Type::Template(id_a, va), Type::Template(id_b, vb)) => id_a == id_b && va == vb,
             (Type::Function(fa, ta), Type::Function(fb, tb)) => fa == fb && ta == tb,
+            (Type::Dyn(ca), Type::Dyn(cb)) => ca == cb,
             
             _ => false
         }
@@ -136,6 +140,59 @@ impl Type {
         }
     }
 
+    // Failure (empty or error) and success alternatives of a union used with the ? operator
+    pub fn propagation_split(&self) -> Option<(Type, Type)> {
+        let merge = |mut v: Vec<Type>| if v.len() == 1 { v.pop().unwrap() } else { Type::Or(v) };
+
+        if let Type::Or(v) = self.deref_type() {
+            let (failure, success): (Vec<_>, Vec<_>) = v.iter().cloned().partition(|i| *i == Type::Empty || *i == ERROR);
+
+            if !failure.is_empty() && !success.is_empty() {
+                let success = match self {
+                    Type::Ref(_) => merge(success).to_ref(),
+                    Type::MutRef(_) => merge(success).to_mut(),
+                    _ => merge(success)
+                };
+
+                return Some((merge(failure), success));
+            }
+        }
+
+        None
+    }
+
+    // Type of a value of this type after checking whether it is bindable to target or not
+    pub fn narrow(&self, target: &Type, bindable: bool, ctx: &NessaContext) -> Option<Type> {
+        if self.has_templates() || target.has_templates() {
+            return None;
+        }
+
+        if bindable {
+            return (target.bindable_to(self, ctx) && !self.bindable_to(target, ctx)).then(|| target.clone());
+        }
+
+        let alternatives = match self {
+            Type::Basic(id) if ctx.type_templates[*id].is_structural() => ctx.type_templates[*id].alias.clone().unwrap(),
+            Type::Template(id, v) if ctx.type_templates[*id].is_structural() => {
+                ctx.type_templates[*id].alias.as_ref().unwrap().sub_templates(&v.iter().cloned().enumerate().collect())
+            },
+            t => t.clone()
+        };
+
+        if let Type::Or(v) = alternatives {
+            let mut remaining = v.iter().filter(|i| !i.bindable_to(target, ctx)).cloned().collect::<Vec<_>>();
+
+            return match remaining.len() {
+                0 => None,
+                1 => remaining.pop(),
+                n if n < v.len() => Some(Type::Or(remaining)),
+                _ => None
+            };
+        }
+
+        None
+    }
+
     pub fn get_name(&self, ctx: &NessaContext) -> String {
         return match self {
             Type::Empty => "()".into(),
@@ -176,7 +233,8 @@ impl Type {
             },
             Type::Template(id, v) => format!("{}<{}>", ctx.type_templates[*id].name.cyan().to_string().clone(), 
                                                        v.iter().map(|i| i.get_name(ctx)).collect::<Vec<_>>().join(", ")),
-            Type::Function(from, to) => format!("{} => {}", from.get_name(ctx), to.get_name(ctx))
+            Type::Function(from, to) => format!("{} => {}", from.get_name(ctx), to.get_name(ctx)),
+            Type::Dyn(c) => format!("{} {}", "dyn".magenta(), c.get_name(ctx))
         }
     }
 
@@ -220,7 +278,8 @@ impl Type {
             },
             Type::Template(id, v) => format!("{}&lt;{}&gt;", ctx.type_templates[*id].name.html_green().to_string().clone(), 
                                                        v.iter().map(|i| i.get_name_html(ctx)).collect::<Vec<_>>().join(", ")),
-            Type::Function(from, to) => format!("{} => {}", from.get_name_html(ctx), to.get_name_html(ctx))
+            Type::Function(from, to) => format!("{} => {}", from.get_name_html(ctx), to.get_name_html(ctx)),
+            Type::Dyn(c) => format!("{} {}", "dyn".html_magenta(), c.get_name_html(ctx))
         }
     }
 
@@ -264,7 +323,8 @@ impl Type {
             },
             Type::Template(id, v) => format!("{}<{}>", ctx.type_templates[*id].name.to_string().clone(), 
                                                        v.iter().map(|i| i.get_name_plain(ctx)).collect::<Vec<_>>().join(", ")),
-            Type::Function(from, to) => format!("{} => {}", from.get_name_plain(ctx), to.get_name_plain(ctx))
+            Type::Function(from, to) => format!("{} => {}", from.get_name_plain(ctx), to.get_name_plain(ctx)),
+            Type::Dyn(c) => format!("dyn {}", c.get_name_plain(ctx))
         }
     }
 
@@ -285,6 +345,8 @@ impl Type {
             
             Type::Function(a, b) => a.has_templates() || b.has_templates(),
 
+            Type::Dyn(c) => c.args.iter().any(Type::has_templates),
+
             _ => unimplemented!()
         };
     }
@@ -307,6 +369,8 @@ impl Type {
             Type::TemplateParamStr(_, c) |
             Type::TemplateParam(_, c) => c.iter().flat_map(|i| &i.args).any(Type::has_self),
 
+            Type::Dyn(c) => c.args.iter().any(Type::has_self),
+
             Type::Function(a, b) => a.has_self() || b.has_self(),
 
             e => unimplemented!("{e:?}")
@@ -327,7 +391,8 @@ impl Type {
 
             Type::Template(_, ts) |
             Type::Or(ts) |
-            Type::And(ts) => ts.iter().for_each(|i| i.template_dependencies(templates)),
+            Type::And(ts) |
+            Type::Dyn(InterfaceConstraint { args: ts, .. }) => ts.iter().for_each(|i| i.template_dependencies(templates)),
 
             Type::TemplateParam(id, cs) => {
                 templates.insert(*id);
@@ -360,7 +425,8 @@ impl Type {
             Type::MutRef(a) => a.type_dependencies(),
 
             Type::Or(ts) |
-            Type::And(ts) => ts.iter().flat_map(|t| t.type_dependencies()).collect(),
+            Type::And(ts) |
+            Type::Dyn(InterfaceConstraint { args: ts, .. }) => ts.iter().flat_map(|t| t.type_dependencies()).collect(),
 
             Type::Function(a, b) => {
                 let mut res = a.type_dependencies();
@@ -420,6 +486,13 @@ impl Type {
 
                 res
             }
+
+            Type::Dyn(c) => {
+                let mut res = vec!(c.id);
+                res.extend(c.args.iter().flat_map(|i| i.interface_dependencies()));
+
+                res
+            }
         };
     }
 
@@ -487,6 +560,13 @@ impl Type {
                 
                 } else {
                     for c in cs {
+                        // Constraints of a template also imply the parents of their interfaces
+                        if let Type::TemplateParam(_, b_cs) = b {
+                            if b_cs.iter().any(|i| ctx.constraint_implies(i, c)) {
+                                continue;
+                            }
+                        }
+
                         if !ctx.implements_interface(b, c, t_assignments, t_deps) {
                             return false;
                         }
@@ -516,6 +596,29 @@ impl Type {
                 return false;
             },
 
+            (Type::Dyn(ca), Type::Dyn(cb)) => {
+                if ca.id == cb.id {
+                    return Type::And(ca.args.clone()).template_bindable_to(&Type::And(cb.args.clone()), t_assignments, t_deps, ctx);
+                }
+
+                // Values of an interface can be used as values of its parents
+                for p in ctx.interface_parents(ca) {
+                    let mut t_assignments_cpy = t_assignments.clone();
+                    let mut t_deps_cpy = t_deps.clone();
+
+                    if Type::Dyn(p).template_bindable_to(other, &mut t_assignments_cpy, &mut t_deps_cpy, ctx) {
+                        *t_assignments = t_assignments_cpy;
+                        *t_deps = t_deps_cpy;
+
+                        return true;
+                    }
+                }
+
+                false
+            },
+
+            (a, Type::Dyn(c)) => !a.is_ref() && ctx.implements_interface_dynamically(a, c, t_assignments, t_deps),
+
             (Type::And(va), Type::And(vb)) => va.len() == vb.len() && va.iter().zip(vb).all(|(i, j)| i.template_bindable_to(j, t_assignments, t_deps, ctx)),
             (Type::And(va), b) => va.len() == 1 && va[0].template_bindable_to(b, t_assignments, t_deps, ctx),
             (a, Type::And(vb)) => vb.len() == 1 && a.template_bindable_to(&vb[0], t_assignments, t_deps, ctx),
@@ -547,6 +650,8 @@ impl Type {
 
             Type::Function(f, t) => f.template_cyclic_reference_check(t_id, t_deps) && t.template_cyclic_reference_check(t_id, t_deps),
 
+            Type::Dyn(c) => c.args.iter().all(|i| i.template_cyclic_reference_check(t_id, t_deps)),
+
             _ => true
         }
     }
@@ -576,6 +681,8 @@ impl Type {
                 t.compile_templates(templates)
             },
 
+            Type::Dyn(c) => c.args.iter_mut().for_each(|i| i.compile_templates(templates)),
+
             _ => { }
         }
     }
@@ -603,6 +710,8 @@ impl Type {
                 t.offset_templates(offset)
             },
 
+            Type::Dyn(c) => c.args.iter_mut().for_each(|i| i.offset_templates(offset)),
+
             _ => { }
         }
     }
@@ -627,6 +736,8 @@ impl Type {
                 f.max_template().max(t.max_template())
             },
 
+            Type::Dyn(c) => c.args.iter().map(|i| i.max_template()).max().unwrap_or(-1),
+
             _ => -1
         }
     }
@@ -664,6 +775,7 @@ impl Type {
                 }
             },
             Type::Template(id, t) => Type::Template(*id, t.iter().map(|i| i.sub_templates_rec(args, rec)).collect()),
+            Type::Dyn(c) => Type::Dyn(InterfaceConstraint::new(c.id, c.args.iter().map(|i| i.sub_templates_rec(args, rec)).collect())),
             _ => self.clone()
         };
     }
@@ -683,6 +795,7 @@ impl Type {
                 }).collect())
             }
             Type::Template(id, t) => Type::Template(*id, t.iter().map(|i| i.sub_self(sub)).collect()),
+            Type::Dyn(c) => Type::Dyn(InterfaceConstraint::new(c.id, c.args.iter().map(|i| i.sub_self(sub)).collect())),
             _ => self.clone()
         };
     }
@@ -708,6 +821,7 @@ impl Type {
                 }).collect())
             }
             Type::Template(id, t) => Type::Template(mapping(*id).unwrap(), t.iter().map(|i| i.map_basic_types(mapping)).collect()),
+            Type::Dyn(c) => Type::Dyn(InterfaceConstraint::new(c.id, c.args.iter().map(|i| i.map_basic_types(mapping)).collect())),
 
             _ => self.clone()
         };
@@ -727,6 +841,7 @@ impl Type {
                 }).collect())
             }
             Type::Template(id, t) => Type::Template(*id, t.iter().map(|i| i.map_interfaces(mapping)).collect()),
+            Type::Dyn(c) => Type::Dyn(InterfaceConstraint::new(mapping(c.id).unwrap(), c.args.iter().map(|i| i.map_interfaces(mapping)).collect())),
 
             _ => self.clone()
         };
@@ -747,12 +862,21 @@ pub const BOOL_ID: usize = 3;
 pub const ARR_ID: usize = 4;
 pub const ARR_IT_ID: usize = 5;
 pub const FILE_ID: usize = 6;
+pub const ERROR_ID: usize = 7;
+pub const MAP_ID: usize = 8;
+pub const SET_ID: usize = 9;
+pub const HASH_IT_ID: usize = 10;
+pub const REGEX_ID: usize = 11;
+pub const RANGE_ID: usize = 12;
 
 pub const INT: Type = Type::Basic(INT_ID);
 pub const FLOAT: Type = Type::Basic(FLOAT_ID);
 pub const STR: Type = Type::Basic(STR_ID);
 pub const BOOL: Type = Type::Basic(BOOL_ID);
 pub const FILE: Type = Type::Basic(FILE_ID);
+pub const ERROR: Type = Type::Basic(ERROR_ID);
+pub const REGEX: Type = Type::Basic(REGEX_ID);
+pub const RANGE: Type = Type::Basic(RANGE_ID);
 
 #[macro_export]
 macro_rules! ARR_OF { ($t: expr) => { Type::Template($crate::types::ARR_ID, vec!($t)) }; }
@@ -760,15 +884,25 @@ macro_rules! ARR_OF { ($t: expr) => { Type::Template($crate::types::ARR_ID, vec!
 #[macro_export]
 macro_rules! ARR_IT_OF { ($t: expr) => { Type::Template($crate::types::ARR_IT_ID, vec!($t)) }; }
 
+#[macro_export]
+macro_rules! MAP_OF { ($k: expr, $v: expr) => { Type::Template($crate::types::MAP_ID, vec!($k, $v)) }; }
+
+#[macro_export]
+macro_rules! SET_OF { ($t: expr) => { Type::Template($crate::types::SET_ID, vec!($t)) }; }
+
+#[macro_export]
+macro_rules! HASH_IT_OF { ($t: expr) => { Type::Template($crate::types::HASH_IT_ID, vec!($t)) }; }
+
 pub const T_0: Type = Type::TemplateParam(0, vec!());
 pub const T_1: Type = Type::TemplateParam(1, vec!());
 pub const T_2: Type = Type::TemplateParam(2, vec!());
+pub const T_3: Type = Type::TemplateParam(3, vec!());
 
 // Standard context
 pub fn standard_types(ctx: &mut NessaContext) {
     ctx.define_type(Location::none(), vec!(), "Int".into(), vec!(), vec!(), None, vec!(), Some(|_, _, s| s.parse::<Integer>().map(Object::new).map_err(|_| "Invalid Int format".into()))).unwrap();
     ctx.define_type(Location::none(), vec!(), "Float".into(), vec!(), vec!(), None, vec!(), Some(|_, _, s| s.parse::<f64>().map(Object::new).map_err(|_| "Invalid float format".to_string()))).unwrap();
-    ctx.define_type(Location::none(), vec!(), "String".into(), vec!(), vec!(), None, vec!(), None).unwrap();
+    ctx.define_type(Location::none(), vec!(), "String".into(), vec!(), vec!(), None, vec!(), Some(|_, _, s| Ok(Object::new(s.clone())))).unwrap();
 
     ctx.define_type(Location::none(), vec!(), "Bool".into(), vec!(), vec!(), None, vec!(), Some(|_, _, s| 
         if s == "true" || s == "false" {
@@ -783,6 +917,19 @@ pub fn standard_types(ctx: &mut NessaContext) {
     ctx.define_type(Location::none(), vec!(), "ArrayIterator".into(), vec!("Inner".into()), vec!(), None, vec!(), None).unwrap();
 
     ctx.define_type(Location::none(), vec!(), "File".into(), vec!(), vec!(), None, vec!(), None).unwrap();
+
+    // Runtime errors are caught as instances of this class
+    ctx.define_type(Location::none(), vec!(), "Error".into(), vec!(), vec!(("message".into(), STR)), None, vec!(), None).unwrap();
+
+    // Hash tables and their iterator, which yields keys for maps and elements for sets
+    ctx.define_type(Location::none(), vec!(), "Map".into(), vec!("Key".into(), "Value".into()), vec!(), None, vec!(), None).unwrap();
+    ctx.define_type(Location::none(), vec!(), "Set".into(), vec!("Inner".into()), vec!(), None, vec!(), None).unwrap();
+    ctx.define_type(Location::none(), vec!(), "HashIterator".into(), vec!("Inner".into()), vec!(), None, vec!(), None).unwrap();
+
+    ctx.define_type(Location::none(), vec!(), "Regex".into(), vec!(), vec!(), None, vec!(), None).unwrap();
+
+    // Integer ranges are their own iterators
+    ctx.define_type(Location::none(), vec!(), "Range".into(), vec!(), vec!(), None, vec!(), None).unwrap();
 }
 
 /*
@@ -1136,4 +1283,63 @@ mod tests {
         assert!(!tuple_6.bindable_to(&list, &ctx));
         assert!(!tuple_7.bindable_to(&list, &ctx));
     }
+
+    #[test]
+    fn inherited_constraint_binding() {
+        let mut ctx = standard_ctx();
+        let named_id = ctx.interfaces.len();
+        let greeter_id = named_id + 1;
+        let named = InterfaceConstraint::new(named_id, vec!());
+        let greeter = InterfaceConstraint::new(greeter_id, vec!());
+
+        ctx.define_interface(Location::none(), vec!(), "Named".into(), vec!(), vec!(), vec!(), vec!(), vec!(), vec!()).unwrap();
+        ctx.define_interface(Location::none(), vec!(), "Greeter".into(), vec!(), vec!(named.clone()), vec!(), vec!(), vec!(), vec!()).unwrap();
+
+        let with_named = Type::TemplateParam(0, vec!(named.clone()));
+        let with_greeter = Type::TemplateParam(0, vec!(greeter.clone()));
+
+        // A child constraint implies its parents
+        assert!(with_named.bindable_to(&Type::TemplateParam(1, vec!(greeter.clone())), &ctx));
+        assert!(!with_greeter.bindable_to(&Type::TemplateParam(1, vec!(named)), &ctx));
+
+        // Implementing a child is not enough without its parents
+        ctx.define_interface_impl("Greeter".into(), vec!(), INT, vec!()).unwrap();
+        ctx.define_interface_impl("Greeter".into(), vec!(), FLOAT, vec!()).unwrap();
+        ctx.define_interface_impl("Named".into(), vec!(), INT, vec!()).unwrap();
+
+        assert!(INT.bindable_to(&with_greeter, &ctx));
+        assert!(!FLOAT.bindable_to(&with_greeter, &ctx));
+        assert!(INT.bindable_to(&with_named, &ctx));
+    }
+
+    #[test]
+    fn dyn_type_binding() {
+        let mut ctx = standard_ctx();
+        let named_id = ctx.interfaces.len();
+        let greeter_id = named_id + 1;
+        let named = Type::Dyn(InterfaceConstraint::new(named_id, vec!()));
+        let greeter = Type::Dyn(InterfaceConstraint::new(greeter_id, vec!()));
+
+        ctx.define_interface(Location::none(), vec!(), "Named".into(), vec!(), vec!(), vec!(), vec!(), vec!(), vec!()).unwrap();
+        ctx.define_interface(Location::none(), vec!(), "Greeter".into(), vec!(), vec!(InterfaceConstraint::new(named_id, vec!())), vec!(), vec!(), vec!(), vec!()).unwrap();
+
+        ctx.define_interface_impl("Named".into(), vec!(), INT, vec!()).unwrap();
+        ctx.define_interface_impl("Greeter".into(), vec!(), INT, vec!()).unwrap();
+        ctx.define_interface_impl("Named".into(), vec!(), FLOAT, vec!()).unwrap();
+
+        // Values of implementing types
+        assert!(INT.bindable_to(&greeter, &ctx));
+        assert!(FLOAT.bindable_to(&named, &ctx));
+        assert!(!FLOAT.bindable_to(&greeter, &ctx));
+        assert!(!INT.to_ref().bindable_to(&named, &ctx));
+        assert!(INT.or(FLOAT).bindable_to(&named, &ctx));
+
+        // Values of child interfaces, but not the other way around
+        assert!(greeter.bindable_to(&named, &ctx));
+        assert!(!named.bindable_to(&greeter, &ctx));
+        assert!(!named.bindable_to(&INT, &ctx));
+
+        // Dyn values implement their interface
+        assert!(greeter.bindable_to(&Type::TemplateParam(0, vec!(InterfaceConstraint::new(named_id, vec!()))), &ctx));
+    }
 } 
\ No newline at end of file